@@ -0,0 +1,392 @@
+use crate::algebra::linalg::{inner, norm, LinearOperator};
+use ndarray::prelude::{Array1, Array2};
+use num::complex::Complex;
+
+// orthonormal krylov basis v_0, v_1, .. of a hermitian operator with the tridiagonal
+// projection T (alphas on the diagonal, betas next to it), every new vector being
+// reorthogonalized against the basis and against the `deflated` vectors
+struct Krylov {
+    basis: Vec<Array1<Complex<f64>>>,
+    alphas: Vec<f64>,
+    betas: Vec<f64>,
+    // norm of the component that would extend the basis, zero on an invariant subspace
+    residual: f64,
+}
+
+fn orthogonalize(vector: &mut Array1<Complex<f64>>, against: &[&Array1<Complex<f64>>]) {
+    // twice is enough to keep orthogonality to machine precision
+    for _ in 0..2 {
+        for v in against.iter() {
+            let overlap = inner(v, vector);
+            vector.zip_mut_with(v, |x, y| *x -= overlap * y);
+        }
+    }
+}
+
+// with a tolerance the basis stops growing once the lowest ritz pair has converged,
+// which is checked every few steps
+fn krylov(
+    op: &dyn LinearOperator,
+    start: &Array1<Complex<f64>>,
+    max_dim: usize,
+    deflated: &[Array1<Complex<f64>>],
+    tolerance: Option<f64>,
+) -> Krylov {
+    let mut v = start.clone();
+    orthogonalize(&mut v, &deflated.iter().collect::<Vec<_>>());
+    let length = norm(&v);
+    assert!(
+        length > 1e-12,
+        "the start vector lies in the deflated subspace"
+    );
+    v.mapv_inplace(|x| x / length);
+
+    let mut result = Krylov {
+        basis: vec![v],
+        alphas: vec![],
+        betas: vec![],
+        residual: 0.,
+    };
+    loop {
+        let current = result.basis.last().unwrap();
+        let mut w = op.matvec(current);
+        result.alphas.push(inner(current, &w).re);
+        let against = result
+            .basis
+            .iter()
+            .chain(deflated.iter())
+            .collect::<Vec<_>>();
+        orthogonalize(&mut w, &against);
+        let beta = norm(&w);
+        result.residual = beta;
+        if beta < 1e-12 || result.basis.len() >= max_dim {
+            return result;
+        }
+        if let Some(tolerance) = tolerance {
+            if result.basis.len().is_multiple_of(10) && result.ritz_residual() < tolerance {
+                return result;
+            }
+        }
+        result.betas.push(beta);
+        result.basis.push(w.mapv(|x| x / beta));
+    }
+}
+
+impl Krylov {
+    // ||H V y - theta V y|| = beta_m |y_m| for the lowest ritz pair
+    fn ritz_residual(&self) -> f64 {
+        let (_, vectors) = self.eigh();
+        self.residual * vectors[[self.alphas.len() - 1, 0]].abs()
+    }
+
+    // eigenvalues in ascending order and eigenvectors (as columns) of the real symmetric
+    // tridiagonal projection, by implicit QL iterations with wilkinson shifts
+    fn eigh(&self) -> (Vec<f64>, Array2<f64>) {
+        let n = self.alphas.len();
+        let mut d = self.alphas.clone();
+        let mut e = self.betas.iter().take(n - 1).cloned().collect::<Vec<_>>();
+        e.push(0.);
+        let mut z = Array2::<f64>::eye(n);
+        for l in 0..n {
+            let mut iterations = 0;
+            loop {
+                let mut m = l;
+                while m + 1 < n && e[m].abs() > f64::EPSILON * (d[m].abs() + d[m + 1].abs()) {
+                    m += 1;
+                }
+                if m == l {
+                    break;
+                }
+                iterations += 1;
+                assert!(iterations < 100, "tridiagonal QL did not converge");
+                let mut g = (d[l + 1] - d[l]) / (2. * e[l]);
+                let r = g.hypot(1.);
+                g = d[m] - d[l] + e[l] / (g + r.copysign(g));
+                let (mut s, mut c, mut p) = (1., 1., 0.);
+                let mut deflated = false;
+                for i in (l..m).rev() {
+                    let f = s * e[i];
+                    let b = c * e[i];
+                    let r = f.hypot(g);
+                    e[i + 1] = r;
+                    if r == 0. {
+                        d[i + 1] -= p;
+                        e[m] = 0.;
+                        deflated = true;
+                        break;
+                    }
+                    s = f / r;
+                    c = g / r;
+                    g = d[i + 1] - p;
+                    let r = (d[i] - g) * s + 2. * c * b;
+                    p = s * r;
+                    d[i + 1] = g + p;
+                    g = c * r - b;
+                    for k in 0..n {
+                        let f = z[[k, i + 1]];
+                        z[[k, i + 1]] = s * z[[k, i]] + c * f;
+                        z[[k, i]] = c * z[[k, i]] - s * f;
+                    }
+                }
+                if !deflated {
+                    d[l] -= p;
+                    e[l] = g;
+                    e[m] = 0.;
+                }
+            }
+        }
+        let mut order = (0..n).collect::<Vec<_>>();
+        order.sort_by(|&i, &j| d[i].total_cmp(&d[j]));
+        let mut vectors = Array2::<f64>::zeros((n, n));
+        for (column, &i) in order.iter().enumerate() {
+            vectors.column_mut(column).assign(&z.column(i));
+        }
+        (order.iter().map(|&i| d[i]).collect(), vectors)
+    }
+
+    // sum_k coefficients[k] v_k
+    fn combine(&self, coefficients: &[Complex<f64>]) -> Array1<Complex<f64>> {
+        let mut vector = Array1::<Complex<f64>>::zeros(self.basis[0].len());
+        for (c, v) in coefficients.iter().zip(self.basis.iter()) {
+            vector.zip_mut_with(v, |x, y| *x += c * y);
+        }
+        vector
+    }
+}
+
+// a fixed start vector with overlap on every basis state, so that no symmetry sector
+// is missed and the results are reproducible
+fn default_start(dim: usize) -> Array1<Complex<f64>> {
+    Array1::from_iter(
+        (0..dim).map(|k| Complex::new(1. + 0.5 * (k as f64 * 1.3).sin(), 0.3 * (k as f64).cos())),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lanczos {
+    pub max_krylov_dim: usize,
+    pub max_restarts: usize,
+    // on the residual ||H v - lambda v|| of each eigenpair
+    pub tolerance: f64,
+}
+
+impl Default for Lanczos {
+    fn default() -> Lanczos {
+        Lanczos {
+            max_krylov_dim: 100,
+            max_restarts: 50,
+            tolerance: 1e-9,
+        }
+    }
+}
+
+impl Lanczos {
+    // the k lowest eigenpairs of a hermitian operator in ascending order. they are found
+    // one at a time, each search being deflated against the eigenvectors already found,
+    // so degenerate eigenvalues are returned with their multiplicity
+    pub fn lowest_eigenpairs(
+        &self,
+        op: &dyn LinearOperator,
+        k: usize,
+    ) -> Vec<(f64, Array1<Complex<f64>>)> {
+        let dim = op.dim();
+        assert!(k <= dim);
+        let mut pairs: Vec<(f64, Array1<Complex<f64>>)> = vec![];
+        while pairs.len() < k {
+            let deflated = pairs.iter().map(|(_, v)| v.clone()).collect::<Vec<_>>();
+            let max_dim = self.max_krylov_dim.min(dim - deflated.len());
+            let mut value = 0.;
+            let mut vector = default_start(dim);
+            for _ in 0..=self.max_restarts {
+                let krylov = krylov(op, &vector, max_dim, &deflated, Some(self.tolerance));
+                let (values, vectors) = krylov.eigh();
+                value = values[0];
+                let coefficients = vectors
+                    .column(0)
+                    .iter()
+                    .map(|&x| Complex::new(x, 0.))
+                    .collect::<Vec<_>>();
+                vector = krylov.combine(&coefficients);
+                let length = norm(&vector);
+                vector.mapv_inplace(|x| x / length);
+                // ||H V y - theta V y|| = beta_m |y_m|
+                let residual = krylov.residual * coefficients.last().unwrap().norm();
+                if residual < self.tolerance {
+                    break;
+                }
+            }
+            pairs.push((value, vector));
+        }
+        pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+        pairs
+    }
+
+    pub fn ground_state(&self, op: &dyn LinearOperator) -> (f64, Array1<Complex<f64>>) {
+        self.lowest_eigenpairs(op, 1).remove(0)
+    }
+}
+
+// exp(-iHt)|psi> by exponentiating the projection of H onto krylov subspaces of
+// dimension `krylov_dim`; the time is split into substeps whose estimated error
+// beta_m |[exp(-iT dt) e_0]_m| stays below `tolerance`
+pub fn krylov_evolution(
+    op: &dyn LinearOperator,
+    state: &Array1<Complex<f64>>,
+    time: f64,
+    krylov_dim: usize,
+    tolerance: f64,
+) -> Array1<Complex<f64>> {
+    let mut psi = state.clone();
+    let length = norm(state);
+    let mut elapsed = 0.;
+    let mut dt = time;
+    while (time - elapsed).abs() > 1e-15 * time.abs().max(1.) {
+        dt = if time > 0. {
+            dt.min(time - elapsed)
+        } else {
+            dt.max(time - elapsed)
+        };
+        let krylov = krylov(op, &psi, krylov_dim, &[], None);
+        let (values, vectors) = krylov.eigh();
+        loop {
+            // exp(-iT dt) e_0 = V exp(-i Lambda dt) V^dagger e_0
+            let m = values.len();
+            let coefficients = (0..m)
+                .map(|row| {
+                    (0..m)
+                        .map(|col| {
+                            Complex::new(0., -values[col] * dt).exp()
+                                * (vectors[[row, col]] * vectors[[0, col]])
+                        })
+                        .sum::<Complex<f64>>()
+                })
+                .collect::<Vec<_>>();
+            let error = krylov.residual * coefficients[m - 1].norm();
+            if error < tolerance || krylov.residual < 1e-12 {
+                psi = krylov.combine(&coefficients);
+                let scale = length / norm(&psi);
+                psi.mapv_inplace(|x| x * scale);
+                elapsed += dt;
+                break;
+            }
+            dt /= 2.;
+        }
+    }
+    psi
+}
+
+#[cfg(test)]
+use crate::algebra::linalg::{eigh, expm};
+#[cfg(test)]
+use crate::operator::pauli::SparsePauliOp;
+
+#[test]
+fn lanczos_test() {
+    // heisenberg ring of 6 spins, its ground state is a singlet and the first excited
+    // level a threefold degenerate triplet
+    let mut labels = vec![];
+    for k in 0..6 {
+        let l = (k + 1) % 6;
+        labels.push(format!("X{} X{} + Y{} Y{} + Z{} Z{}", k, l, k, l, k, l));
+    }
+    let hamiltonian: SparsePauliOp = labels.join(" + ").parse().unwrap();
+    let sparse = hamiltonian.to_sparse();
+    let (exact, _) = eigh(&hamiltonian.to_matrix());
+
+    // a small krylov space forces restarts
+    let lanczos = Lanczos {
+        max_krylov_dim: 20,
+        ..Lanczos::default()
+    };
+    let pairs = lanczos.lowest_eigenpairs(&sparse, 4);
+    for (k, (value, vector)) in pairs.iter().enumerate() {
+        assert!((value - exact[k]).abs() < 1e-8);
+        let residual = sparse.matvec(vector) - vector.mapv(|x| x * value);
+        assert!(norm(&residual) < 1e-6);
+    }
+    assert!((pairs[1].0 - pairs[3].0).abs() < 1e-8);
+    assert!(inner(&pairs[1].1, &pairs[2].1).norm() < 1e-8);
+
+    let (value, _) = lanczos.ground_state(&hamiltonian);
+    assert!((value - exact[0]).abs() < 1e-8);
+}
+
+#[test]
+fn early_stop_test() {
+    // on a heisenberg ring of 6 spins the lowest ritz pair converges well before the
+    // basis fills the 64 dimensional space, and the basis stops growing there
+    let mut labels = vec![];
+    for k in 0..6 {
+        let l = (k + 1) % 6;
+        labels.push(format!("X{} X{} + Y{} Y{} + Z{} Z{}", k, l, k, l, k, l));
+    }
+    let hamiltonian: SparsePauliOp = labels.join(" + ").parse().unwrap();
+    let sparse = hamiltonian.to_sparse();
+    let (exact, _) = eigh(&hamiltonian.to_matrix());
+    let tolerance = 1e-6;
+    let start = default_start(64);
+    let full = krylov(&sparse, &start, 64, &[], None);
+    let stopped = krylov(&sparse, &start, 64, &[], Some(tolerance));
+    assert!(stopped.basis.len() < full.basis.len());
+    assert!(stopped.basis.len().is_multiple_of(10));
+    assert!(stopped.ritz_residual() < tolerance);
+
+    // the residual bounds the distance of the ritz value to the spectrum
+    let (values, vectors) = stopped.eigh();
+    assert!((values[0] - exact[0]).abs() < tolerance);
+    let coefficients = vectors
+        .column(0)
+        .iter()
+        .map(|&x| Complex::new(x, 0.))
+        .collect::<Vec<_>>();
+    let vector = stopped.combine(&coefficients);
+    let residual = sparse.matvec(&vector) - vector.mapv(|x| x * values[0]);
+    assert!((norm(&residual) - stopped.ritz_residual()).abs() < 1e-8);
+}
+
+#[test]
+fn tridiagonal_eigh_test() {
+    let krylov = Krylov {
+        basis: vec![],
+        alphas: vec![1., -2., 0.5, 3., 0., -1.],
+        betas: vec![0.7, 1.3, 1e-9, 2., 0.4],
+        residual: 0.,
+    };
+    let n = krylov.alphas.len();
+    let mut t = Array2::<Complex<f64>>::zeros((n, n));
+    for k in 0..n {
+        t[[k, k]] = Complex::new(krylov.alphas[k], 0.);
+    }
+    for k in 0..n - 1 {
+        t[[k, k + 1]] = Complex::new(krylov.betas[k], 0.);
+        t[[k + 1, k]] = Complex::new(krylov.betas[k], 0.);
+    }
+    let (exact, _) = eigh(&t);
+    let (values, vectors) = krylov.eigh();
+    for k in 0..n {
+        assert!((values[k] - exact[k]).abs() < 1e-10);
+        let vector = vectors.column(k).mapv(|x| Complex::new(x, 0.));
+        let residual = t.dot(&vector) - vector.mapv(|x| x * values[k]);
+        assert!(norm(&residual) < 1e-10);
+    }
+}
+
+#[test]
+fn krylov_evolution_test() {
+    let hamiltonian: SparsePauliOp = "X0 X1 + 0.5*Y1 Y2 + 0.3*Z0 - 0.8*Z2 + 0.4*X2 + Z1 Z3 + X3"
+        .parse()
+        .unwrap();
+    let sparse = hamiltonian.to_sparse();
+    let state = default_start(16);
+    let state = state.mapv(|x| x / norm(&state));
+    for time in [0.3, 2.5, -1.7] {
+        let unitary = expm(
+            &hamiltonian
+                .to_matrix()
+                .mapv(|x| x * Complex::new(0., -time)),
+        );
+        let expected = unitary.dot(&state);
+        let evolved = krylov_evolution(&sparse, &state, time, 6, 1e-10);
+        assert!(norm(&(evolved - expected)) < 1e-8);
+    }
+}
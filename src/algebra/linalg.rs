@@ -0,0 +1,164 @@
+use ndarray::prelude::{Array1, Array2};
+use num::complex::Complex;
+
+// maximum absolute column sum
+pub fn norm_1(matrix: &Array2<Complex<f64>>) -> f64 {
+    matrix
+        .columns()
+        .into_iter()
+        .map(|column| column.iter().map(|x| x.norm()).sum::<f64>())
+        .fold(0., f64::max)
+}
+
+// scaling and squaring: the matrix is divided by 2^s until its norm is below 1/2,
+// exponentiated by its taylor series and squared s times
+pub fn expm(matrix: &Array2<Complex<f64>>) -> Array2<Complex<f64>> {
+    assert_eq!(matrix.nrows(), matrix.ncols());
+    let norm = norm_1(matrix);
+    let squarings = if norm > 0.5 {
+        (norm / 0.5).log2().ceil() as i32
+    } else {
+        0
+    };
+    let scaled = matrix.mapv(|x| x / 2f64.powi(squarings));
+
+    let mut result = Array2::<Complex<f64>>::eye(matrix.nrows());
+    let mut term = result.clone();
+    for k in 1..=30 {
+        term = term.dot(&scaled).mapv(|x| x / k as f64);
+        result += &term;
+        if norm_1(&term) < 1e-18 {
+            break;
+        }
+    }
+    for _ in 0..squarings {
+        result = result.dot(&result);
+    }
+    result
+}
+
+pub trait LinearOperator {
+    fn dim(&self) -> usize;
+
+    fn matvec(&self, vector: &Array1<Complex<f64>>) -> Array1<Complex<f64>>;
+}
+
+impl LinearOperator for Array2<Complex<f64>> {
+    fn dim(&self) -> usize {
+        self.nrows()
+    }
+
+    fn matvec(&self, vector: &Array1<Complex<f64>>) -> Array1<Complex<f64>> {
+        self.dot(vector)
+    }
+}
+
+pub fn inner(a: &Array1<Complex<f64>>, b: &Array1<Complex<f64>>) -> Complex<f64> {
+    a.iter().zip(b.iter()).map(|(x, y)| x.conj() * y).sum()
+}
+
+pub fn norm(vector: &Array1<Complex<f64>>) -> f64 {
+    vector.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt()
+}
+
+// eigenvalues in ascending order and the matching eigenvectors as columns, by cyclic
+// jacobi rotations: each off-diagonal a_pq = |a_pq| e^{i phi} is first made real by
+// the phase diag(1, e^{-i phi}) on q and then removed by a real rotation
+pub fn eigh(matrix: &Array2<Complex<f64>>) -> (Vec<f64>, Array2<Complex<f64>>) {
+    let n = matrix.nrows();
+    assert_eq!(n, matrix.ncols());
+    let mut a = matrix.clone();
+    let mut v = Array2::<Complex<f64>>::eye(n);
+    let scale = norm_1(matrix).max(f64::MIN_POSITIVE);
+    for _ in 0..100 {
+        let off = (0..n)
+            .flat_map(|p| (0..n).filter(move |&q| q != p).map(move |q| (p, q)))
+            .map(|(p, q)| a[[p, q]].norm_sqr())
+            .sum::<f64>()
+            .sqrt();
+        if off < 1e-15 * scale {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = a[[p, q]];
+                if apq.norm() < 1e-300 {
+                    continue;
+                }
+                let phase = apq / apq.norm();
+                let theta = 0.5 * (2. * apq.norm()).atan2(a[[q, q]].re - a[[p, p]].re);
+                let (c, s) = (theta.cos(), theta.sin());
+                // U = D R with D = diag(1, conj(phase)) and R = [[c, s], [-s, c]] on (p, q)
+                let up = [Complex::new(c, 0.), -phase.conj() * s];
+                let uq = [Complex::new(s, 0.), phase.conj() * c];
+                for k in 0..n {
+                    let (x, y) = (a[[k, p]], a[[k, q]]);
+                    a[[k, p]] = x * up[0] + y * up[1];
+                    a[[k, q]] = x * uq[0] + y * uq[1];
+                    let (x, y) = (v[[k, p]], v[[k, q]]);
+                    v[[k, p]] = x * up[0] + y * up[1];
+                    v[[k, q]] = x * uq[0] + y * uq[1];
+                }
+                for k in 0..n {
+                    let (x, y) = (a[[p, k]], a[[q, k]]);
+                    a[[p, k]] = up[0].conj() * x + up[1].conj() * y;
+                    a[[q, k]] = uq[0].conj() * x + uq[1].conj() * y;
+                }
+            }
+        }
+    }
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.sort_by(|&i, &j| a[[i, i]].re.total_cmp(&a[[j, j]].re));
+    let values = order.iter().map(|&i| a[[i, i]].re).collect();
+    let mut vectors = Array2::<Complex<f64>>::zeros((n, n));
+    for (column, &i) in order.iter().enumerate() {
+        vectors.column_mut(column).assign(&v.column(i));
+    }
+    (values, vectors)
+}
+
+#[cfg(test)]
+use crate::operator::pauli::SparsePauliOp;
+#[cfg(test)]
+use ndarray::array;
+
+#[test]
+fn expm_test() {
+    // exp(-i theta P) = cos(theta) - i sin(theta) P for a pauli string P
+    let pauli: SparsePauliOp = "X0 Y1".parse().unwrap();
+    let matrix = pauli.to_matrix();
+    for theta in [0.1, 1.3, 7.9] {
+        let exponential = expm(&matrix.mapv(|x| x * Complex::new(0., -theta)));
+        let expected = Array2::<Complex<f64>>::eye(4).mapv(|x| x * theta.cos())
+            - matrix.mapv(|x| x * Complex::new(0., theta.sin()));
+        assert!((exponential - expected).iter().all(|x| x.norm() < 1e-12));
+    }
+
+    // exp(A) exp(-A) = 1 for a non-normal matrix
+    let a = array![
+        [Complex::new(0.3, 1.), Complex::new(2., 0.)],
+        [Complex::new(0., 0.), Complex::new(-1., 0.5)]
+    ];
+    let product = expm(&a).dot(&expm(&a.mapv(|x| -x)));
+    assert!((product - Array2::<Complex<f64>>::eye(2))
+        .iter()
+        .all(|x| x.norm() < 1e-12));
+}
+
+#[test]
+fn eigh_test() {
+    let op: SparsePauliOp = "0.5*X0 Y1 + Z0 - 0.3*Y0 + 0.7*Z0 Z1 + 0.2*X1 + Y0 X1"
+        .parse()
+        .unwrap();
+    let matrix = op.to_matrix();
+    let (values, vectors) = eigh(&matrix);
+    for (k, value) in values.iter().enumerate() {
+        let vector = vectors.column(k).to_owned();
+        let residual = matrix.matvec(&vector) - vector.mapv(|x| x * value);
+        assert!(norm(&residual) < 1e-10);
+        assert!((norm(&vector) - 1.).abs() < 1e-10);
+    }
+    assert!(values.windows(2).all(|w| w[0] <= w[1]));
+    let trace = (0..4).map(|k| matrix[[k, k]].re).sum::<f64>();
+    assert!((values.iter().sum::<f64>() - trace).abs() < 1e-10);
+}
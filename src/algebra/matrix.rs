@@ -27,7 +27,7 @@ pub fn mask_vec(qubits: &[&usize]) -> Vec<usize> {
     for i in 1..qubits.len() {
         masked_qubits_elements[i] = (0xFFFF_FFFF_FFFF_FFFFusize
             << (qubits[qubits.len() - i - 1] + 1))
-            | (!(0xFFFF_FFFF_FFFF_FFFFusize << (qubits[qubits.len() - i])));
+            & (!(0xFFFF_FFFF_FFFF_FFFFusize << (qubits[qubits.len() - i])));
     }
 
     masked_qubits_elements[qubits.len()] = !(0xFFFF_FFFF_FFFF_FFFFusize << qubits[0]);
@@ -35,10 +35,17 @@ pub fn mask_vec(qubits: &[&usize]) -> Vec<usize> {
     masked_qubits_elements
 }
 
+// the index-th basis state in which the qubits are all 0
+pub fn base_index(index: usize, mask: &[usize], dim: usize) -> usize {
+    let mut base = 0;
+    for (s, m) in mask.iter().enumerate() {
+        base |= (index << (dim - s)) & m;
+    }
+    base
+}
+
 pub fn index_vec(index: usize, qubits: &[&usize], mask: &[usize], dim: usize) -> Vec<usize> {
-    let imask = (0..dim + 1)
-        .map(|s| (index << (dim - s)) & mask[s])
-        .fold(0, |acc, m| acc | m);
+    let imask = base_index(index, mask, dim);
     (0..1 << dim)
         .map(|i| {
             (0..dim).fold(imask, |acc, j| {
@@ -47,3 +54,18 @@ pub fn index_vec(index: usize, qubits: &[&usize], mask: &[usize], dim: usize) ->
         })
         .collect()
 }
+
+#[test]
+fn mask_vec_test() {
+    // the bits above, between and below the qubits 1 and 3
+    let all = 0xFFFF_FFFF_FFFF_FFFFusize;
+    assert_eq!(vec![all << 4, 0b100, 0b1], mask_vec(&[&3, &1]));
+
+    // the groups of amplitudes a two-qubit gate mixes cover a 5-qubit state once
+    let masks = mask_vec(&[&1, &3]);
+    let mut indices = (0..8)
+        .flat_map(|i| index_vec(i, &[&1, &3], &masks, 2))
+        .collect::<Vec<_>>();
+    indices.sort();
+    assert_eq!((0..32).collect::<Vec<_>>(), indices);
+}
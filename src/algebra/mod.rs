@@ -1,2 +1,5 @@
+#[allow(unused_imports)]
 pub mod complex;
+pub mod krylov;
+pub mod linalg;
 pub mod matrix;
@@ -0,0 +1,472 @@
+use crate::algorithm::grover::{grover_operator, Grover, Oracle};
+use crate::algorithm::phase_estimation::PhaseEstimation;
+use crate::circuit::circuit::QuantumCircuit;
+use crate::circuit::parameter::ParameterizedCircuit;
+use crate::gate::gate::Gate;
+use ndarray::prelude::Array2;
+use num::complex::Complex;
+use num::Zero;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+// the amplitude a = |<good|A|0>|^2 of the good states, which the estimators read from
+// the grover operator Q = A (2|0><0| - I) A^dagger S_good. with a = sin^2(theta) the
+// good probability after k applications of Q is sin^2((2k + 1) theta)
+pub struct EstimationProblem<'a> {
+    pub preparation: &'a ParameterizedCircuit,
+    pub oracle: &'a dyn Oracle,
+}
+
+impl EstimationProblem<'_> {
+    fn grover(&self) -> Grover<'_> {
+        Grover {
+            oracle: self.oracle,
+            preparation: Some(self.preparation),
+            iterations: None,
+            shots: 0,
+        }
+    }
+
+    // the number of times the good outcome is measured in the given shots
+    fn sample(&self, iterations: usize, shots: usize) -> usize {
+        let probability = self.grover().amplified_probability(iterations);
+        (0..shots)
+            .filter(|_| rand::random::<f64>() < probability)
+            .count()
+    }
+
+    // Q as a gate whose matrix index has qubit q as bit q
+    fn grover_gate(&self) -> Gate {
+        let n = self.preparation.size();
+        let qubits = (0..n).collect::<Vec<_>>();
+        let mut matrix = Array2::<Complex<f64>>::zeros((1 << n, 1 << n));
+        for j in 0..1 << n {
+            let mut qc = QuantumCircuit::new(n);
+            qc.state.elements[0] = Complex::zero();
+            qc.state.elements[j] = Complex::new(1., 0.);
+            grover_operator(&mut qc, self.oracle, self.preparation, &qubits);
+            matrix.column_mut(j).assign(&qc.state.elements);
+        }
+        Gate { size: n, matrix }
+    }
+}
+
+// the good states are those with the objective qubit set, as for a payoff encoded in
+// the amplitude of one qubit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectiveOracle {
+    pub num_qubits: usize,
+    pub objective: usize,
+}
+
+impl Oracle for ObjectiveOracle {
+    fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    fn is_good(&self, item: usize) -> bool {
+        item >> self.objective & 1 == 1
+    }
+
+    fn apply(&self, qc: &mut QuantumCircuit, qubits: &[usize]) {
+        qc.Z(qubits[self.objective]);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmplitudeEstimationResult {
+    pub estimation: f64,
+    pub confidence_interval: (f64, f64),
+    // the applications of Q, each querying the oracle once, summed over the shots
+    pub oracle_calls: usize,
+}
+
+pub trait AmplitudeEstimator {
+    fn estimate(&self, problem: &EstimationProblem) -> AmplitudeEstimationResult;
+}
+
+// canonical amplitude estimation (Brassard et al. 2002): phase estimation of Q, whose
+// eigenphases are +-theta / pi, reads y with a = sin^2(pi y / M). the interval is the
+// bound |a - estimate| <= 2 pi sqrt(a (1 - a)) / M + pi^2 / M^2 which holds with
+// probability at least 8 / pi^2
+pub struct AmplitudeEstimation {
+    pub num_evaluation_qubits: usize,
+    pub shots: usize,
+}
+
+impl AmplitudeEstimation {
+    // the sampled estimates sin^2(pi y / M) with their frequencies, in increasing order
+    pub fn distribution(&self, problem: &EstimationProblem) -> Vec<(f64, f64)> {
+        let qpe = PhaseEstimation {
+            num_evaluation_qubits: self.num_evaluation_qubits,
+            shots: self.shots,
+        };
+        let result = qpe.estimate(&problem.grover_gate(), problem.preparation);
+        let m = 1 << self.num_evaluation_qubits;
+        // y and M - y give the same estimate
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        for (&y, &count) in result.counts.iter() {
+            *counts.entry(y.min(m - y)).or_insert(0) += count;
+        }
+        let mut distribution = counts
+            .into_iter()
+            .map(|(y, count)| {
+                (
+                    (PI * y as f64 / m as f64).sin().powi(2),
+                    count as f64 / self.shots as f64,
+                )
+            })
+            .collect::<Vec<_>>();
+        distribution.sort_by(|a, b| a.0.total_cmp(&b.0));
+        distribution
+    }
+}
+
+impl AmplitudeEstimator for AmplitudeEstimation {
+    fn estimate(&self, problem: &EstimationProblem) -> AmplitudeEstimationResult {
+        let (estimation, _) = self
+            .distribution(problem)
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("no samples");
+        let m = (1 << self.num_evaluation_qubits) as f64;
+        let error = 2. * PI * (estimation * (1. - estimation)).sqrt() / m + PI * PI / (m * m);
+        AmplitudeEstimationResult {
+            estimation,
+            confidence_interval: ((estimation - error).max(0.), (estimation + error).min(1.)),
+            oracle_calls: self.shots * ((1 << self.num_evaluation_qubits) - 1),
+        }
+    }
+}
+
+// iterative amplitude estimation (Grinko et al. 2021) narrows an interval
+// [theta_l, theta_u] without phase estimation. each round picks the largest
+// K = 4k + 2 for which K theta stays within one half period of the good probability
+// (1 - cos(K theta)) / 2, measures it with a chernoff-hoeffding interval at confidence
+// 1 - alpha / T and maps that back to theta, until the interval on a is within epsilon
+pub struct IterativeAmplitudeEstimation {
+    pub epsilon: f64,
+    pub alpha: f64,
+    // per round
+    pub shots: usize,
+}
+
+impl IterativeAmplitudeEstimation {
+    // the next power k and whether the good probability increases with theta there
+    fn next_power(&self, k: usize, theta_lower: f64, theta_upper: f64) -> (usize, bool) {
+        let current = 4 * k + 2;
+        let mut candidate = (PI / (theta_upper - theta_lower)).floor() as usize;
+        if candidate < 2 {
+            return (k, true);
+        }
+        candidate -= (candidate - 2) % 4;
+        while candidate >= 2 * current {
+            let lower = (candidate as f64 * theta_lower / PI).floor();
+            let upper = (candidate as f64 * theta_upper / PI).ceil() - 1.;
+            if lower == upper {
+                return ((candidate - 2) / 4, (lower as usize).is_multiple_of(2));
+            }
+            candidate -= 4;
+        }
+        let half_period = (current as f64 * theta_lower / PI).floor() as usize;
+        (k, half_period.is_multiple_of(2))
+    }
+}
+
+impl AmplitudeEstimator for IterativeAmplitudeEstimation {
+    fn estimate(&self, problem: &EstimationProblem) -> AmplitudeEstimationResult {
+        let max_rounds = (PI / (8. * self.epsilon)).log2().ceil().max(1.);
+        let hoeffding = ((2. * max_rounds / self.alpha).ln() / 2.).sqrt();
+        let (mut theta_lower, mut theta_upper) = (0., PI / 2.);
+        let (mut k, mut ones, mut shots) = (0, 0, 0);
+        let mut oracle_calls = 0;
+        let interval = |lower: f64, upper: f64| (lower.sin().powi(2), upper.sin().powi(2));
+        for _ in 0..1000 {
+            let (a_lower, a_upper) = interval(theta_lower, theta_upper);
+            if a_upper - a_lower <= 2. * self.epsilon {
+                break;
+            }
+            let (next, increasing) = self.next_power(k, theta_lower, theta_upper);
+            // the measurements of a power are pooled until it changes
+            if next != k {
+                k = next;
+                ones = 0;
+                shots = 0;
+            }
+            ones += problem.sample(k, self.shots);
+            shots += self.shots;
+            oracle_calls += self.shots * k;
+
+            let frequency = ones as f64 / shots as f64;
+            let error = hoeffding / (shots as f64).sqrt();
+            let (a_min, a_max) = ((frequency - error).max(0.), (frequency + error).min(1.));
+            let factor = (4 * k + 2) as f64;
+            let half_period = (factor * theta_lower / PI).floor();
+            let angle = |a: f64| (1. - 2. * a).clamp(-1., 1.).acos();
+            let (lower, upper) = if increasing {
+                (
+                    half_period * PI + angle(a_min),
+                    half_period * PI + angle(a_max),
+                )
+            } else {
+                (
+                    (half_period + 1.) * PI - angle(a_max),
+                    (half_period + 1.) * PI - angle(a_min),
+                )
+            };
+            theta_lower = f64::max(theta_lower, lower / factor);
+            theta_upper = f64::min(theta_upper, upper / factor);
+        }
+        let confidence_interval = interval(theta_lower, theta_upper);
+        AmplitudeEstimationResult {
+            estimation: (confidence_interval.0 + confidence_interval.1) / 2.,
+            confidence_interval,
+            oracle_calls,
+        }
+    }
+}
+
+// maximum likelihood amplitude estimation (Suzuki et al. 2020) measures A Q^k|0> for a
+// schedule of powers and maximizes sum_k h_k ln sin^2((2k + 1) theta)
+// + (N - h_k) ln cos^2((2k + 1) theta). the interval uses the fisher information
+// 4 N sum_k (2k + 1)^2 of theta at confidence 1 - alpha
+pub struct MaximumLikelihoodAmplitudeEstimation {
+    pub powers: Vec<usize>,
+    pub alpha: f64,
+    // per power
+    pub shots: usize,
+}
+
+impl MaximumLikelihoodAmplitudeEstimation {
+    // the powers 0, 1, 2, 4, .., 2^(num_powers - 2)
+    pub fn exponential(num_powers: usize, alpha: f64, shots: usize) -> Self {
+        let mut powers = vec![0];
+        powers.extend((0..num_powers.saturating_sub(1)).map(|j| 1 << j));
+        MaximumLikelihoodAmplitudeEstimation {
+            powers,
+            alpha,
+            shots,
+        }
+    }
+
+    fn log_likelihood(&self, theta: f64, ones: &[usize]) -> f64 {
+        self.powers
+            .iter()
+            .zip(ones.iter())
+            .map(|(&k, &h)| {
+                let angle = (2 * k + 1) as f64 * theta;
+                let good = angle.sin().powi(2).max(1e-300);
+                let bad = angle.cos().powi(2).max(1e-300);
+                h as f64 * good.ln() + (self.shots - h) as f64 * bad.ln()
+            })
+            .sum()
+    }
+}
+
+impl AmplitudeEstimator for MaximumLikelihoodAmplitudeEstimation {
+    fn estimate(&self, problem: &EstimationProblem) -> AmplitudeEstimationResult {
+        let ones = self
+            .powers
+            .iter()
+            .map(|&k| problem.sample(k, self.shots))
+            .collect::<Vec<_>>();
+
+        // a grid finer than the fastest oscillation, then golden section search
+        let max_power = self.powers.iter().max().copied().unwrap_or(0);
+        let num_points = 100 * (2 * max_power + 1);
+        let step = PI / 2. / num_points as f64;
+        let best = (0..=num_points)
+            .map(|j| j as f64 * step)
+            .max_by(|&a, &b| {
+                self.log_likelihood(a, &ones)
+                    .total_cmp(&self.log_likelihood(b, &ones))
+            })
+            .unwrap();
+        let ratio = (5f64.sqrt() - 1.) / 2.;
+        let (mut lower, mut upper) = ((best - step).max(0.), (best + step).min(PI / 2.));
+        while upper - lower > 1e-12 {
+            let left = upper - ratio * (upper - lower);
+            let right = lower + ratio * (upper - lower);
+            if self.log_likelihood(left, &ones) < self.log_likelihood(right, &ones) {
+                lower = left;
+            } else {
+                upper = right;
+            }
+        }
+        let theta = (lower + upper) / 2.;
+
+        let fisher = 4.
+            * self.shots as f64
+            * self
+                .powers
+                .iter()
+                .map(|&k| ((2 * k + 1) * (2 * k + 1)) as f64)
+                .sum::<f64>();
+        let error = normal_quantile(1. - self.alpha / 2.) / fisher.sqrt();
+        let estimation = theta.sin().powi(2);
+        let confidence_interval = (
+            (theta - error).clamp(0., PI / 2.).sin().powi(2),
+            (theta + error).clamp(0., PI / 2.).sin().powi(2),
+        );
+        AmplitudeEstimationResult {
+            estimation,
+            confidence_interval,
+            oracle_calls: self.shots * self.powers.iter().sum::<usize>(),
+        }
+    }
+}
+
+// the inverse of the standard normal distribution function by the rational
+// approximation of Acklam, accurate to about 1e-9
+fn normal_quantile(p: f64) -> f64 {
+    assert!(p > 0. && p < 1.);
+    let a = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.38357751867269e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    let b = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    let c = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    let d = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    let tail = |q: f64| {
+        (((((c[0] * q + c[1]) * q + c[2]) * q + c[3]) * q + c[4]) * q + c[5])
+            / ((((d[0] * q + d[1]) * q + d[2]) * q + d[3]) * q + 1.)
+    };
+    if p < 0.02425 {
+        tail((-2. * p.ln()).sqrt())
+    } else if p > 1. - 0.02425 {
+        -tail((-2. * (1. - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((a[0] * r + a[1]) * r + a[2]) * r + a[3]) * r + a[4]) * r + a[5]) * q
+            / (((((b[0] * r + b[1]) * r + b[2]) * r + b[3]) * r + b[4]) * r + 1.)
+    }
+}
+
+// a bernoulli variable with P(1) = probability on a single qubit
+#[cfg(test)]
+fn bernoulli(probability: f64) -> ParameterizedCircuit {
+    let mut preparation = ParameterizedCircuit::new(1);
+    preparation.RY(0, 2. * probability.sqrt().asin());
+    preparation
+}
+
+#[test]
+fn amplitude_estimation_test() {
+    // an amplitude on the grid sin^2(pi y / M) is found exactly
+    let exact = (PI * 3. / 16.).sin().powi(2);
+    let preparation = bernoulli(exact);
+    let oracle = ObjectiveOracle {
+        num_qubits: 1,
+        objective: 0,
+    };
+    let problem = EstimationProblem {
+        preparation: &preparation,
+        oracle: &oracle,
+    };
+    let qae = AmplitudeEstimation {
+        num_evaluation_qubits: 4,
+        shots: 100,
+    };
+    let result = qae.estimate(&problem);
+    assert!((result.estimation - exact).abs() < 1e-10);
+    assert_eq!(1500, result.oracle_calls);
+    assert_eq!(1, qae.distribution(&problem).len());
+
+    // otherwise the nearest grid value within the interval
+    let preparation = bernoulli(0.3);
+    let problem = EstimationProblem {
+        preparation: &preparation,
+        oracle: &oracle,
+    };
+    let qae = AmplitudeEstimation {
+        num_evaluation_qubits: 6,
+        shots: 1000,
+    };
+    let result = qae.estimate(&problem);
+    assert!((result.estimation - 0.3).abs() < 0.025);
+    let (lower, upper) = result.confidence_interval;
+    assert!(lower < 0.3 && 0.3 < upper);
+}
+
+#[test]
+fn iterative_amplitude_estimation_test() {
+    let preparation = bernoulli(0.3);
+    let oracle = ObjectiveOracle {
+        num_qubits: 1,
+        objective: 0,
+    };
+    let problem = EstimationProblem {
+        preparation: &preparation,
+        oracle: &oracle,
+    };
+    let iqae = IterativeAmplitudeEstimation {
+        epsilon: 0.005,
+        alpha: 0.01,
+        shots: 100,
+    };
+    let result = iqae.estimate(&problem);
+    let (lower, upper) = result.confidence_interval;
+    assert!(upper - lower <= 0.01);
+    assert!(lower <= 0.3 && 0.3 <= upper);
+    assert!(result.oracle_calls > 0);
+}
+
+#[test]
+fn maximum_likelihood_amplitude_estimation_test() {
+    // the expected payoff of f(x) = sin^2((b + c_0 x_0 + c_1 x_1) / 2) for a uniform
+    // two bit x, encoded in the objective qubit 2
+    let (b, c0, c1) = (0.4, 0.5, 0.9);
+    let mut preparation = ParameterizedCircuit::new(3);
+    preparation.H(0);
+    preparation.H(1);
+    preparation.RY(2, b);
+    preparation.CRY(0, 2, c0);
+    preparation.CRY(1, 2, c1);
+    let expected = (0..4)
+        .map(|x| {
+            let angle = b + c0 * (x & 1) as f64 + c1 * (x >> 1) as f64;
+            (angle / 2.).sin().powi(2) / 4.
+        })
+        .sum::<f64>();
+    let oracle = ObjectiveOracle {
+        num_qubits: 3,
+        objective: 2,
+    };
+    let problem = EstimationProblem {
+        preparation: &preparation,
+        oracle: &oracle,
+    };
+    let mlae = MaximumLikelihoodAmplitudeEstimation::exponential(5, 1e-4, 100);
+    assert_eq!(vec![0, 1, 2, 4, 8], mlae.powers);
+    let result = mlae.estimate(&problem);
+    assert!((result.estimation - expected).abs() < 0.01);
+    let (lower, upper) = result.confidence_interval;
+    assert!(lower < expected && expected < upper);
+    assert_eq!(1500, result.oracle_calls);
+
+    assert!((normal_quantile(0.975) - 1.959963985).abs() < 1e-8);
+    assert!((normal_quantile(0.005) + 2.575829304).abs() < 1e-8);
+}
@@ -0,0 +1,198 @@
+use crate::chemistry::fermion::{FermionicOp, Ladder};
+use crate::chemistry::mapping::Mapping;
+use crate::circuit::parameter::{Parameter, ParameterizedCircuit};
+use num::complex::Complex;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Entanglement {
+    Linear,
+    Circular,
+    Full,
+}
+
+impl Entanglement {
+    pub fn pairs(self, num_qubits: usize) -> Vec<(usize, usize)> {
+        match self {
+            Entanglement::Linear => (1..num_qubits).map(|q| (q - 1, q)).collect(),
+            Entanglement::Circular => {
+                let mut pairs = Entanglement::Linear.pairs(num_qubits);
+                if num_qubits > 2 {
+                    pairs.push((num_qubits - 1, 0));
+                }
+                pairs
+            }
+            Entanglement::Full => (0..num_qubits)
+                .flat_map(|a| (a + 1..num_qubits).map(move |b| (a, b)))
+                .collect(),
+        }
+    }
+}
+
+// `reps` layers of RY-RZ rotations on every qubit followed by CNOT entanglers, and a
+// final rotation layer; the parameters are named theta[0], theta[1], ...
+pub fn hardware_efficient(
+    num_qubits: usize,
+    reps: usize,
+    entanglement: Entanglement,
+) -> ParameterizedCircuit {
+    let mut pc = ParameterizedCircuit::new(num_qubits);
+    let mut count = 0;
+    let mut next = || {
+        count += 1;
+        Parameter::new(&format!("theta[{}]", count - 1))
+    };
+    for layer in 0..=reps {
+        for qubit in 0..num_qubits {
+            pc.RY(qubit, next());
+            pc.RZ(qubit, next());
+        }
+        if layer < reps {
+            for (ctrl, target) in entanglement.pairs(num_qubits) {
+                pc.CNOT(ctrl, target);
+            }
+        }
+    }
+    pc
+}
+
+// exp(theta (T - T^dagger)) for T = a^dagger_{to..} a_{from..}; the pauli terms of the
+// jordan-wigner image commute, so the exponential is a product of pauli rotations
+fn excitation(pc: &mut ParameterizedCircuit, from: &[usize], to: &[usize], theta: &Parameter) {
+    let mut ladders = to.iter().map(|&a| Ladder::Creation(a)).collect::<Vec<_>>();
+    ladders.extend(from.iter().rev().map(|&i| Ladder::Annihilation(i)));
+    let t = Mapping::JordanWigner.map(&FermionicOp::from_terms(
+        pc.size(),
+        vec![(Complex::new(1., 0.), ladders)],
+    ));
+    // i (T - T^dagger) is hermitian, so exp(theta (T - T^dagger)) = exp(-i theta H)
+    let generator = (&(&t - &t.adjoint()) * Complex::new(0., 1.)).simplify();
+    for (coefficient, pauli) in generator.terms().iter() {
+        pc.pauli_rotation(pauli, theta * (2. * coefficient.re));
+    }
+}
+
+// UCCSD-style ansatz on 2 * num_spatial_orbitals spin orbitals (alpha orbitals on the
+// first half of the qubits, beta orbitals on the second half) mapped with jordan-wigner:
+// the hartree-fock reference followed by the spin conserving single and double
+// excitations from occupied to virtual orbitals, each with its own parameter
+pub fn uccsd(num_spatial_orbitals: usize, num_particles: (usize, usize)) -> ParameterizedCircuit {
+    let (num_alpha, num_beta) = num_particles;
+    assert!(num_alpha <= num_spatial_orbitals && num_beta <= num_spatial_orbitals);
+    let n = num_spatial_orbitals;
+    let mut pc = ParameterizedCircuit::new(2 * n);
+    let alpha_occupied = (0..num_alpha).collect::<Vec<_>>();
+    let alpha_virtual = (num_alpha..n).collect::<Vec<_>>();
+    let beta_occupied = (n..n + num_beta).collect::<Vec<_>>();
+    let beta_virtual = (n + num_beta..2 * n).collect::<Vec<_>>();
+    for &qubit in alpha_occupied.iter().chain(beta_occupied.iter()) {
+        pc.X(qubit);
+    }
+
+    let mut excitations: Vec<(Vec<usize>, Vec<usize>)> = vec![];
+    for (occupied, virtuals) in [
+        (&alpha_occupied, &alpha_virtual),
+        (&beta_occupied, &beta_virtual),
+    ] {
+        for &i in occupied.iter() {
+            for &a in virtuals.iter() {
+                excitations.push((vec![i], vec![a]));
+            }
+        }
+    }
+    for (occupied, virtuals) in [
+        (&alpha_occupied, &alpha_virtual),
+        (&beta_occupied, &beta_virtual),
+    ] {
+        for (k, &i) in occupied.iter().enumerate() {
+            for &j in occupied[k + 1..].iter() {
+                for (l, &a) in virtuals.iter().enumerate() {
+                    for &b in virtuals[l + 1..].iter() {
+                        excitations.push((vec![i, j], vec![a, b]));
+                    }
+                }
+            }
+        }
+    }
+    for &i in alpha_occupied.iter() {
+        for &j in beta_occupied.iter() {
+            for &a in alpha_virtual.iter() {
+                for &b in beta_virtual.iter() {
+                    excitations.push((vec![i, j], vec![a, b]));
+                }
+            }
+        }
+    }
+
+    for (k, (from, to)) in excitations.iter().enumerate() {
+        excitation(&mut pc, from, to, &Parameter::new(&format!("t[{}]", k)));
+    }
+    pc
+}
+
+#[test]
+fn hardware_efficient_test() {
+    assert_eq!(vec![(0, 1), (1, 2), (2, 3)], Entanglement::Linear.pairs(4));
+    assert_eq!(
+        vec![(0, 1), (1, 2), (2, 3), (3, 0)],
+        Entanglement::Circular.pairs(4)
+    );
+    assert_eq!(6, Entanglement::Full.pairs(4).len());
+
+    let pc = hardware_efficient(3, 2, Entanglement::Linear);
+    assert_eq!(3, pc.size());
+    assert_eq!(2 * 3 * 3, pc.num_parameters());
+    let probabilities = pc.run_values(&[0.; 18]).probabilities();
+    assert!((probabilities[0] - 1.).abs() < 1e-12);
+}
+
+#[test]
+fn uccsd_test() {
+    // 2 spatial orbitals with one alpha and one beta electron: 2 singles and 1 double
+    let pc = uccsd(2, (1, 1));
+    assert_eq!(4, pc.size());
+    assert_eq!(3, pc.num_parameters());
+
+    // zero amplitudes leave the hartree-fock state |0101>
+    let probabilities = pc.run_values(&[0., 0., 0.]).probabilities();
+    assert!((probabilities[0b0101] - 1.).abs() < 1e-12);
+
+    // any amplitudes keep one alpha (qubits 0, 1) and one beta (qubits 2, 3) electron
+    let probabilities = pc.run_values(&[0.3, -0.7, 1.1]).probabilities();
+    for (index, probability) in probabilities.iter().enumerate() {
+        if (index & 0b0011).count_ones() != 1 || (index & 0b1100).count_ones() != 1 {
+            assert!(*probability < 1e-12);
+        }
+    }
+    assert!(probabilities[0b1010] > 1e-3);
+}
+
+#[test]
+fn uccsd_vqe_test() {
+    use crate::algorithm::vqe::VQE;
+    use crate::operator::pauli::SparsePauliOp;
+    use crate::optimizer::lbfgs::LBFGS;
+
+    // hubbard dimer with t = 1 and U = 2 written in its bonding (qubits 0, 2) and
+    // antibonding (qubits 1, 3) orbitals, ground energy U/2 - sqrt(U^2/4 + 4t^2)
+    let annihilation = |j: usize| Mapping::JordanWigner.map(&FermionicOp::annihilation(j, 4));
+    let site = |spin: usize, sign: f64| {
+        let bonding = annihilation(2 * spin);
+        let antibonding = annihilation(2 * spin + 1);
+        &(&bonding + &(&antibonding * sign)) * 0.5f64.sqrt()
+    };
+    let number = |spin: usize, sign: f64| site(spin, sign).adjoint().compose(&site(spin, sign));
+    let mut hamiltonian = SparsePauliOp::new(4);
+    for spin in [0, 1] {
+        let hop = site(spin, 1.).adjoint().compose(&site(spin, -1.));
+        hamiltonian = &hamiltonian - &(&hop + &hop.adjoint());
+    }
+    for sign in [1., -1.] {
+        hamiltonian = &hamiltonian + &(&number(0, sign).compose(&number(1, sign)) * 2.);
+    }
+    let hamiltonian = hamiltonian.simplify();
+
+    let ansatz = uccsd(2, (1, 1));
+    let optimizer = LBFGS::new(10);
+    let result = VQE::new(&ansatz, &optimizer).compute_minimum_eigenvalue(&hamiltonian, &[0.; 3]);
+    assert!((result.energy - (1. - 5f64.sqrt())).abs() < 1e-6);
+}
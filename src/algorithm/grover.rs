@@ -0,0 +1,271 @@
+use crate::circuit::circuit::QuantumCircuit;
+use crate::circuit::parameter::ParameterizedCircuit;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+// a phase oracle flips the sign of the good basis states, with qubits[q] as bit q of
+// the item
+pub trait Oracle {
+    fn num_qubits(&self) -> usize;
+
+    fn is_good(&self, item: usize) -> bool;
+
+    fn apply(&self, qc: &mut QuantumCircuit, qubits: &[usize]);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseOracle {
+    num_qubits: usize,
+    marked: Vec<usize>,
+}
+
+impl PhaseOracle {
+    pub fn from_marked(num_qubits: usize, marked: &[usize]) -> PhaseOracle {
+        let mut marked = marked.to_vec();
+        marked.sort();
+        marked.dedup();
+        assert!(marked.iter().all(|&item| item < 1 << num_qubits));
+        PhaseOracle { num_qubits, marked }
+    }
+
+    // bitstrings with the highest qubit first, as in State::from_bitstring
+    pub fn from_bitstrings(bitstrings: &[&str]) -> PhaseOracle {
+        let num_qubits = bitstrings.first().map_or(0, |bits| bits.len());
+        let marked = bitstrings
+            .iter()
+            .map(|bits| {
+                assert_eq!(num_qubits, bits.len());
+                usize::from_str_radix(bits, 2).expect("invalid bitstring")
+            })
+            .collect::<Vec<_>>();
+        PhaseOracle::from_marked(num_qubits, &marked)
+    }
+
+    // the predicate is evaluated classically on every item
+    pub fn from_predicate(num_qubits: usize, predicate: impl Fn(usize) -> bool) -> PhaseOracle {
+        let marked = (0..1 << num_qubits)
+            .filter(|&item| predicate(item))
+            .collect::<Vec<_>>();
+        PhaseOracle { num_qubits, marked }
+    }
+
+    pub fn marked(&self) -> &[usize] {
+        &self.marked
+    }
+}
+
+impl Oracle for PhaseOracle {
+    fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    fn is_good(&self, item: usize) -> bool {
+        self.marked.binary_search(&item).is_ok()
+    }
+
+    // one multi-controlled Z per marked item, conjugated by X on its zero bits
+    fn apply(&self, qc: &mut QuantumCircuit, qubits: &[usize]) {
+        assert_eq!(self.num_qubits, qubits.len());
+        for &item in self.marked.iter() {
+            let zeros = (0..self.num_qubits)
+                .filter(|q| item >> q & 1 == 0)
+                .map(|q| qubits[q])
+                .collect::<Vec<_>>();
+            for &q in zeros.iter() {
+                qc.X(q);
+            }
+            qc.MCZ(qubits);
+            for &q in zeros.iter() {
+                qc.X(q);
+            }
+        }
+    }
+}
+
+// hadamards on every qubit
+pub fn uniform_superposition(num_qubits: usize) -> ParameterizedCircuit {
+    let mut preparation = ParameterizedCircuit::new(num_qubits);
+    for q in 0..num_qubits {
+        preparation.H(q);
+    }
+    preparation
+}
+
+// the reflection A (2|0><0| - I) A^dagger about the prepared state
+pub fn diffusion(qc: &mut QuantumCircuit, preparation: &ParameterizedCircuit, qubits: &[usize]) {
+    qc.append(&preparation.inverse(), qubits);
+    for &q in qubits.iter() {
+        qc.X(q);
+    }
+    qc.MCZ(qubits);
+    for &q in qubits.iter() {
+        qc.X(q);
+    }
+    qc.append(preparation, qubits);
+    qc.global_phase(PI);
+}
+
+// Q = A (2|0><0| - I) A^dagger S_good rotates A|0> = sin(theta)|good> + cos(theta)|bad>
+// by 2 theta towards the good states
+pub fn grover_operator(
+    qc: &mut QuantumCircuit,
+    oracle: &dyn Oracle,
+    preparation: &ParameterizedCircuit,
+    qubits: &[usize],
+) {
+    oracle.apply(qc, qubits);
+    diffusion(qc, preparation, qubits);
+}
+
+// the number of iterations maximizing sin^2((2k + 1) theta) for the probability
+// sin^2(theta) of a good state after the preparation
+pub fn optimal_iterations(probability: f64) -> usize {
+    if probability <= 0. || probability >= 1. {
+        return 0;
+    }
+    let theta = probability.sqrt().asin();
+    (PI / (4. * theta)).floor() as usize
+}
+
+// grover search, or amplitude amplification with a custom preparation A
+pub struct Grover<'a> {
+    pub oracle: &'a dyn Oracle,
+    // the uniform superposition when None
+    pub preparation: Option<&'a ParameterizedCircuit>,
+    // the optimal number for the initial probability when None
+    pub iterations: Option<usize>,
+    pub shots: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroverResult {
+    pub iterations: usize,
+    // the probability of measuring a good item after the iterations
+    pub success_probability: f64,
+    pub counts: HashMap<usize, usize>,
+    // the sampled good items, the most frequent first
+    pub found: Vec<usize>,
+}
+
+impl Grover<'_> {
+    fn preparation(&self) -> ParameterizedCircuit {
+        match self.preparation {
+            Some(preparation) => {
+                assert_eq!(self.oracle.num_qubits(), preparation.size());
+                preparation.clone()
+            }
+            None => uniform_superposition(self.oracle.num_qubits()),
+        }
+    }
+
+    fn good_probability(&self, qc: &QuantumCircuit) -> f64 {
+        qc.state
+            .probabilities()
+            .iter()
+            .enumerate()
+            .filter(|&(item, _)| self.oracle.is_good(item))
+            .map(|(_, p)| p)
+            .sum()
+    }
+
+    // the probability of a good item in A|0>
+    pub fn initial_probability(&self) -> f64 {
+        self.amplified_probability(0)
+    }
+
+    // the probability of a good item in Q^iterations A|0>
+    pub fn amplified_probability(&self, iterations: usize) -> f64 {
+        self.good_probability(&self.construct_circuit(iterations))
+    }
+
+    pub fn construct_circuit(&self, iterations: usize) -> QuantumCircuit {
+        let n = self.oracle.num_qubits();
+        let qubits = (0..n).collect::<Vec<_>>();
+        let preparation = self.preparation();
+        let mut qc = QuantumCircuit::new(n);
+        qc.append(&preparation, &qubits);
+        for _ in 0..iterations {
+            grover_operator(&mut qc, self.oracle, &preparation, &qubits);
+        }
+        qc
+    }
+
+    pub fn run(&self) -> GroverResult {
+        let iterations = self
+            .iterations
+            .unwrap_or_else(|| optimal_iterations(self.initial_probability()));
+        let qc = self.construct_circuit(iterations);
+        let counts = qc.state.sample(self.shots);
+        let mut found = counts
+            .iter()
+            .filter(|&(&item, _)| self.oracle.is_good(item))
+            .map(|(&item, &count)| (item, count))
+            .collect::<Vec<_>>();
+        found.sort_by_key(|&(item, count)| (std::cmp::Reverse(count), item));
+        GroverResult {
+            iterations,
+            success_probability: self.good_probability(&qc),
+            counts,
+            found: found.into_iter().map(|(item, _)| item).collect(),
+        }
+    }
+}
+
+#[test]
+fn grover_test() {
+    let oracle = PhaseOracle::from_bitstrings(&["01101"]);
+    assert_eq!(vec![13], oracle.marked());
+    let grover = Grover {
+        oracle: &oracle,
+        preparation: None,
+        iterations: None,
+        shots: 100,
+    };
+    assert!((grover.initial_probability() - 1. / 32.).abs() < 1e-12);
+    let result = grover.run();
+    assert_eq!(4, result.iterations);
+    let theta = (1f64 / 32.).sqrt().asin();
+    assert!((result.success_probability - (9. * theta).sin().powi(2)).abs() < 1e-10);
+    assert_eq!(13, result.found[0]);
+
+    // several solutions given by a predicate
+    let oracle = PhaseOracle::from_predicate(4, |x| x > 0 && x % 5 == 0);
+    assert_eq!(vec![5, 10, 15], oracle.marked());
+    let result = Grover {
+        oracle: &oracle,
+        preparation: None,
+        iterations: None,
+        shots: 500,
+    }
+    .run();
+    assert_eq!(1, result.iterations);
+    assert!(result.success_probability > 0.94);
+    let mut found = result.found.clone();
+    found.sort();
+    assert_eq!(vec![5, 10, 15], found);
+}
+
+#[test]
+fn amplitude_amplification_test() {
+    // A puts probability 0.1 on qubit 0 being 1 and spreads qubit 1 uniformly
+    let theta = 0.1f64.sqrt().asin();
+    let mut preparation = ParameterizedCircuit::new(2);
+    preparation.RY(0, 2. * theta);
+    preparation.H(1);
+    preparation.CRY(0, 1, 0.3);
+    let oracle = PhaseOracle::from_predicate(2, |x| x & 1 == 1);
+    for k in 0..5 {
+        let grover = Grover {
+            oracle: &oracle,
+            preparation: Some(&preparation),
+            iterations: Some(k),
+            shots: 10,
+        };
+        let result = grover.run();
+        let expected = ((2 * k + 1) as f64 * theta).sin().powi(2);
+        assert!((result.success_probability - expected).abs() < 1e-10);
+    }
+    assert_eq!(2, optimal_iterations(0.1));
+    assert_eq!(1, optimal_iterations(0.25));
+    assert_eq!(25, optimal_iterations(1. / 1024.));
+}
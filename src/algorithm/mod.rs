@@ -0,0 +1,7 @@
+pub mod amplitude_estimation;
+pub mod ansatz;
+pub mod grover;
+pub mod phase_estimation;
+pub mod qaoa;
+pub mod shor;
+pub mod vqe;
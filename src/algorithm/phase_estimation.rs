@@ -0,0 +1,242 @@
+use crate::circuit::circuit::QuantumCircuit;
+use crate::circuit::parameter::ParameterizedCircuit;
+use crate::circuit::qft::QFT;
+#[cfg(test)]
+use crate::gate::base_gates::{DoubleGate, SingleGate};
+use crate::gate::gate::Gate;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+// the unitary acts on the system qubits with system qubit q as bit q of its matrix
+// index (see ParameterizedCircuit::to_gate), the preparation maps |0..0> to an
+// eigenstate U|psi> = e^{2 pi i phase}|psi> with the phase in [0, 1)
+fn apply_controlled_power(
+    qc: &mut QuantumCircuit,
+    control: usize,
+    system: &[usize],
+    unitary: &Gate,
+    exponent: usize,
+) {
+    let gate = unitary.power(exponent).controlled();
+    let mut qubits = vec![&control];
+    qubits.extend(system.iter().rev());
+    qc.apply(&qubits, &gate);
+}
+
+// evaluation qubit k controls U^(2^k), after which the evaluation register holds the
+// fourier transform of the phase and the inverse QFT returns phase * 2^m to it
+pub struct PhaseEstimation {
+    pub num_evaluation_qubits: usize,
+    pub shots: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseEstimationResult {
+    pub num_evaluation_qubits: usize,
+    pub shots: usize,
+    // how often each integer was read from the evaluation qubits
+    pub counts: HashMap<usize, usize>,
+}
+
+impl PhaseEstimationResult {
+    // the sampled phases k / 2^m with their frequencies, in increasing phase
+    pub fn distribution(&self) -> Vec<(f64, f64)> {
+        let mut distribution = self
+            .counts
+            .iter()
+            .map(|(&k, &count)| {
+                (
+                    k as f64 / (1 << self.num_evaluation_qubits) as f64,
+                    count as f64 / self.shots as f64,
+                )
+            })
+            .collect::<Vec<_>>();
+        distribution.sort_by(|a, b| a.0.total_cmp(&b.0));
+        distribution
+    }
+
+    // the most frequent phase
+    pub fn phase(&self) -> f64 {
+        let (&k, _) = self
+            .counts
+            .iter()
+            .max_by_key(|&(&k, &count)| (count, std::cmp::Reverse(k)))
+            .expect("no samples");
+        k as f64 / (1 << self.num_evaluation_qubits) as f64
+    }
+}
+
+impl PhaseEstimation {
+    // evaluation qubits 0..m followed by the system qubits
+    pub fn construct_circuit(
+        &self,
+        unitary: &Gate,
+        preparation: &ParameterizedCircuit,
+    ) -> QuantumCircuit {
+        let m = self.num_evaluation_qubits;
+        let system = (m..m + unitary.size()).collect::<Vec<_>>();
+        let mut qc = QuantumCircuit::new(m + unitary.size());
+        qc.append(preparation, &system);
+        for k in 0..m {
+            qc.H(k);
+            apply_controlled_power(&mut qc, k, &system, unitary, 1 << k);
+        }
+        QFT::inverse().apply(&mut qc, &(0..m).collect::<Vec<_>>());
+        qc
+    }
+
+    pub fn estimate(
+        &self,
+        unitary: &Gate,
+        preparation: &ParameterizedCircuit,
+    ) -> PhaseEstimationResult {
+        let qc = self.construct_circuit(unitary, preparation);
+        let mask = (1 << self.num_evaluation_qubits) - 1;
+        let mut counts = HashMap::new();
+        for (index, count) in qc.state.sample(self.shots) {
+            *counts.entry(index & mask).or_insert(0) += count;
+        }
+        PhaseEstimationResult {
+            num_evaluation_qubits: self.num_evaluation_qubits,
+            shots: self.shots,
+            counts,
+        }
+    }
+}
+
+// kitaev's single ancilla variant reads the bits of the phase from the least
+// significant one: U^(2^(m-1-r)) leaves x_r / 2 on the ancilla once the phase of the
+// bits x_0..x_r-1 already read is rotated away, and each bit is the majority of the shots
+pub struct IterativePhaseEstimation {
+    pub num_iterations: usize,
+    pub shots: usize,
+}
+
+impl IterativePhaseEstimation {
+    // the probability of reading 1 on the ancilla in the round that reads bit r
+    fn round(
+        &self,
+        unitary: &Gate,
+        preparation: &ParameterizedCircuit,
+        r: usize,
+        correction: f64,
+    ) -> f64 {
+        let system = (1..=unitary.size()).collect::<Vec<_>>();
+        let mut qc = QuantumCircuit::new(1 + unitary.size());
+        qc.append(preparation, &system);
+        qc.H(0);
+        apply_controlled_power(
+            &mut qc,
+            0,
+            &system,
+            unitary,
+            1 << (self.num_iterations - 1 - r),
+        );
+        // P(theta) multiplies |1> by e^{-i theta}
+        qc.P(0, correction);
+        qc.H(0);
+        qc.state
+            .probabilities()
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| index & 1 == 1)
+            .map(|(_, p)| p)
+            .sum()
+    }
+
+    pub fn estimate(&self, unitary: &Gate, preparation: &ParameterizedCircuit) -> f64 {
+        let mut bits = 0usize;
+        for r in 0..self.num_iterations {
+            // 2 pi (0.0 x_r-1 .. x_0) in binary
+            let correction = 2. * PI * bits as f64 / (1 << (r + 1)) as f64;
+            let probability = self.round(unitary, preparation, r, correction);
+            let ones = (0..self.shots)
+                .filter(|_| rand::random::<f64>() < probability)
+                .count();
+            if 2 * ones > self.shots {
+                bits |= 1 << r;
+            }
+        }
+        bits as f64 / (1 << self.num_iterations) as f64
+    }
+}
+
+// a phase gate with eigenvalue e^{2 pi i phase} on |1>
+#[cfg(test)]
+fn phase_gate(phase: f64) -> Gate {
+    SingleGate::P(-2. * PI * phase)
+}
+
+#[cfg(test)]
+fn excited() -> ParameterizedCircuit {
+    let mut preparation = ParameterizedCircuit::new(1);
+    preparation.X(0);
+    preparation
+}
+
+#[test]
+fn phase_estimation_test() {
+    // a phase with four binary digits is read exactly
+    let qpe = PhaseEstimation {
+        num_evaluation_qubits: 4,
+        shots: 100,
+    };
+    let result = qpe.estimate(&phase_gate(5. / 16.), &excited());
+    assert_eq!(HashMap::from([(5, 100)]), result.counts);
+    assert_eq!(vec![(5. / 16., 1.)], result.distribution());
+
+    // otherwise the nearest phase k / 2^m is the most likely, with probability > 4 / pi^2
+    let qpe = PhaseEstimation {
+        num_evaluation_qubits: 5,
+        shots: 2000,
+    };
+    let result = qpe.estimate(&phase_gate(1. / 3.), &excited());
+    assert_eq!(11. / 32., result.phase());
+    let (_, frequency) = result
+        .distribution()
+        .into_iter()
+        .find(|&(phase, _)| phase == 11. / 32.)
+        .unwrap();
+    assert!(frequency > 0.3);
+    assert!(result.distribution().len() > 1);
+
+    // a two qubit sub-circuit on |01>: RZZ(theta) contributes e^{i theta / 2} and
+    // the phase gate on qubit 0 another 1 / 8, which also checks the qubit order
+    let mut circuit = ParameterizedCircuit::new(2);
+    circuit.RZZ(0, 1, PI);
+    circuit.P(0, -2. * PI / 8.);
+    let unitary = circuit.to_gate(&HashMap::new());
+    let mut preparation = ParameterizedCircuit::new(2);
+    preparation.X(0);
+    let qpe = PhaseEstimation {
+        num_evaluation_qubits: 3,
+        shots: 50,
+    };
+    assert_eq!(3. / 8., qpe.estimate(&unitary, &preparation).phase());
+}
+
+#[test]
+fn iterative_phase_estimation_test() {
+    let ipe = IterativePhaseEstimation {
+        num_iterations: 4,
+        shots: 50,
+    };
+    for k in [0, 3, 11, 15] {
+        let phase = k as f64 / 16.;
+        assert_eq!(phase, ipe.estimate(&phase_gate(phase), &excited()));
+    }
+
+    // the eigenstate |10> of CP with eigenvalue 1 and |11> with e^{-i theta}
+    let unitary = DoubleGate::CP(-2. * PI * 0.625);
+    let mut preparation = ParameterizedCircuit::new(2);
+    preparation.X(0);
+    preparation.X(1);
+    let ipe = IterativePhaseEstimation {
+        num_iterations: 3,
+        shots: 20,
+    };
+    assert_eq!(0.625, ipe.estimate(&unitary, &preparation));
+    preparation = ParameterizedCircuit::new(2);
+    preparation.X(1);
+    assert_eq!(0., ipe.estimate(&unitary, &preparation));
+}
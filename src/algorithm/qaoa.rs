@@ -0,0 +1,432 @@
+use crate::circuit::circuit::QuantumCircuit;
+use crate::circuit::parameter::{Parameter, ParameterizedCircuit};
+use crate::operator::pauli::{Pauli, PauliString, SparsePauliOp};
+use crate::optimizer::optimizer::{ExpectationObjective, OptimizationResult, Optimizer};
+use crate::state::expectation::parity;
+use ndarray::prelude::Array2;
+use num::complex::Complex;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Graph {
+    num_nodes: usize,
+    edges: Vec<(usize, usize, f64)>,
+}
+
+impl Graph {
+    pub fn new(num_nodes: usize) -> Graph {
+        Graph {
+            num_nodes,
+            edges: vec![],
+        }
+    }
+
+    pub fn add_edge(&mut self, a: usize, b: usize, weight: f64) {
+        assert!(a < self.num_nodes && b < self.num_nodes && a != b);
+        self.edges.push((a, b, weight));
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    pub fn edges(&self) -> &[(usize, usize, f64)] {
+        &self.edges
+    }
+
+    // total weight of the edges between the nodes whose bit is 0 and those whose bit is 1
+    pub fn cut_value(&self, bits: usize) -> f64 {
+        self.edges
+            .iter()
+            .filter(|(a, b, _)| (bits >> a & 1) != (bits >> b & 1))
+            .map(|(_, _, w)| w)
+            .sum()
+    }
+}
+
+// E(z) = offset + sum_i h_i z_i + sum_(i,j) J_ij z_i z_j, where z_i = 1 - 2 b_i is the
+// eigenvalue of Z_i on the bit b_i, so that E is the diagonal of the cost hamiltonian
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ising {
+    pub h: Vec<f64>,
+    pub j: Vec<(usize, usize, f64)>,
+    pub offset: f64,
+}
+
+impl Ising {
+    pub fn new(num_spins: usize) -> Ising {
+        Ising {
+            h: vec![0.; num_spins],
+            j: vec![],
+            offset: 0.,
+        }
+    }
+
+    pub fn num_spins(&self) -> usize {
+        self.h.len()
+    }
+
+    // minimizing sum_(a,b) w_ab (z_a z_b - 1) / 2 maximizes the cut
+    pub fn maxcut(graph: &Graph) -> Ising {
+        let mut ising = Ising::new(graph.num_nodes());
+        for &(a, b, weight) in graph.edges() {
+            ising.j.push((a, b, weight / 2.));
+            ising.offset -= weight / 2.;
+        }
+        ising
+    }
+
+    // minimizing x^T Q x over bits x_i = (1 - z_i) / 2
+    pub fn from_qubo(q: &Array2<f64>) -> Ising {
+        assert_eq!(q.nrows(), q.ncols());
+        let mut ising = Ising::new(q.nrows());
+        for ((a, b), &value) in q.indexed_iter() {
+            if value == 0. {
+                continue;
+            }
+            if a == b {
+                ising.offset += value / 2.;
+                ising.h[a] -= value / 2.;
+            } else {
+                ising.offset += value / 4.;
+                ising.h[a] -= value / 4.;
+                ising.h[b] -= value / 4.;
+                ising.j.push((a.min(b), a.max(b), value / 4.));
+            }
+        }
+        ising
+    }
+
+    pub fn energy(&self, bits: usize) -> f64 {
+        let spin = |i: usize| if bits >> i & 1 == 0 { 1. } else { -1. };
+        self.offset
+            + self
+                .h
+                .iter()
+                .enumerate()
+                .map(|(i, h)| h * spin(i))
+                .sum::<f64>()
+            + self
+                .j
+                .iter()
+                .map(|&(a, b, j)| j * spin(a) * spin(b))
+                .sum::<f64>()
+    }
+
+    pub fn hamiltonian(&self) -> SparsePauliOp {
+        let n = self.num_spins();
+        let mut op = SparsePauliOp::new(n);
+        let z = |qubits: &[usize]| {
+            let mut pauli = PauliString::identity(n);
+            for &q in qubits {
+                pauli.set(q, Pauli::Z);
+            }
+            pauli
+        };
+        if self.offset != 0. {
+            op.add_term(Complex::new(self.offset, 0.), z(&[]));
+        }
+        for (i, &h) in self.h.iter().enumerate() {
+            if h != 0. {
+                op.add_term(Complex::new(h, 0.), z(&[i]));
+            }
+        }
+        for &(a, b, j) in self.j.iter() {
+            op.add_term(Complex::new(j, 0.), z(&[a, b]));
+        }
+        op.simplify()
+    }
+
+    // exhaustive search, only meant for small problems and tests
+    pub fn brute_force(&self) -> (usize, f64) {
+        (0..1usize << self.num_spins())
+            .map(|bits| (bits, self.energy(bits)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap()
+    }
+}
+
+// <bits|H|bits> for a hamiltonian made of Z and I factors only
+pub fn diagonal_value(op: &SparsePauliOp, bits: usize) -> f64 {
+    op.terms()
+        .iter()
+        .map(|(c, p)| {
+            assert_eq!(0, p.x_mask(), "the cost hamiltonian should be diagonal");
+            c.re * parity(bits, p.z_mask())
+        })
+        .sum()
+}
+
+// p layers of exp(-i beta_k sum X) exp(-i gamma_k H) on |+..+>; one and two qubit
+// terms become RZ and RZZ gates, longer Z strings are decomposed into a CNOT ladder.
+// the parameters are gamma[k] and beta[k], in the order gamma[0], beta[0], gamma[1], ...
+pub fn qaoa_ansatz(cost: &SparsePauliOp, layers: usize) -> ParameterizedCircuit {
+    assert!(cost.terms().iter().any(|(_, p)| !p.is_identity()));
+    let n = cost.size();
+    let mut pc = ParameterizedCircuit::new(n);
+    for q in 0..n {
+        pc.H(q);
+    }
+    for k in 0..layers {
+        let gamma = Parameter::new(&format!("gamma[{}]", k));
+        let beta = Parameter::new(&format!("beta[{}]", k));
+        for (c, pauli) in cost.terms().iter() {
+            assert_eq!(0, pauli.x_mask(), "the cost hamiltonian should be diagonal");
+            let angle = &gamma * (2. * c.re);
+            match pauli.support().as_slice() {
+                [] => {}
+                [q] => pc.RZ(*q, angle),
+                [a, b] => pc.RZZ(*a, *b, angle),
+                _ => pc.pauli_rotation(pauli, angle),
+            }
+        }
+        for q in 0..n {
+            pc.RX(q, &beta * 2.);
+        }
+    }
+    pc
+}
+
+pub fn interleave(gammas: &[f64], betas: &[f64]) -> Vec<f64> {
+    assert_eq!(gammas.len(), betas.len());
+    gammas
+        .iter()
+        .zip(betas.iter())
+        .flat_map(|(&g, &b)| [g, b])
+        .collect()
+}
+
+pub fn deinterleave(values: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let gammas = values.iter().step_by(2).cloned().collect();
+    let betas = values.iter().skip(1).step_by(2).cloned().collect();
+    (gammas, betas)
+}
+
+pub fn qaoa_circuit(cost: &SparsePauliOp, gammas: &[f64], betas: &[f64]) -> QuantumCircuit {
+    qaoa_ansatz(cost, gammas.len()).bind_values(&interleave(gammas, betas))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InitialPoint {
+    // discretized annealing schedule with the given time step:
+    // gamma_k = (k + 1/2) / p * dt and beta_k = (1 - (k + 1/2) / p) * dt
+    LinearRamp(f64),
+    // gamma uniform in [0, pi) and beta uniform in [0, pi/2)
+    Random,
+    // INTERP of Zhou et al.: the optimal angles of p - 1 layers interpolated to p layers
+    Interp { gammas: Vec<f64>, betas: Vec<f64> },
+}
+
+impl InitialPoint {
+    // interleaved values for `layers` layers, in the parameter order of `qaoa_ansatz`
+    pub fn values(&self, layers: usize) -> Vec<f64> {
+        let (gammas, betas): (Vec<f64>, Vec<f64>) = match self {
+            InitialPoint::LinearRamp(dt) => (0..layers)
+                .map(|k| {
+                    let s = (k as f64 + 0.5) / layers as f64;
+                    (s * dt, (1. - s) * dt)
+                })
+                .unzip(),
+            InitialPoint::Random => (0..layers)
+                .map(|_| (PI * rand::random::<f64>(), PI / 2. * rand::random::<f64>()))
+                .unzip(),
+            InitialPoint::Interp { gammas, betas } => {
+                assert_eq!(gammas.len() + 1, layers);
+                (interpolate(gammas), interpolate(betas))
+            }
+        };
+        interleave(&gammas, &betas)
+    }
+}
+
+// x'_i = (i - 1)/p x_(i-1) + (p - i + 1)/p x_i for i = 1..p+1, with x_0 = x_(p+1) = 0
+fn interpolate(values: &[f64]) -> Vec<f64> {
+    let p = values.len();
+    let x = |i: usize| if i == 0 || i > p { 0. } else { values[i - 1] };
+    (1..=p + 1)
+        .map(|i| {
+            if p == 0 {
+                return 0.;
+            }
+            (i - 1) as f64 / p as f64 * x(i - 1) + (p + 1 - i) as f64 / p as f64 * x(i)
+        })
+        .collect()
+}
+
+// the sampled bitstring with the lowest cost, ties broken by the number of counts
+pub fn best_bitstring(
+    counts: &HashMap<usize, usize>,
+    cost: impl Fn(usize) -> f64,
+) -> Option<(usize, f64)> {
+    counts
+        .iter()
+        .map(|(&bits, &count)| (bits, count, cost(bits)))
+        .min_by(|a, b| a.2.total_cmp(&b.2).then(b.1.cmp(&a.1)).then(a.0.cmp(&b.0)))
+        .map(|(bits, _, value)| (bits, value))
+}
+
+pub struct QAOA<'a> {
+    pub cost: &'a SparsePauliOp,
+    pub layers: usize,
+    pub optimizer: &'a dyn Optimizer,
+    // shots sampled from the optimized state to pick the best bitstring
+    pub shots: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QAOAResult {
+    pub gammas: Vec<f64>,
+    pub betas: Vec<f64>,
+    // <H> of the optimized state
+    pub energy: f64,
+    pub counts: HashMap<usize, usize>,
+    pub best_bitstring: usize,
+    pub best_value: f64,
+    pub optimization: OptimizationResult,
+}
+
+impl<'a> QAOA<'a> {
+    pub fn new(cost: &'a SparsePauliOp, layers: usize, optimizer: &'a dyn Optimizer) -> QAOA<'a> {
+        QAOA {
+            cost,
+            layers,
+            optimizer,
+            shots: 1000,
+        }
+    }
+
+    pub fn run(&self, initial: &InitialPoint) -> QAOAResult {
+        let ansatz = qaoa_ansatz(self.cost, self.layers);
+        let objective = ExpectationObjective::new(&ansatz, self.cost);
+        let optimization = self
+            .optimizer
+            .minimize(&objective, &initial.values(self.layers));
+        let counts = ansatz
+            .run_values(&optimization.parameters)
+            .sample(self.shots);
+        let (best_bitstring, best_value) =
+            best_bitstring(&counts, |bits| diagonal_value(self.cost, bits)).unwrap();
+        let (gammas, betas) = deinterleave(&optimization.parameters);
+        QAOAResult {
+            gammas,
+            betas,
+            energy: optimization.value,
+            counts,
+            best_bitstring,
+            best_value,
+            optimization,
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::optimizer::lbfgs::LBFGS;
+#[cfg(test)]
+use ndarray::array;
+
+#[test]
+fn encoding_test() {
+    let mut graph = Graph::new(4);
+    graph.add_edge(0, 1, 1.);
+    graph.add_edge(1, 2, 2.);
+    graph.add_edge(2, 3, 0.5);
+    graph.add_edge(3, 0, 1.5);
+    graph.add_edge(0, 2, 1.);
+    let ising = Ising::maxcut(&graph);
+    let hamiltonian = ising.hamiltonian();
+    for bits in 0..16 {
+        assert!((ising.energy(bits) + graph.cut_value(bits)).abs() < 1e-12);
+        assert!((diagonal_value(&hamiltonian, bits) - ising.energy(bits)).abs() < 1e-12);
+    }
+    // the best cut separates {0, 2} from {1, 3}
+    let (bits, energy) = ising.brute_force();
+    assert!(bits == 0b1010 || bits == 0b0101);
+    assert!((energy + 5.).abs() < 1e-12);
+
+    let q = array![[1., -2., 0.5], [0., -3., 1.], [2., 0., 0.5]];
+    let ising = Ising::from_qubo(&q);
+    let hamiltonian = ising.hamiltonian();
+    for bits in 0..8 {
+        let x = (0..3).map(|i| (bits >> i & 1) as f64).collect::<Vec<_>>();
+        let mut value = 0.;
+        for ((a, b), q) in q.indexed_iter() {
+            value += q * x[a] * x[b];
+        }
+        assert!((ising.energy(bits) - value).abs() < 1e-12);
+        assert!((diagonal_value(&hamiltonian, bits) - value).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn qaoa_circuit_test() {
+    // RZZ(theta) = exp(-i theta/2 Z Z)
+    let mut qc = QuantumCircuit::new(2);
+    qc.H(0);
+    qc.H(1);
+    qc.RZZ(0, 1, 0.7);
+    let mut expected = QuantumCircuit::new(2);
+    expected.H(0);
+    expected.H(1);
+    expected.CNOT(0, 1);
+    expected.RZ(1, 0.7);
+    expected.CNOT(0, 1);
+    for (a, b) in qc.state.elements.iter().zip(expected.state.elements.iter()) {
+        assert!((a - b).norm() < 1e-12);
+    }
+
+    // the ansatz works for cost terms of any weight and interleaves the parameters
+    let cost: SparsePauliOp = "0.5*Z0 Z1 Z2 - Z1 + 0.3*Z0 Z2".parse().unwrap();
+    let ansatz = qaoa_ansatz(&cost, 2);
+    let names = ansatz
+        .parameters()
+        .iter()
+        .map(|p| p.name().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["gamma[0]", "beta[0]", "gamma[1]", "beta[1]"], names);
+    let qc = qaoa_circuit(&cost, &[0.4, 0.9], &[0.3, 0.1]);
+    let norm = qc.state.elements.iter().map(|a| a.norm_sqr()).sum::<f64>();
+    assert!((norm - 1.).abs() < 1e-12);
+}
+
+#[test]
+fn initial_point_test() {
+    let values = InitialPoint::LinearRamp(1.).values(2);
+    assert_eq!(vec![0.25, 0.75, 0.75, 0.25], values);
+    let values = InitialPoint::Random.values(3);
+    assert_eq!(6, values.len());
+    let (gammas, betas) = deinterleave(&values);
+    assert!(gammas.iter().all(|g| (0. ..PI).contains(g)));
+    assert!(betas.iter().all(|b| (0. ..PI / 2.).contains(b)));
+    let values = InitialPoint::Interp {
+        gammas: vec![0.2, 0.4],
+        betas: vec![0.6, 0.2],
+    }
+    .values(3);
+    assert_eq!(6, values.len());
+    let (gammas, betas) = deinterleave(&values);
+    for (value, expected) in gammas
+        .iter()
+        .chain(betas.iter())
+        .zip([0.2, 0.3, 0.4, 0.6, 0.4, 0.2])
+    {
+        assert!((value - expected).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn qaoa_maxcut_test() {
+    // ring of 4 nodes, the maximum cut 4 is reached by 0101 and 1010
+    let mut graph = Graph::new(4);
+    for a in 0..4 {
+        graph.add_edge(a, (a + 1) % 4, 1.);
+    }
+    let cost = Ising::maxcut(&graph).hamiltonian();
+    let optimizer = LBFGS::new(10);
+    let qaoa = QAOA::new(&cost, 2, &optimizer);
+    let result = qaoa.run(&InitialPoint::LinearRamp(0.8));
+    assert!(result.energy < -3.);
+    assert!(result.best_bitstring == 0b0101 || result.best_bitstring == 0b1010);
+    assert!((result.best_value + 4.).abs() < 1e-12);
+    assert_eq!(1000, result.counts.values().sum::<usize>());
+}
@@ -0,0 +1,193 @@
+use crate::arithmetic::modular::{gcd, mod_pow, multiply_constant_modulo};
+use crate::circuit::circuit::QuantumCircuit;
+use rand::Rng;
+use std::f64::consts::PI;
+
+// the convergents p / q of the continued fraction of numerator / denominator
+pub fn convergents(numerator: usize, denominator: usize) -> Vec<(usize, usize)> {
+    let (mut p0, mut q0, mut p1, mut q1) = (0, 1, 1, 0);
+    let (mut a, mut b) = (numerator, denominator);
+    let mut result = vec![];
+    while b != 0 {
+        let term = a / b;
+        (p0, p1) = (p1, term * p1 + p0);
+        (q0, q1) = (q1, term * q1 + q0);
+        result.push((p1, q1));
+        (a, b) = (b, a % b);
+    }
+    result
+}
+
+// the factor r if n = r^k for some k > 1
+fn perfect_power_root(n: usize) -> Option<usize> {
+    (2..usize::BITS - n.leading_zeros()).find_map(|k| {
+        let root = (n as f64).powf(1. / k as f64).round() as usize;
+        (root.saturating_sub(1)..=root + 1).find(|&r| r > 1 && r.checked_pow(k) == Some(n))
+    })
+}
+
+// order finding with the phase estimation of U|x> = |a x mod N> on a single control
+// qubit (Beauregard 2003): the 2n bits of the phase s / r are read one at a time from
+// the least significant one, each after U^(2^(2n - 1 - j)) and the rotation of the
+// bits already measured, which replaces the inverse QFT. the work register x of n
+// qubits starts in |1>, the multiplication uses n + 1 more qubits and an ancilla
+pub struct Shor {
+    // the runs of order finding per base and the bases tried
+    pub max_attempts: usize,
+}
+
+impl Default for Shor {
+    fn default() -> Self {
+        Shor { max_attempts: 10 }
+    }
+}
+
+impl Shor {
+    // the 2n bits y of a sample of the phase y / 2^2n of U
+    pub fn sample_phase(&self, base: usize, modulus: usize) -> usize {
+        assert_eq!(1, gcd(base, modulus));
+        let n = (usize::BITS - modulus.leading_zeros()) as usize;
+        let t = 2 * n;
+        let x = (1..=n).collect::<Vec<_>>();
+        let register = (n + 1..=2 * n + 1).collect::<Vec<_>>();
+        let ancilla = 2 * n + 2;
+        let mut qc = QuantumCircuit::new(2 * n + 3);
+        qc.X(x[0]);
+        let mut bits = 0;
+        for j in 0..t {
+            let multiplier = mod_pow(base, 1 << (t - 1 - j), modulus);
+            qc.H(0);
+            if multiplier != 1 {
+                multiply_constant_modulo(
+                    &mut qc,
+                    &[0],
+                    &x,
+                    &register,
+                    ancilla,
+                    multiplier,
+                    modulus,
+                );
+            }
+            // P(theta) multiplies |1> by e^{-i theta}
+            qc.P(0, 2. * PI * bits as f64 / (1 << (j + 1)) as f64);
+            qc.H(0);
+            if qc.measure(0, 1).ones() == 1 {
+                bits |= 1 << j;
+                qc.X(0);
+            }
+        }
+        bits
+    }
+
+    // the denominator of the last convergent of y / 2^2n below N, which is r / gcd(s, r)
+    // for the closest fraction s / r when the sample is good
+    pub fn denominator(&self, modulus: usize, phase: usize) -> usize {
+        let n = (usize::BITS - modulus.leading_zeros()) as usize;
+        convergents(phase, 1 << (2 * n))
+            .into_iter()
+            .map(|(_, q)| q)
+            .take_while(|&q| q < modulus)
+            .last()
+            .unwrap_or(1)
+    }
+
+    // the least common multiple of the denominators of the samples until a^r = 1 mod N
+    pub fn find_order(&self, base: usize, modulus: usize) -> Option<usize> {
+        let mut order = 1;
+        for _ in 0..self.max_attempts {
+            let q = self.denominator(modulus, self.sample_phase(base, modulus));
+            order = order / gcd(order, q) * q;
+            if order >= modulus {
+                order = 1;
+            } else if mod_pow(base, order, modulus) == 1 {
+                return Some(order);
+            }
+        }
+        None
+    }
+
+    // a nontrivial factorization p <= q of n
+    pub fn factor(&self, n: usize) -> Option<(usize, usize)> {
+        assert!(n > 3);
+        if n.is_multiple_of(2) {
+            return Some((2, n / 2));
+        }
+        if let Some(root) = perfect_power_root(n) {
+            return Some((root, n / root));
+        }
+        let mut rng = rand::thread_rng();
+        for _ in 0..self.max_attempts {
+            let base = rng.gen_range(2..n);
+            let divisor = gcd(base, n);
+            if divisor > 1 {
+                return Some((divisor.min(n / divisor), divisor.max(n / divisor)));
+            }
+            let Some(order) = self.find_order(base, n) else {
+                continue;
+            };
+            if order % 2 == 1 {
+                continue;
+            }
+            let half = mod_pow(base, order / 2, n);
+            if half == n - 1 {
+                continue;
+            }
+            for divisor in [gcd(half + 1, n), gcd(half + n - 1, n)] {
+                if divisor > 1 && divisor < n {
+                    return Some((divisor.min(n / divisor), divisor.max(n / divisor)));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[test]
+fn convergents_test() {
+    // 0.8125 = 13 / 16 = [0; 1, 4, 3]
+    assert_eq!(vec![(0, 1), (1, 1), (4, 5), (13, 16)], convergents(13, 16));
+    // 85 / 256 is close to 1 / 3
+    assert!(convergents(85, 256).contains(&(1, 3)));
+    let shor = Shor::default();
+    assert_eq!(3, shor.denominator(15, 85));
+    assert_eq!(1, shor.denominator(15, 0));
+    assert_eq!(Some(3), perfect_power_root(27));
+    assert_eq!(Some(5), perfect_power_root(125));
+    assert_eq!(None, perfect_power_root(35));
+}
+
+#[test]
+fn order_finding_test() {
+    // a sample gives nothing with probability 1 / r, when s = 0
+    let shor = Shor { max_attempts: 20 };
+    // the phases of 7 mod 15 are s / 4 and read exactly from 8 bits
+    for _ in 0..3 {
+        let phase = shor.sample_phase(7, 15);
+        assert!([0, 64, 128, 192].contains(&phase));
+    }
+    assert_eq!(Some(4), shor.find_order(7, 15));
+    assert_eq!(Some(4), shor.find_order(2, 15));
+    assert_eq!(Some(2), shor.find_order(8, 21));
+    assert_eq!(Some(2), shor.find_order(6, 35));
+    // the phases s / 6 of 2 mod 21 are not exact in 10 bits, so the order comes from
+    // continued fractions and the lcm of the denominators of several samples
+    assert_eq!(Some(6), shor.find_order(2, 21));
+}
+
+#[test]
+fn factor_test() {
+    let shor = Shor::default();
+    assert_eq!(Some((3, 5)), shor.factor(15));
+    assert_eq!(Some((3, 9)), shor.factor(27));
+    assert_eq!(Some((2, 11)), shor.factor(22));
+}
+
+// on 13 and 15 qubits this takes about a minute and a half in a debug build and under
+// a second in a release one, so it runs with --release or --ignored
+#[test]
+#[cfg_attr(debug_assertions, ignore)]
+fn factor_slow_test() {
+    let shor = Shor::default();
+    assert_eq!(Some((3, 7)), shor.factor(21));
+    assert_eq!(Some((5, 7)), shor.factor(35));
+}
@@ -0,0 +1,186 @@
+use crate::circuit::parameter::ParameterizedCircuit;
+use crate::gradient::gradient::{parameter_shift_gradient_with, GradientMethod};
+use crate::operator::pauli::SparsePauliOp;
+use crate::optimizer::optimizer::{
+    Callback, ExpectationObjective, Objective, OptimizationResult, Optimizer,
+};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Estimator {
+    // <H> computed exactly from the statevector
+    Exact,
+    // <H> estimated from this many shots per pauli term
+    Shots(usize),
+}
+
+// shot-based energy; the gradient uses the shift rule on shot estimates as well
+struct ShotObjective<'a> {
+    circuit: &'a ParameterizedCircuit,
+    observable: &'a SparsePauliOp,
+    shots: usize,
+}
+
+impl Objective for ShotObjective<'_> {
+    fn num_parameters(&self) -> usize {
+        self.circuit.num_parameters()
+    }
+
+    fn value(&self, parameters: &[f64]) -> f64 {
+        self.circuit
+            .run_values(parameters)
+            .estimate_expectation(self.observable, self.shots)
+            .mean
+    }
+
+    fn gradient(&self, parameters: &[f64]) -> Vec<f64> {
+        parameter_shift_gradient_with(self.circuit, parameters, |state| {
+            state.estimate_expectation(self.observable, self.shots).mean
+        })
+    }
+}
+
+pub struct VQE<'a> {
+    pub ansatz: &'a ParameterizedCircuit,
+    pub optimizer: &'a dyn Optimizer,
+    pub estimator: Estimator,
+    // used with the exact estimator
+    pub gradient_method: GradientMethod,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VQEResult {
+    pub energy: f64,
+    pub parameters: Vec<f64>,
+    pub optimization: OptimizationResult,
+}
+
+impl VQEResult {
+    // energy after each optimizer iteration
+    pub fn trace(&self) -> Vec<f64> {
+        self.optimization
+            .history
+            .iter()
+            .map(|it| it.value)
+            .collect()
+    }
+}
+
+impl fmt::Display for VQEResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "energy: {}, iterations: {}, evaluations: {}, parameters: {:?}",
+            self.energy,
+            self.optimization.iterations,
+            self.optimization.evaluations,
+            self.parameters
+        )
+    }
+}
+
+impl<'a> VQE<'a> {
+    pub fn new(ansatz: &'a ParameterizedCircuit, optimizer: &'a dyn Optimizer) -> VQE<'a> {
+        VQE {
+            ansatz,
+            optimizer,
+            estimator: Estimator::Exact,
+            gradient_method: GradientMethod::Adjoint,
+        }
+    }
+
+    pub fn with_shots(mut self, shots: usize) -> VQE<'a> {
+        self.estimator = Estimator::Shots(shots);
+        self
+    }
+
+    pub fn compute_minimum_eigenvalue(
+        &self,
+        hamiltonian: &SparsePauliOp,
+        initial: &[f64],
+    ) -> VQEResult {
+        self.compute_minimum_eigenvalue_with_callback(hamiltonian, initial, &mut |_| true)
+    }
+
+    pub fn compute_minimum_eigenvalue_with_callback(
+        &self,
+        hamiltonian: &SparsePauliOp,
+        initial: &[f64],
+        callback: Callback,
+    ) -> VQEResult {
+        assert!(hamiltonian.size() <= self.ansatz.size());
+        assert!(hamiltonian.is_hermitian());
+        assert_eq!(self.ansatz.num_parameters(), initial.len());
+        let optimization = match self.estimator {
+            Estimator::Exact => {
+                let mut objective = ExpectationObjective::new(self.ansatz, hamiltonian);
+                objective.method = self.gradient_method;
+                self.optimizer
+                    .minimize_with_callback(&objective, initial, callback)
+            }
+            Estimator::Shots(shots) => {
+                let objective = ShotObjective {
+                    circuit: self.ansatz,
+                    observable: hamiltonian,
+                    shots,
+                };
+                self.optimizer
+                    .minimize_with_callback(&objective, initial, callback)
+            }
+        };
+        VQEResult {
+            energy: optimization.value,
+            parameters: optimization.parameters.clone(),
+            optimization,
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::algorithm::ansatz::{hardware_efficient, Entanglement};
+#[cfg(test)]
+use crate::optimizer::{adam::Adam, lbfgs::LBFGS};
+
+// H2 at 0.735 angstrom in the sto-3g basis, reduced to two qubits by parity mapping
+#[cfg(test)]
+const H2_HAMILTONIAN: &str = "-1.052373245772859 + 0.39793742484318045*Z0 \
+    - 0.39793742484318045*Z1 - 0.01128010425623538*Z0 Z1 + 0.18093119978423156*X0 X1";
+#[cfg(test)]
+const H2_GROUND_ENERGY: f64 = -1.857275030202378;
+
+#[test]
+fn vqe_exact_test() {
+    let hamiltonian: SparsePauliOp = H2_HAMILTONIAN.parse().unwrap();
+    let ansatz = hardware_efficient(2, 1, Entanglement::Linear);
+    let optimizer = LBFGS::new(10);
+    let vqe = VQE::new(&ansatz, &optimizer);
+    let initial = (0..ansatz.num_parameters())
+        .map(|k| 0.1 * k as f64)
+        .collect::<Vec<_>>();
+    let result = vqe.compute_minimum_eigenvalue(&hamiltonian, &initial);
+    assert!((result.energy - H2_GROUND_ENERGY).abs() < 1e-6);
+    let state = ansatz.run_values(&result.parameters);
+    assert!((state.expectation(&hamiltonian) - result.energy).abs() < 1e-10);
+    let trace = result.trace();
+    assert_eq!(result.optimization.iterations, trace.len());
+    assert!(trace.last().unwrap() < &trace[0]);
+}
+
+#[test]
+fn vqe_shots_test() {
+    let hamiltonian: SparsePauliOp = H2_HAMILTONIAN.parse().unwrap();
+    let ansatz = hardware_efficient(2, 1, Entanglement::Linear);
+    let mut optimizer = Adam::new(0.2);
+    optimizer.criteria.max_iterations = 60;
+    optimizer.criteria.tolerance = 0.;
+    let vqe = VQE::new(&ansatz, &optimizer).with_shots(1000);
+    let initial = (0..ansatz.num_parameters())
+        .map(|k| 0.1 * k as f64)
+        .collect::<Vec<_>>();
+    let result = vqe.compute_minimum_eigenvalue(&hamiltonian, &initial);
+    let exact = ansatz
+        .run_values(&result.parameters)
+        .expectation(&hamiltonian);
+    assert!(exact - H2_GROUND_ENERGY < 0.05);
+    assert!((result.energy - exact).abs() < 0.05);
+}
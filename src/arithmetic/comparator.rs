@@ -0,0 +1,82 @@
+#[cfg(test)]
+use crate::arithmetic::draper::{basis_circuit, basis_index, encode};
+use crate::circuit::circuit::QuantumCircuit;
+
+// comparators without ancillas on registers of equal width. after b ^= a, b_j = 0
+// marks the equal bits, and a < b exactly when for some bit i a_i = 0 and b_i = 1
+// with every higher bit equal, one multi-controlled X per bit since the cases exclude
+// each other
+
+fn xor_into(qc: &mut QuantumCircuit, a: &[usize], b: &[usize]) {
+    assert_eq!(a.len(), b.len());
+    for (&qa, &qb) in a.iter().zip(b.iter()) {
+        qc.CNOT(qa, qb);
+    }
+}
+
+fn flip(qc: &mut QuantumCircuit, register: &[usize]) {
+    for &q in register.iter() {
+        qc.X(q);
+    }
+}
+
+// flips the result qubit when a < b
+pub fn less_than(qc: &mut QuantumCircuit, a: &[usize], b: &[usize], result: usize) {
+    xor_into(qc, a, b);
+    // a_j = 0 and the equal bits b_j = 0 become 1
+    flip(qc, a);
+    flip(qc, b);
+    for i in 0..a.len() {
+        qc.X(b[i]);
+        let mut controls = vec![a[i], b[i]];
+        controls.extend_from_slice(&b[i + 1..]);
+        qc.MCX(&controls, result);
+        qc.X(b[i]);
+    }
+    flip(qc, b);
+    flip(qc, a);
+    xor_into(qc, a, b);
+}
+
+// flips the result qubit when a > b
+pub fn greater_than(qc: &mut QuantumCircuit, a: &[usize], b: &[usize], result: usize) {
+    less_than(qc, b, a, result);
+}
+
+// flips the result qubit when a = b
+pub fn equal(qc: &mut QuantumCircuit, a: &[usize], b: &[usize], result: usize) {
+    xor_into(qc, a, b);
+    flip(qc, b);
+    qc.MCX(b, result);
+    flip(qc, b);
+    xor_into(qc, a, b);
+}
+
+#[cfg(test)]
+fn check_comparator(
+    comparator: fn(&mut QuantumCircuit, &[usize], &[usize], usize),
+    expected: fn(usize, usize) -> bool,
+) {
+    let (a, b) = ([4, 0, 2], [1, 5, 3]);
+    let result = 6;
+    for x in 0..8 {
+        for y in 0..8 {
+            for flag in 0..2 {
+                let mut qc = basis_circuit(7, &[(&a, x), (&b, y), (&[result], flag)]);
+                comparator(&mut qc, &a, &b, result);
+                let flag = flag ^ usize::from(expected(x, y));
+                assert_eq!(
+                    encode(&a, x) | encode(&b, y) | flag << result,
+                    basis_index(&qc)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn comparator_test() {
+    check_comparator(less_than, |x, y| x < y);
+    check_comparator(greater_than, |x, y| x > y);
+    check_comparator(equal, |x, y| x == y);
+}
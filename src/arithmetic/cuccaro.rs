@@ -0,0 +1,165 @@
+#[cfg(test)]
+use crate::arithmetic::draper::{basis_circuit, basis_index, encode};
+use crate::circuit::circuit::QuantumCircuit;
+
+// the ripple-carry adder of Cuccaro et al. 2004 on registers of equal width listed from
+// the least significant bit, with one ancilla in |0> that carries the input carry. MAJ
+// leaves the carry into the next bit on a, UMA restores a and writes the sum to b
+
+fn majority(qc: &mut QuantumCircuit, c: usize, b: usize, a: usize) {
+    qc.CNOT(a, b);
+    qc.CNOT(a, c);
+    qc.Toffoli(c, b, a);
+}
+
+fn unmajority(qc: &mut QuantumCircuit, c: usize, b: usize, a: usize) {
+    qc.Toffoli(c, b, a);
+    qc.CNOT(a, c);
+    qc.CNOT(c, b);
+}
+
+fn check_registers(a: &[usize], b: &[usize]) {
+    assert_eq!(a.len(), b.len());
+    assert!(!a.is_empty());
+}
+
+// the carries are computed up the MAJ ladder, the top one is flipped onto the carry
+// qubit and the ladder is undone by UMA, which leaves the sum on b when sum is true
+// and b unchanged otherwise
+fn ripple(
+    qc: &mut QuantumCircuit,
+    a: &[usize],
+    b: &[usize],
+    ancilla: usize,
+    carry: Option<usize>,
+    sum: bool,
+) {
+    check_registers(a, b);
+    let n = a.len();
+    let previous = |i: usize| if i == 0 { ancilla } else { a[i - 1] };
+    for i in 0..n {
+        majority(qc, previous(i), b[i], a[i]);
+    }
+    if let Some(carry) = carry {
+        qc.CNOT(a[n - 1], carry);
+    }
+    for i in (0..n).rev() {
+        if sum {
+            unmajority(qc, previous(i), b[i], a[i]);
+        } else {
+            // the inverse of MAJ
+            qc.Toffoli(previous(i), b[i], a[i]);
+            qc.CNOT(a[i], previous(i));
+            qc.CNOT(a[i], b[i]);
+        }
+    }
+}
+
+// b -> a + b mod 2^n, flipping the carry qubit when the sum overflows
+pub fn add(
+    qc: &mut QuantumCircuit,
+    a: &[usize],
+    b: &[usize],
+    ancilla: usize,
+    carry: Option<usize>,
+) {
+    ripple(qc, a, b, ancilla, carry, true);
+}
+
+// b -> b - a mod 2^n as the complement of a + (2^n - 1 - b), flipping the borrow
+// qubit when a > b
+pub fn subtract(
+    qc: &mut QuantumCircuit,
+    a: &[usize],
+    b: &[usize],
+    ancilla: usize,
+    borrow: Option<usize>,
+) {
+    for &q in b.iter() {
+        qc.X(q);
+    }
+    add(qc, a, b, ancilla, borrow);
+    for &q in b.iter() {
+        qc.X(q);
+    }
+}
+
+// flips the result qubit when a > b, with both registers left unchanged: the carry of
+// a + (2^n - 1 - b) without the sum
+pub fn greater_than(
+    qc: &mut QuantumCircuit,
+    a: &[usize],
+    b: &[usize],
+    ancilla: usize,
+    result: usize,
+) {
+    for &q in b.iter() {
+        qc.X(q);
+    }
+    ripple(qc, a, b, ancilla, Some(result), false);
+    for &q in b.iter() {
+        qc.X(q);
+    }
+}
+
+// flips the result qubit when a < b
+pub fn less_than(qc: &mut QuantumCircuit, a: &[usize], b: &[usize], ancilla: usize, result: usize) {
+    greater_than(qc, b, a, ancilla, result);
+}
+
+#[test]
+fn add_test() {
+    // a and b interleaved as in the paper, with the ancilla and the carry at the ends
+    let (a, b) = ([1, 3, 5], [2, 4, 6]);
+    let (ancilla, carry) = (0, 7);
+    for x in 0..8 {
+        for y in 0..8 {
+            let mut qc = basis_circuit(8, &[(&a, x), (&b, y)]);
+            add(&mut qc, &a, &b, ancilla, Some(carry));
+            let expected = encode(&a, x) | encode(&b, (x + y) % 8) | ((x + y) / 8) << carry;
+            assert_eq!(expected, basis_index(&qc));
+
+            let mut qc = basis_circuit(8, &[(&a, x), (&b, y)]);
+            add(&mut qc, &a, &b, ancilla, None);
+            assert_eq!(encode(&a, x) | encode(&b, (x + y) % 8), basis_index(&qc));
+        }
+    }
+}
+
+#[test]
+fn subtract_test() {
+    let (a, b) = ([0, 1, 2], [3, 4, 5]);
+    let (ancilla, borrow) = (6, 7);
+    for x in 0..8 {
+        for y in 0..8 {
+            let mut qc = basis_circuit(8, &[(&a, x), (&b, y)]);
+            subtract(&mut qc, &a, &b, ancilla, Some(borrow));
+            let expected =
+                encode(&a, x) | encode(&b, (y + 8 - x) % 8) | usize::from(x > y) << borrow;
+            assert_eq!(expected, basis_index(&qc));
+        }
+    }
+}
+
+#[test]
+fn comparator_test() {
+    let (a, b) = ([0, 1, 2], [3, 4, 5]);
+    let (ancilla, result) = (6, 7);
+    for x in 0..8 {
+        for y in 0..8 {
+            for flag in 0..2 {
+                let mut qc = basis_circuit(8, &[(&a, x), (&b, y), (&[result], flag)]);
+                greater_than(&mut qc, &a, &b, ancilla, result);
+                let expected =
+                    encode(&a, x) | encode(&b, y) | (flag ^ usize::from(x > y)) << result;
+                assert_eq!(expected, basis_index(&qc));
+
+                let mut qc = basis_circuit(8, &[(&a, x), (&b, y), (&[result], flag)]);
+                less_than(&mut qc, &a, &b, ancilla, result);
+                let expected =
+                    encode(&a, x) | encode(&b, y) | (flag ^ usize::from(x < y)) << result;
+                assert_eq!(expected, basis_index(&qc));
+            }
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use crate::circuit::circuit::QuantumCircuit;
+use crate::circuit::qft::QFT;
+use std::f64::consts::PI;
+
+// registers list their qubits from the least significant bit. the draper adders
+// (Draper 2000) work on the fourier transform QFT|b> = sum_k e^{2 pi i bk / 2^n}|k>,
+// where adding a multiplies |k> by e^{2 pi i ak / 2^n}, one phase per qubit of k.
+// the transform is taken without the swaps, so register[n - 1 - q] holds bit q of k
+
+pub fn to_fourier(qc: &mut QuantumCircuit, register: &[usize]) {
+    QFT {
+        do_swaps: false,
+        ..QFT::default()
+    }
+    .apply(qc, register);
+}
+
+pub fn from_fourier(qc: &mut QuantumCircuit, register: &[usize]) {
+    QFT {
+        do_swaps: false,
+        ..QFT::inverse()
+    }
+    .apply(qc, register);
+}
+
+// QFT|b> -> QFT|b + constant mod 2^n> when every control is 1
+pub fn phi_add_constant(
+    qc: &mut QuantumCircuit,
+    controls: &[usize],
+    register: &[usize],
+    constant: usize,
+) {
+    let n = register.len();
+    let modulus = 1usize << n;
+    for q in 0..n {
+        // the angle 2 pi constant 2^q / 2^n reduced before the division
+        let angle = 2. * PI * ((constant << q) % modulus) as f64 / modulus as f64;
+        if angle != 0. {
+            // MCP(theta) multiplies |1..1> by e^{-i theta}
+            qc.MCP(controls, register[n - 1 - q], -angle);
+        }
+    }
+}
+
+// b -> b + constant mod 2^n when every control is 1
+pub fn add_constant(
+    qc: &mut QuantumCircuit,
+    controls: &[usize],
+    register: &[usize],
+    constant: usize,
+) {
+    to_fourier(qc, register);
+    phi_add_constant(qc, controls, register, constant);
+    from_fourier(qc, register);
+}
+
+// b -> b - constant mod 2^n when every control is 1
+pub fn subtract_constant(
+    qc: &mut QuantumCircuit,
+    controls: &[usize],
+    register: &[usize],
+    constant: usize,
+) {
+    let modulus = 1usize << register.len();
+    add_constant(qc, controls, register, modulus - constant % modulus);
+}
+
+// QFT|b> -> QFT|b + a mod 2^n> when every control is 1, for a register a no wider
+// than b: bit j of a and bit q of k give the phase e^{2 pi i 2^(j + q) / 2^n}
+pub fn phi_add(qc: &mut QuantumCircuit, controls: &[usize], a: &[usize], b: &[usize]) {
+    let n = b.len();
+    assert!(a.len() <= n);
+    for (j, &qubit) in a.iter().enumerate() {
+        let mut controls = controls.to_vec();
+        controls.push(qubit);
+        for q in 0..n - j {
+            let angle = 2. * PI / (1usize << (n - j - q)) as f64;
+            qc.MCP(&controls, b[n - 1 - q], -angle);
+        }
+    }
+}
+
+// QFT|b> -> QFT|b - a mod 2^n> when every control is 1
+pub fn phi_subtract(qc: &mut QuantumCircuit, controls: &[usize], a: &[usize], b: &[usize]) {
+    let n = b.len();
+    assert!(a.len() <= n);
+    for (j, &qubit) in a.iter().enumerate() {
+        let mut controls = controls.to_vec();
+        controls.push(qubit);
+        for q in 0..n - j {
+            let angle = 2. * PI / (1usize << (n - j - q)) as f64;
+            qc.MCP(&controls, b[n - 1 - q], angle);
+        }
+    }
+}
+
+// b -> a + b mod 2^n when every control is 1
+pub fn add(qc: &mut QuantumCircuit, controls: &[usize], a: &[usize], b: &[usize]) {
+    to_fourier(qc, b);
+    phi_add(qc, controls, a, b);
+    from_fourier(qc, b);
+}
+
+// b -> b - a mod 2^n when every control is 1
+pub fn subtract(qc: &mut QuantumCircuit, controls: &[usize], a: &[usize], b: &[usize]) {
+    to_fourier(qc, b);
+    phi_subtract(qc, controls, a, b);
+    from_fourier(qc, b);
+}
+
+// flips the result qubit when the register holds less than the constant, for a
+// constant below 2^n. the top qubit in |0> extends the register to n + 1 bits, whose
+// top bit is the sign of b - constant
+pub fn less_than_constant(
+    qc: &mut QuantumCircuit,
+    register: &[usize],
+    top: usize,
+    result: usize,
+    constant: usize,
+) {
+    assert!(constant < 1 << register.len());
+    let mut extended = register.to_vec();
+    extended.push(top);
+    subtract_constant(qc, &[], &extended, constant);
+    qc.CNOT(top, result);
+    add_constant(qc, &[], &extended, constant);
+}
+
+// the register of the given width and value in the circuit index
+#[cfg(test)]
+pub fn encode(register: &[usize], value: usize) -> usize {
+    register
+        .iter()
+        .enumerate()
+        .map(|(bit, q)| (value >> bit & 1) << q)
+        .sum()
+}
+
+// the circuit prepared in the basis state with the registers holding the values
+#[cfg(test)]
+pub fn basis_circuit(size: usize, registers: &[(&[usize], usize)]) -> QuantumCircuit {
+    let mut qc = QuantumCircuit::new(size);
+    for &(register, value) in registers.iter() {
+        for (bit, &q) in register.iter().enumerate() {
+            if value >> bit & 1 == 1 {
+                qc.X(q);
+            }
+        }
+    }
+    qc
+}
+
+// the basis state the circuit is in, which it should be in up to a phase
+#[cfg(test)]
+pub fn basis_index(qc: &QuantumCircuit) -> usize {
+    let probabilities = qc.state.probabilities();
+    let index = (0..probabilities.len())
+        .find(|&i| probabilities[i] > 0.5)
+        .expect("the circuit should be in a basis state");
+    assert!((probabilities[index] - 1.).abs() < 1e-10);
+    index
+}
+
+#[test]
+fn add_constant_test() {
+    let register = [3, 0, 2];
+    for value in 0..8 {
+        for constant in 0..8 {
+            for control in 0..2 {
+                let mut qc = basis_circuit(5, &[(&register, value), (&[4], control)]);
+                add_constant(&mut qc, &[4], &register, constant);
+                let expected = if control == 1 {
+                    (value + constant) % 8
+                } else {
+                    value
+                };
+                assert_eq!(encode(&register, expected) | control << 4, basis_index(&qc));
+            }
+        }
+    }
+}
+
+#[test]
+fn add_test() {
+    // a narrower than b, controlled by qubit 5
+    let (a, b) = ([0, 1], [2, 3, 4]);
+    for x in 0..4 {
+        for y in 0..8 {
+            for control in 0..2 {
+                let mut qc = basis_circuit(6, &[(&a, x), (&b, y), (&[5], control)]);
+                add(&mut qc, &[5], &a, &b);
+                let expected = if control == 1 { (x + y) % 8 } else { y };
+                assert_eq!(
+                    encode(&a, x) | encode(&b, expected) | control << 5,
+                    basis_index(&qc)
+                );
+
+                let mut qc = basis_circuit(6, &[(&a, x), (&b, y), (&[5], control)]);
+                subtract(&mut qc, &[5], &a, &b);
+                let expected = if control == 1 { (y + 8 - x) % 8 } else { y };
+                assert_eq!(
+                    encode(&a, x) | encode(&b, expected) | control << 5,
+                    basis_index(&qc)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn subtract_constant_test() {
+    let register = [1, 2, 0];
+    for value in 0..8 {
+        for constant in 0..10 {
+            let mut qc = basis_circuit(3, &[(&register, value)]);
+            subtract_constant(&mut qc, &[], &register, constant);
+            assert_eq!(
+                encode(&register, (value + 16 - constant) % 8),
+                basis_index(&qc)
+            );
+        }
+    }
+}
+
+#[test]
+fn less_than_constant_test() {
+    let register = [0, 1, 2];
+    let (top, result) = (3, 4);
+    for value in 0..8 {
+        for constant in 0..8 {
+            for flag in 0..2 {
+                let mut qc = basis_circuit(5, &[(&register, value), (&[result], flag)]);
+                less_than_constant(&mut qc, &register, top, result, constant);
+                let expected =
+                    encode(&register, value) | (flag ^ usize::from(value < constant)) << result;
+                assert_eq!(expected, basis_index(&qc));
+            }
+        }
+    }
+}
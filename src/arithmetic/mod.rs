@@ -0,0 +1,5 @@
+pub mod comparator;
+pub mod cuccaro;
+pub mod draper;
+pub mod modular;
+pub mod multiplier;
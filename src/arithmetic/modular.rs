@@ -0,0 +1,264 @@
+#[cfg(test)]
+use crate::arithmetic::draper::{basis_circuit, basis_index, encode};
+use crate::arithmetic::draper::{
+    from_fourier, phi_add, phi_add_constant, phi_subtract, to_fourier,
+};
+use crate::circuit::circuit::QuantumCircuit;
+
+pub fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub fn mod_pow(base: usize, exponent: usize, modulus: usize) -> usize {
+    let mut result = 1 % modulus;
+    let mut square = base % modulus;
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * square % modulus;
+        }
+        square = square * square % modulus;
+        exponent >>= 1;
+    }
+    result
+}
+
+// the inverse by the extended euclidean algorithm, if a and the modulus are coprime
+pub fn mod_inverse(a: usize, modulus: usize) -> Option<usize> {
+    let (mut r0, mut r1) = (modulus as i64, (a % modulus) as i64);
+    let (mut t0, mut t1) = (0i64, 1i64);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    if r0 != 1 {
+        return None;
+    }
+    Some(t0.rem_euclid(modulus as i64) as usize)
+}
+
+// the modular adders of Beauregard 2003 keep b < N in a register of n + 1 qubits with
+// 2^n > N, whose top qubit catches the overflow of b + a - N, and one ancilla in |0>
+
+fn check_modulus(register: &[usize], modulus: usize) {
+    assert!(modulus > 0 && modulus < 1 << (register.len() - 1));
+}
+
+// QFT|b> -> QFT|(b + constant) mod N> when every control is 1, for b < N and a
+// constant below N: subtract N after the addition, copy the sign of b + a - N to the
+// ancilla and add N back if it was negative, then uncompute the ancilla by comparing
+// the result with the constant
+pub fn phi_add_constant_modulo(
+    qc: &mut QuantumCircuit,
+    controls: &[usize],
+    register: &[usize],
+    ancilla: usize,
+    constant: usize,
+    modulus: usize,
+) {
+    check_modulus(register, modulus);
+    let constant = constant % modulus;
+    let full = 1 << register.len();
+    let top = register[register.len() - 1];
+    phi_add_constant(qc, controls, register, constant);
+    phi_add_constant(qc, &[], register, full - modulus);
+    from_fourier(qc, register);
+    qc.CNOT(top, ancilla);
+    to_fourier(qc, register);
+    phi_add_constant(qc, &[ancilla], register, modulus);
+    phi_add_constant(qc, controls, register, full - constant);
+    from_fourier(qc, register);
+    qc.X(top);
+    qc.CNOT(top, ancilla);
+    qc.X(top);
+    to_fourier(qc, register);
+    phi_add_constant(qc, controls, register, constant);
+}
+
+// QFT|b> -> QFT|(a + b) mod N> when every control is 1, for a and b below N, in the
+// same steps as phi_add_constant_modulo with the register a in place of the constant
+pub fn phi_add_modulo(
+    qc: &mut QuantumCircuit,
+    controls: &[usize],
+    a: &[usize],
+    b: &[usize],
+    ancilla: usize,
+    modulus: usize,
+) {
+    check_modulus(b, modulus);
+    let full = 1 << b.len();
+    let top = b[b.len() - 1];
+    phi_add(qc, controls, a, b);
+    phi_add_constant(qc, &[], b, full - modulus);
+    from_fourier(qc, b);
+    qc.CNOT(top, ancilla);
+    to_fourier(qc, b);
+    phi_add_constant(qc, &[ancilla], b, modulus);
+    phi_subtract(qc, controls, a, b);
+    from_fourier(qc, b);
+    qc.X(top);
+    qc.CNOT(top, ancilla);
+    qc.X(top);
+    to_fourier(qc, b);
+    phi_add(qc, controls, a, b);
+}
+
+// b -> (a + b) mod N when every control is 1, for a and b below N
+pub fn add_modulo(
+    qc: &mut QuantumCircuit,
+    controls: &[usize],
+    a: &[usize],
+    b: &[usize],
+    ancilla: usize,
+    modulus: usize,
+) {
+    to_fourier(qc, b);
+    phi_add_modulo(qc, controls, a, b, ancilla, modulus);
+    from_fourier(qc, b);
+}
+
+// |x>|b> -> |x>|(b + constant x) mod N> when every control is 1, adding
+// constant 2^i mod N controlled by bit i of x
+pub fn multiply_add_constant_modulo(
+    qc: &mut QuantumCircuit,
+    controls: &[usize],
+    x: &[usize],
+    register: &[usize],
+    ancilla: usize,
+    constant: usize,
+    modulus: usize,
+) {
+    to_fourier(qc, register);
+    for (i, &qubit) in x.iter().enumerate() {
+        let mut controls = controls.to_vec();
+        controls.push(qubit);
+        let term = constant % modulus * mod_pow(2, i, modulus) % modulus;
+        phi_add_constant_modulo(qc, &controls, register, ancilla, term, modulus);
+    }
+    from_fourier(qc, register);
+}
+
+// |x> -> |constant x mod N> in place when every control is 1, for x < N and a
+// constant coprime to N. b += a x, swapping x and b and b -= a^-1 (a x) = x leave the
+// register of n + 1 qubits in |0> again
+pub fn multiply_constant_modulo(
+    qc: &mut QuantumCircuit,
+    controls: &[usize],
+    x: &[usize],
+    register: &[usize],
+    ancilla: usize,
+    constant: usize,
+    modulus: usize,
+) {
+    assert_eq!(x.len() + 1, register.len());
+    let inverse = mod_inverse(constant, modulus).expect("the constant should be invertible");
+    multiply_add_constant_modulo(qc, controls, x, register, ancilla, constant, modulus);
+    for (&a, &b) in x.iter().zip(register.iter()) {
+        // a controlled swap from three multi-controlled X
+        let mut to_b = controls.to_vec();
+        to_b.push(a);
+        let mut to_a = controls.to_vec();
+        to_a.push(b);
+        qc.MCX(&to_a, a);
+        qc.MCX(&to_b, b);
+        qc.MCX(&to_a, a);
+    }
+    multiply_add_constant_modulo(
+        qc,
+        controls,
+        x,
+        register,
+        ancilla,
+        modulus - inverse,
+        modulus,
+    );
+}
+
+#[test]
+fn number_theory_test() {
+    assert_eq!(3, gcd(21, 15));
+    assert_eq!(1, gcd(35, 8));
+    assert_eq!(16, mod_pow(2, 10, 21));
+    assert_eq!(1, mod_pow(7, 4, 15));
+    assert_eq!(Some(13), mod_inverse(7, 15));
+    assert_eq!(None, mod_inverse(6, 15));
+    for a in 1..35 {
+        if let Some(inverse) = mod_inverse(a, 35) {
+            assert_eq!(1, a * inverse % 35);
+        }
+    }
+}
+
+#[test]
+fn phi_add_constant_modulo_test() {
+    // b and the constant below N = 5 on four qubits, controlled by qubits 5 and 6
+    let modulus = 5;
+    let register = [0, 1, 2, 3];
+    let (ancilla, controls) = (4, [5, 6]);
+    for b in 0..modulus {
+        for constant in 0..modulus {
+            for control in [0b01, 0b11] {
+                let mut qc = basis_circuit(7, &[(&register, b), (&controls, control)]);
+                to_fourier(&mut qc, &register);
+                phi_add_constant_modulo(&mut qc, &controls, &register, ancilla, constant, modulus);
+                from_fourier(&mut qc, &register);
+                let expected = if control == 0b11 {
+                    (b + constant) % modulus
+                } else {
+                    b
+                };
+                assert_eq!(
+                    encode(&register, expected) | encode(&controls, control),
+                    basis_index(&qc)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn add_modulo_test() {
+    // a and b below N = 6, b on four qubits
+    let modulus = 6;
+    let (a, b) = ([0, 1, 2], [3, 4, 5, 6]);
+    let (ancilla, control) = (7, 8);
+    for x in 0..modulus {
+        for y in 0..modulus {
+            for flag in 0..2 {
+                let mut qc = basis_circuit(9, &[(&a, x), (&b, y), (&[control], flag)]);
+                add_modulo(&mut qc, &[control], &a, &b, ancilla, modulus);
+                let expected = if flag == 1 { (x + y) % modulus } else { y };
+                assert_eq!(
+                    encode(&a, x) | encode(&b, expected) | flag << control,
+                    basis_index(&qc)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn multiply_constant_modulo_test() {
+    // x -> 3x mod 7 with the control on qubit 0
+    let modulus = 7;
+    let x = [1, 2, 3];
+    let register = [4, 5, 6, 7];
+    let ancilla = 8;
+    for value in 0..modulus {
+        for control in 0..2 {
+            let mut qc = basis_circuit(9, &[(&x, value), (&[0], control)]);
+            multiply_constant_modulo(&mut qc, &[0], &x, &register, ancilla, 3, modulus);
+            let expected = if control == 1 {
+                3 * value % modulus
+            } else {
+                value
+            };
+            assert_eq!(encode(&x, expected) | control, basis_index(&qc));
+        }
+    }
+}
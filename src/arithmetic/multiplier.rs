@@ -0,0 +1,83 @@
+#[cfg(test)]
+use crate::arithmetic::draper::{basis_circuit, basis_index, encode};
+use crate::arithmetic::draper::{from_fourier, phi_add_constant, to_fourier};
+use crate::circuit::circuit::QuantumCircuit;
+use std::f64::consts::PI;
+
+// multipliers accumulate into a product register of m qubits modulo 2^m with the
+// draper adders, one controlled addition of a shifted operand per bit of x
+
+// |x>|p> -> |x>|p + constant x mod 2^m> when every control is 1
+pub fn multiply_constant(
+    qc: &mut QuantumCircuit,
+    controls: &[usize],
+    x: &[usize],
+    product: &[usize],
+    constant: usize,
+) {
+    let m = product.len();
+    to_fourier(qc, product);
+    for (i, &qubit) in x.iter().enumerate().take(m) {
+        let mut controls = controls.to_vec();
+        controls.push(qubit);
+        let term = (constant % (1 << m)) << i & ((1 << m) - 1);
+        phi_add_constant(qc, &controls, product, term);
+    }
+    from_fourier(qc, product);
+}
+
+// |a>|b>|p> -> |a>|b>|p + a b mod 2^m>: bits i of a, j of b and q of the fourier index
+// give the phase e^{2 pi i 2^(i + j + q) / 2^m} on a doubly controlled phase
+pub fn multiply(qc: &mut QuantumCircuit, a: &[usize], b: &[usize], product: &[usize]) {
+    let m = product.len();
+    to_fourier(qc, product);
+    for (i, &qa) in a.iter().enumerate().take(m) {
+        for (j, &qb) in b.iter().enumerate().take(m - i) {
+            for q in 0..m - i - j {
+                let angle = 2. * PI / (1usize << (m - i - j - q)) as f64;
+                qc.MCP(&[qa, qb], product[m - 1 - q], -angle);
+            }
+        }
+    }
+    from_fourier(qc, product);
+}
+
+#[test]
+fn multiply_constant_test() {
+    // x on three qubits into a product of four, controlled by qubit 7
+    let (x, product) = ([0, 1, 2], [3, 4, 5, 6]);
+    for value in 0..8 {
+        for constant in [0, 1, 3, 6, 13] {
+            for control in 0..2 {
+                let mut qc = basis_circuit(8, &[(&x, value), (&product, 5), (&[7], control)]);
+                multiply_constant(&mut qc, &[7], &x, &product, constant);
+                let expected = if control == 1 {
+                    (5 + constant * value) % 16
+                } else {
+                    5
+                };
+                assert_eq!(
+                    encode(&x, value) | encode(&product, expected) | control << 7,
+                    basis_index(&qc)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn multiply_test() {
+    let (a, b, product) = ([0, 1], [2, 3, 4], [5, 6, 7, 8]);
+    for x in 0..4 {
+        for y in 0..8 {
+            for initial in [0, 9] {
+                let mut qc = basis_circuit(9, &[(&a, x), (&b, y), (&product, initial)]);
+                multiply(&mut qc, &a, &b, &product);
+                assert_eq!(
+                    encode(&a, x) | encode(&b, y) | encode(&product, (initial + x * y) % 16),
+                    basis_index(&qc)
+                );
+            }
+        }
+    }
+}
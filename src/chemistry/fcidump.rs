@@ -0,0 +1,451 @@
+use crate::chemistry::fermion::{FermionicOp, Ladder};
+use crate::chemistry::mapping::Mapping;
+use crate::operator::pauli::SparsePauliOp;
+use ndarray::prelude::Array2;
+use num::complex::Complex;
+use std::fs;
+use std::str::FromStr;
+
+// real molecular orbital integrals: E = core_energy + h_pq + (pq|rs) in chemists'
+// notation, with the eightfold permutational symmetry of real orbitals
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElectronicIntegrals {
+    num_orbitals: usize,
+    num_electrons: usize,
+    // twice the spin projection, n_alpha - n_beta
+    ms2: i32,
+    core_energy: f64,
+    one_body: Array2<f64>,
+    two_body: Vec<f64>,
+}
+
+impl ElectronicIntegrals {
+    pub fn new(num_orbitals: usize, num_electrons: usize, ms2: i32) -> ElectronicIntegrals {
+        assert!(num_electrons <= 2 * num_orbitals);
+        assert!(ms2.unsigned_abs() as usize <= num_electrons);
+        assert_eq!(num_electrons % 2, ms2.unsigned_abs() as usize % 2);
+        ElectronicIntegrals {
+            num_orbitals,
+            num_electrons,
+            ms2,
+            core_energy: 0.,
+            one_body: Array2::zeros((num_orbitals, num_orbitals)),
+            two_body: vec![0.; num_orbitals.pow(4)],
+        }
+    }
+
+    pub fn read(path: &str) -> Result<ElectronicIntegrals, String> {
+        fs::read_to_string(path).map_err(|e| e.to_string())?.parse()
+    }
+
+    pub fn num_orbitals(&self) -> usize {
+        self.num_orbitals
+    }
+
+    pub fn num_electrons(&self) -> usize {
+        self.num_electrons
+    }
+
+    pub fn ms2(&self) -> i32 {
+        self.ms2
+    }
+
+    // (n_alpha, n_beta)
+    pub fn num_particles(&self) -> (usize, usize) {
+        let n = self.num_electrons as i32;
+        (((n + self.ms2) / 2) as usize, ((n - self.ms2) / 2) as usize)
+    }
+
+    pub fn core_energy(&self) -> f64 {
+        self.core_energy
+    }
+
+    pub fn set_core_energy(&mut self, energy: f64) {
+        self.core_energy = energy;
+    }
+
+    pub fn one_body(&self, p: usize, q: usize) -> f64 {
+        self.one_body[[p, q]]
+    }
+
+    pub fn set_one_body(&mut self, p: usize, q: usize, value: f64) {
+        self.one_body[[p, q]] = value;
+        self.one_body[[q, p]] = value;
+    }
+
+    fn index(&self, p: usize, q: usize, r: usize, s: usize) -> usize {
+        ((p * self.num_orbitals + q) * self.num_orbitals + r) * self.num_orbitals + s
+    }
+
+    pub fn two_body(&self, p: usize, q: usize, r: usize, s: usize) -> f64 {
+        self.two_body[self.index(p, q, r, s)]
+    }
+
+    pub fn set_two_body(&mut self, p: usize, q: usize, r: usize, s: usize, value: f64) {
+        for (a, b) in [(p, q), (q, p)] {
+            for (c, d) in [(r, s), (s, r)] {
+                let (i, j) = (self.index(a, b, c, d), self.index(c, d, a, b));
+                self.two_body[i] = value;
+                self.two_body[j] = value;
+            }
+        }
+    }
+
+    // spin orbital p + n * spin, the alpha orbitals first and the beta orbitals after them
+    // like the uccsd ansatz:
+    // H = E_core + sum h_pq a+_p,s a_q,s + 1/2 sum (pq|rs) a+_p,s a+_r,t a_s,t a_q,s
+    pub fn hamiltonian(&self) -> FermionicOp {
+        let n = self.num_orbitals;
+        let mut op = FermionicOp::new(2 * n);
+        if self.core_energy != 0. {
+            op.add_term(Complex::new(self.core_energy, 0.), vec![]);
+        }
+        for s in [0, n] {
+            for p in 0..n {
+                for q in 0..n {
+                    let value = self.one_body(p, q);
+                    if value.abs() > 1e-14 {
+                        op.add_term(
+                            Complex::new(value, 0.),
+                            vec![Ladder::Creation(p + s), Ladder::Annihilation(q + s)],
+                        );
+                    }
+                }
+            }
+        }
+        for s in [0, n] {
+            for t in [0, n] {
+                for p in 0..n {
+                    for q in 0..n {
+                        for r in 0..n {
+                            for u in 0..n {
+                                let value = self.two_body(p, q, r, u);
+                                if value.abs() < 1e-14 || (p + s == r + t) || (q + s == u + t) {
+                                    continue;
+                                }
+                                op.add_term(
+                                    Complex::new(0.5 * value, 0.),
+                                    vec![
+                                        Ladder::Creation(p + s),
+                                        Ladder::Creation(r + t),
+                                        Ladder::Annihilation(u + t),
+                                        Ladder::Annihilation(q + s),
+                                    ],
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        op
+    }
+
+    pub fn qubit_hamiltonian(&self, mapping: Mapping) -> SparsePauliOp {
+        mapping.map(&self.hamiltonian())
+    }
+
+    // total electron number sum_p n_p on the spin orbitals
+    pub fn number_operator(&self) -> FermionicOp {
+        let size = 2 * self.num_orbitals;
+        (0..size).fold(FermionicOp::new(size), |acc, mode| {
+            &acc + &FermionicOp::number(mode, size)
+        })
+    }
+
+    // occupation bitstring of the hartree-fock determinant, the lowest orbitals of each
+    // spin being occupied
+    pub fn hartree_fock_occupations(&self) -> usize {
+        let (alpha, beta) = self.num_particles();
+        ((1 << alpha) - 1) | (((1 << beta) - 1) << self.num_orbitals)
+    }
+
+    pub fn hartree_fock_energy(&self) -> f64 {
+        let (alpha, beta) = self.num_particles();
+        let occupied = (0..alpha)
+            .map(|p| (p, 0))
+            .chain((0..beta).map(|p| (p, 1)))
+            .collect::<Vec<_>>();
+        let mut energy = self.core_energy;
+        for &(p, spin) in occupied.iter() {
+            energy += self.one_body(p, p);
+            for &(q, other) in occupied.iter() {
+                energy += 0.5 * self.two_body(p, p, q, q);
+                if spin == other {
+                    energy -= 0.5 * self.two_body(p, q, q, p);
+                }
+            }
+        }
+        energy
+    }
+
+    // the FCIDUMP text with the symmetry-unique integrals
+    pub fn to_fcidump(&self) -> String {
+        let n = self.num_orbitals;
+        let mut text = format!(
+            " &FCI NORB={},NELEC={},MS2={},\n  ORBSYM={}\n  ISYM=1,\n &END\n",
+            n,
+            self.num_electrons,
+            self.ms2,
+            "1,".repeat(n)
+        );
+        let pair = |p: usize, q: usize| p * (p + 1) / 2 + q;
+        for p in 0..n {
+            for q in 0..=p {
+                for r in 0..n {
+                    for s in 0..=r {
+                        let value = self.two_body(p, q, r, s);
+                        if pair(p, q) >= pair(r, s) && value != 0. {
+                            text += &format!(
+                                "{:24.16E} {} {} {} {}\n",
+                                value,
+                                p + 1,
+                                q + 1,
+                                r + 1,
+                                s + 1
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        for p in 0..n {
+            for q in 0..=p {
+                let value = self.one_body(p, q);
+                if value != 0. {
+                    text += &format!("{:24.16E} {} {} 0 0\n", value, p + 1, q + 1);
+                }
+            }
+        }
+        text += &format!("{:24.16E} 0 0 0 0\n", self.core_energy);
+        text
+    }
+}
+
+// the value of a header field, which fails on a value out of the range of T such as a
+// negative NORB
+fn header_field<T: FromStr>(values: &[(String, String)], name: &str) -> Result<Option<T>, String> {
+    match values.iter().find(|(key, _)| key == name) {
+        Some((_, value)) => value
+            .parse::<T>()
+            .map(Some)
+            .map_err(|_| format!("invalid {} '{}'", name, value)),
+        None => Ok(None),
+    }
+}
+
+impl FromStr for ElectronicIntegrals {
+    type Err = String;
+
+    // the namelist header "&FCI NORB=.., NELEC=.., MS2=.., .. &END" (or "/") followed by
+    // lines "value i j k l": two electron integrals (ij|kl), one electron integrals
+    // h_ij with k = l = 0 and the core energy with i = j = k = l = 0. indices start at 1
+    // and lines with only i nonzero (orbital energies) are ignored
+    fn from_str(text: &str) -> Result<ElectronicIntegrals, String> {
+        // ascii only, so that the offsets found in upper also hold in text
+        let upper = text.to_ascii_uppercase();
+        let start = upper
+            .find("&FCI")
+            .ok_or_else(|| "missing &FCI header".to_string())?;
+        let (end, length) = match (upper[start..].find("&END"), upper[start..].find('/')) {
+            (Some(e), Some(s)) if s < e => (start + s, 1),
+            (Some(e), _) => (start + e, 4),
+            (None, Some(s)) => (start + s, 1),
+            (None, None) => return Err("missing &END".to_string()),
+        };
+
+        let header = upper[start + 4..end].replace(',', " ");
+        let tokens = header.split_whitespace().collect::<Vec<_>>();
+        let mut values = vec![];
+        let mut k = 0;
+        while k < tokens.len() {
+            if let Some((key, value)) = tokens[k].split_once('=') {
+                let value = if value.is_empty() {
+                    k += 1;
+                    tokens.get(k).cloned().unwrap_or("")
+                } else {
+                    value
+                };
+                values.push((key.to_string(), value.to_string()));
+            }
+            k += 1;
+        }
+        let num_orbitals =
+            header_field::<usize>(&values, "NORB")?.ok_or_else(|| "missing NORB".to_string())?;
+        let num_electrons =
+            header_field::<usize>(&values, "NELEC")?.ok_or_else(|| "missing NELEC".to_string())?;
+        let ms2 = header_field::<i32>(&values, "MS2")?.unwrap_or(0);
+        if num_orbitals == 0 {
+            return Err("NORB should be positive".to_string());
+        }
+        if num_electrons > 2 * num_orbitals
+            || ms2.unsigned_abs() as usize > num_electrons
+            || num_electrons % 2 != ms2.unsigned_abs() as usize % 2
+        {
+            return Err(format!(
+                "inconsistent NORB={}, NELEC={}, MS2={}",
+                num_orbitals, num_electrons, ms2
+            ));
+        }
+
+        let mut integrals = ElectronicIntegrals::new(num_orbitals, num_electrons, ms2);
+        for line in text[end + length..].lines() {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            if fields.is_empty() {
+                continue;
+            }
+            if fields.len() != 5 {
+                return Err(format!("expected 'value i j k l', found '{}'", line.trim()));
+            }
+            let value = fields[0]
+                .replace(['D', 'd'], "E")
+                .parse::<f64>()
+                .map_err(|_| format!("invalid integral '{}'", fields[0]))?;
+            let mut indices = [0; 4];
+            for (index, field) in indices.iter_mut().zip(fields[1..].iter()) {
+                *index = field
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid index '{}'", field))?;
+                if *index > num_orbitals {
+                    return Err(format!("orbital index {} out of range", index));
+                }
+            }
+            match indices {
+                [0, 0, 0, 0] => integrals.core_energy = value,
+                [i, j, 0, 0] if i > 0 && j > 0 => integrals.set_one_body(i - 1, j - 1, value),
+                [i, j, k, l] if i > 0 && j > 0 && k > 0 && l > 0 => {
+                    integrals.set_two_body(i - 1, j - 1, k - 1, l - 1, value)
+                }
+                [_, 0, 0, 0] => {}
+                _ => return Err(format!("invalid index combination in '{}'", line.trim())),
+            }
+        }
+        Ok(integrals)
+    }
+}
+
+#[cfg(test)]
+use crate::algebra::krylov::Lanczos;
+#[cfg(test)]
+use crate::algorithm::ansatz::uccsd;
+#[cfg(test)]
+use crate::algorithm::vqe::VQE;
+#[cfg(test)]
+use crate::operator::tapering::Z2Symmetries;
+#[cfg(test)]
+use crate::optimizer::lbfgs::LBFGS;
+
+// STO-3G integrals in the canonical hartree-fock orbitals of H2 at 0.7414 angstrom and
+// LiH at 1.45 angstrom
+#[cfg(test)]
+const H2_FCIDUMP: &str = include_str!("data/h2_sto3g.fcidump");
+#[cfg(test)]
+const LIH_FCIDUMP: &str = include_str!("data/lih_sto3g.fcidump");
+
+// the lowest eigenvalue with the electron number of the molecule, other electron numbers
+// being pushed up by the penalty (N - N_0)^2, in the symmetry sector of the hartree-fock
+// determinant
+#[cfg(test)]
+fn ground_energy(integrals: &ElectronicIntegrals, mapping: Mapping) -> f64 {
+    let hamiltonian = integrals.qubit_hamiltonian(mapping);
+    let size = hamiltonian.size();
+    let shifted = &mapping.map(&integrals.number_operator())
+        - &(&SparsePauliOp::identity(size) * integrals.num_electrons() as f64);
+    let penalized = (&hamiltonian + &shifted.compose(&shifted)).simplify();
+    let symmetries = Z2Symmetries::find(&penalized);
+    let sector = symmetries.sector(mapping.encode(integrals.hartree_fock_occupations(), size));
+    let tapered = symmetries.taper(&penalized, &sector);
+    Lanczos::default().ground_state(&tapered.to_sparse()).0
+}
+
+#[test]
+fn fcidump_parse_test() {
+    let integrals: ElectronicIntegrals = H2_FCIDUMP.parse().unwrap();
+    assert_eq!(2, integrals.num_orbitals());
+    assert_eq!((1, 1), integrals.num_particles());
+    assert!((integrals.core_energy() - 0.7137539936876182).abs() < 1e-15);
+    assert_eq!(
+        integrals.two_body(1, 0, 1, 0),
+        integrals.two_body(0, 1, 1, 0)
+    );
+    assert_eq!(
+        integrals.two_body(0, 0, 1, 1),
+        integrals.two_body(1, 1, 0, 0)
+    );
+    assert_eq!(integrals, integrals.to_fcidump().parse().unwrap());
+
+    // namelist variations and fortran exponents
+    let text = "&FCI NORB= 1, NELEC=1, MS2=1, ORBSYM=1, /\n 1.5D-1 1 1 1 1\n-2.0 1 1 0 0\n";
+    let integrals: ElectronicIntegrals = text.parse().unwrap();
+    assert_eq!((1, 0), integrals.num_particles());
+    assert_eq!(0.15, integrals.two_body(0, 0, 0, 0));
+    assert_eq!(-2., integrals.hartree_fock_energy());
+    // a non-ascii title in the header, whose full uppercase is shorter in bytes
+    let titled = text.replace("ORBSYM=1,", "ORBSYM=1, TITLE='Kırıkkale',");
+    assert_eq!(integrals, titled.parse().unwrap());
+
+    assert!("NORB=2".parse::<ElectronicIntegrals>().is_err());
+    assert!("&FCI NELEC=2 &END".parse::<ElectronicIntegrals>().is_err());
+    assert!("&FCI NORB=1,NELEC=2 &END\n 1.0 2 1 0 0"
+        .parse::<ElectronicIntegrals>()
+        .is_err());
+    assert!("&FCI NORB=1,NELEC=3 &END"
+        .parse::<ElectronicIntegrals>()
+        .is_err());
+    // negative or zero sizes are rejected instead of wrapping around
+    assert!("&FCI NORB=-2,NELEC=2 &END"
+        .parse::<ElectronicIntegrals>()
+        .is_err());
+    assert!("&FCI NORB=2,NELEC=-1 &END"
+        .parse::<ElectronicIntegrals>()
+        .is_err());
+    assert!("&FCI NORB=0,NELEC=0 &END"
+        .parse::<ElectronicIntegrals>()
+        .is_err());
+}
+
+#[test]
+fn h2_test() {
+    // reference energies of H2/STO-3G at 0.7414 angstrom
+    let integrals: ElectronicIntegrals = H2_FCIDUMP.parse().unwrap();
+    let hartree_fock = -1.1166843870;
+    let full_ci = -1.1372701747;
+    assert!((integrals.hartree_fock_energy() - hartree_fock).abs() < 1e-8);
+    for mapping in [
+        Mapping::JordanWigner,
+        Mapping::Parity,
+        Mapping::BravyiKitaev,
+    ] {
+        assert!((ground_energy(&integrals, mapping) - full_ci).abs() < 1e-8);
+    }
+
+    // the hartree-fock determinant is the expectation value at zero uccsd amplitudes and
+    // vqe recovers the full ci energy
+    let hamiltonian = integrals.qubit_hamiltonian(Mapping::JordanWigner);
+    let ansatz = uccsd(2, integrals.num_particles());
+    let optimizer = LBFGS::new(10);
+    let vqe = VQE::new(&ansatz, &optimizer);
+    let result = vqe.compute_minimum_eigenvalue(&hamiltonian, &[0.; 3]);
+    assert!((result.energy - full_ci).abs() < 1e-6);
+    let diagonal = hamiltonian.to_matrix()[[0b0101, 0b0101]].re;
+    assert_eq!(0b0101, integrals.hartree_fock_occupations());
+    assert!((diagonal - hartree_fock).abs() < 1e-8);
+}
+
+#[test]
+fn lih_test() {
+    // LiH/STO-3G at 1.45 angstrom in 12 spin orbitals, the reference energies being
+    // those of the self-consistent field run that produced the integrals and of a full ci
+    // in the same orbitals
+    let integrals: ElectronicIntegrals = LIH_FCIDUMP.parse().unwrap();
+    assert_eq!(6, integrals.num_orbitals());
+    assert_eq!(4, integrals.num_electrons());
+    assert!((integrals.hartree_fock_energy() - -7.8625677855).abs() < 1e-8);
+    for mapping in [
+        Mapping::JordanWigner,
+        Mapping::Parity,
+        Mapping::BravyiKitaev,
+    ] {
+        assert!((ground_energy(&integrals, mapping) - -7.8809823146).abs() < 1e-8);
+    }
+}
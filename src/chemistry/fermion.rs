@@ -0,0 +1,354 @@
+use num::complex::Complex;
+use num::One;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+const SIMPLIFY_TOLERANCE: f64 = 1e-12;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Ladder {
+    Creation(usize),
+    Annihilation(usize),
+}
+
+impl Ladder {
+    pub fn mode(&self) -> usize {
+        match self {
+            Ladder::Creation(mode) | Ladder::Annihilation(mode) => *mode,
+        }
+    }
+
+    pub fn is_creation(&self) -> bool {
+        matches!(self, Ladder::Creation(_))
+    }
+
+    pub fn adjoint(&self) -> Ladder {
+        match *self {
+            Ladder::Creation(mode) => Ladder::Annihilation(mode),
+            Ladder::Annihilation(mode) => Ladder::Creation(mode),
+        }
+    }
+}
+
+impl fmt::Display for Ladder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Ladder::Creation(mode) => write!(f, "+_{}", mode),
+            Ladder::Annihilation(mode) => write!(f, "-_{}", mode),
+        }
+    }
+}
+
+// a sum of products of creation and annihilation operators on `num_modes` modes, each
+// product being applied from right to left like the operators it is made of
+#[derive(Debug, Clone, PartialEq)]
+pub struct FermionicOp {
+    num_modes: usize,
+    terms: Vec<(Complex<f64>, Vec<Ladder>)>,
+}
+
+impl FermionicOp {
+    pub fn new(num_modes: usize) -> FermionicOp {
+        FermionicOp {
+            num_modes,
+            terms: vec![],
+        }
+    }
+
+    pub fn identity(num_modes: usize) -> FermionicOp {
+        FermionicOp::from_terms(num_modes, vec![(Complex::one(), vec![])])
+    }
+
+    pub fn from_terms(num_modes: usize, terms: Vec<(Complex<f64>, Vec<Ladder>)>) -> FermionicOp {
+        for (_, ladders) in terms.iter() {
+            assert!(ladders.iter().all(|l| l.mode() < num_modes));
+        }
+        FermionicOp { num_modes, terms }
+    }
+
+    pub fn creation(mode: usize, num_modes: usize) -> FermionicOp {
+        FermionicOp::from_terms(
+            num_modes,
+            vec![(Complex::one(), vec![Ladder::Creation(mode)])],
+        )
+    }
+
+    pub fn annihilation(mode: usize, num_modes: usize) -> FermionicOp {
+        FermionicOp::from_terms(
+            num_modes,
+            vec![(Complex::one(), vec![Ladder::Annihilation(mode)])],
+        )
+    }
+
+    // n_i = a+_i a_i
+    pub fn number(mode: usize, num_modes: usize) -> FermionicOp {
+        FermionicOp::from_terms(
+            num_modes,
+            vec![(
+                Complex::one(),
+                vec![Ladder::Creation(mode), Ladder::Annihilation(mode)],
+            )],
+        )
+    }
+
+    pub fn num_modes(&self) -> usize {
+        self.num_modes
+    }
+
+    pub fn terms(&self) -> &[(Complex<f64>, Vec<Ladder>)] {
+        &self.terms
+    }
+
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn add_term(&mut self, coefficient: Complex<f64>, ladders: Vec<Ladder>) {
+        assert!(ladders.iter().all(|l| l.mode() < self.num_modes));
+        self.terms.push((coefficient, ladders));
+    }
+
+    // merges identical products and drops terms with negligible coefficients; products
+    // that only differ by their order are merged by `normal_order`
+    pub fn simplify(&self) -> FermionicOp {
+        let mut terms: Vec<(Complex<f64>, Vec<Ladder>)> = vec![];
+        for (coefficient, ladders) in self.terms.iter() {
+            match terms.iter_mut().find(|(_, l)| l == ladders) {
+                Some((c, _)) => *c += coefficient,
+                None => terms.push((*coefficient, ladders.clone())),
+            }
+        }
+        terms.retain(|(c, _)| c.norm() > SIMPLIFY_TOLERANCE);
+        FermionicOp {
+            num_modes: self.num_modes,
+            terms,
+        }
+    }
+
+    // rewrites every product with the creation operators left of the annihilation
+    // operators and both in descending mode order, using a_i a+_j = delta_ij - a+_j a_i
+    // and a_i a_j = -a_j a_i; products with a repeated creation or annihilation vanish
+    pub fn normal_order(&self) -> FermionicOp {
+        let mut result = FermionicOp::new(self.num_modes);
+        let mut pending = self.terms.clone();
+        while let Some((coefficient, mut ladders)) = pending.pop() {
+            let position = (0..ladders.len().saturating_sub(1))
+                .find(|&k| !in_order(&ladders[k], &ladders[k + 1]));
+            let Some(k) = position else {
+                result.add_term(coefficient, ladders);
+                continue;
+            };
+            let (left, right) = (ladders[k], ladders[k + 1]);
+            if left.is_creation() == right.is_creation() && left.mode() == right.mode() {
+                continue;
+            }
+            if !left.is_creation() && right.is_creation() && left.mode() == right.mode() {
+                let mut contracted = ladders.clone();
+                contracted.drain(k..k + 2);
+                pending.push((coefficient, contracted));
+            }
+            ladders.swap(k, k + 1);
+            pending.push((-coefficient, ladders));
+        }
+        result.simplify()
+    }
+
+    pub fn adjoint(&self) -> FermionicOp {
+        FermionicOp {
+            num_modes: self.num_modes,
+            terms: self
+                .terms
+                .iter()
+                .map(|(c, ladders)| {
+                    (
+                        c.conj(),
+                        ladders.iter().rev().map(|l| l.adjoint()).collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_hermitian(&self) -> bool {
+        (self - &self.adjoint()).normal_order().is_empty()
+    }
+
+    pub fn compose(&self, other: &FermionicOp) -> FermionicOp {
+        assert_eq!(self.num_modes, other.num_modes);
+        let mut result = FermionicOp::new(self.num_modes);
+        for (c1, l1) in self.terms.iter() {
+            for (c2, l2) in other.terms.iter() {
+                let mut ladders = l1.clone();
+                ladders.extend(l2.iter().cloned());
+                result.add_term(c1 * c2, ladders);
+            }
+        }
+        result
+    }
+
+    // {A, B} = AB + BA in normal order
+    pub fn anticommutator(&self, other: &FermionicOp) -> FermionicOp {
+        (&self.compose(other) + &other.compose(self)).normal_order()
+    }
+}
+
+// creation operators come first, and each kind is sorted by descending mode
+fn in_order(left: &Ladder, right: &Ladder) -> bool {
+    match (left.is_creation(), right.is_creation()) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => left.mode() > right.mode(),
+    }
+}
+
+impl Add<&FermionicOp> for &FermionicOp {
+    type Output = FermionicOp;
+
+    fn add(self, other: &FermionicOp) -> FermionicOp {
+        assert_eq!(self.num_modes, other.num_modes);
+        let mut terms = self.terms.clone();
+        terms.extend(other.terms.iter().cloned());
+        FermionicOp {
+            num_modes: self.num_modes,
+            terms,
+        }
+    }
+}
+
+impl Sub<&FermionicOp> for &FermionicOp {
+    type Output = FermionicOp;
+
+    fn sub(self, other: &FermionicOp) -> FermionicOp {
+        self + &(-other)
+    }
+}
+
+impl Neg for &FermionicOp {
+    type Output = FermionicOp;
+
+    fn neg(self) -> FermionicOp {
+        self * Complex::new(-1., 0.)
+    }
+}
+
+impl Mul<&FermionicOp> for &FermionicOp {
+    type Output = FermionicOp;
+
+    fn mul(self, other: &FermionicOp) -> FermionicOp {
+        self.compose(other)
+    }
+}
+
+impl Mul<Complex<f64>> for &FermionicOp {
+    type Output = FermionicOp;
+
+    fn mul(self, scalar: Complex<f64>) -> FermionicOp {
+        FermionicOp {
+            num_modes: self.num_modes,
+            terms: self
+                .terms
+                .iter()
+                .map(|(c, ladders)| (c * scalar, ladders.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl Mul<f64> for &FermionicOp {
+    type Output = FermionicOp;
+
+    fn mul(self, scalar: f64) -> FermionicOp {
+        self * Complex::new(scalar, 0.)
+    }
+}
+
+impl fmt::Display for FermionicOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+        let terms = self
+            .terms
+            .iter()
+            .map(|(c, ladders)| {
+                let labels = ladders.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+                format!("({})*{}", c, labels.join(" "))
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", terms.join(" + "))
+    }
+}
+
+#[test]
+fn anticommutation_test() {
+    let n = 3;
+    for i in 0..n {
+        for j in 0..n {
+            let a = FermionicOp::annihilation(i, n);
+            let b = FermionicOp::creation(j, n);
+            let anticommutator = a.anticommutator(&b);
+            if i == j {
+                assert_eq!(FermionicOp::identity(n), anticommutator);
+            } else {
+                assert!(anticommutator.is_empty());
+            }
+            assert!(a
+                .anticommutator(&FermionicOp::annihilation(j, n))
+                .is_empty());
+        }
+    }
+}
+
+#[test]
+fn normal_order_test() {
+    // a_0 a+_1 a_1 a+_0 = n_1 (1 - n_0) = a+_1 a_1 + a+_1 a+_0 a_1 a_0
+    let product = FermionicOp::from_terms(
+        2,
+        vec![(
+            Complex::one(),
+            vec![
+                Ladder::Annihilation(0),
+                Ladder::Creation(1),
+                Ladder::Annihilation(1),
+                Ladder::Creation(0),
+            ],
+        )],
+    );
+    let ordered = product.normal_order();
+    assert_eq!(2, ordered.len());
+    let coefficient = |ladders: &[Ladder]| {
+        ordered
+            .terms()
+            .iter()
+            .find(|(_, l)| l == ladders)
+            .map(|(c, _)| *c)
+    };
+    assert_eq!(
+        Some(Complex::one()),
+        coefficient(&[Ladder::Creation(1), Ladder::Annihilation(1)])
+    );
+    assert_eq!(
+        Some(Complex::one()),
+        coefficient(&[
+            Ladder::Creation(1),
+            Ladder::Creation(0),
+            Ladder::Annihilation(1),
+            Ladder::Annihilation(0)
+        ])
+    );
+
+    // a+_0 a+_0 = 0 and n_0 n_0 = n_0
+    let creation = FermionicOp::creation(0, 1);
+    assert!((&creation * &creation).normal_order().is_empty());
+    let number = FermionicOp::number(0, 1);
+    assert_eq!(number, (&number * &number).normal_order());
+
+    let hopping = &(&FermionicOp::creation(0, 2) * &FermionicOp::annihilation(1, 2))
+        + &(&FermionicOp::creation(1, 2) * &FermionicOp::annihilation(0, 2));
+    assert!(hopping.is_hermitian());
+    assert!(!(&hopping * Complex::new(0., 1.)).is_hermitian());
+}
@@ -0,0 +1,217 @@
+use crate::chemistry::fermion::{FermionicOp, Ladder};
+use crate::operator::pauli::{Pauli, PauliString, SparsePauliOp};
+use num::complex::Complex;
+
+// fermion to qubit encodings in which qubit k stores the parity of the occupations
+// selected by row k of a binary matrix A, b = A n (mod 2):
+// jordan-wigner stores the occupations themselves, the parity mapping the parities of
+// the prefixes n_0..n_k and bravyi-kitaev the partial sums of a fenwick tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mapping {
+    JordanWigner,
+    Parity,
+    BravyiKitaev,
+}
+
+impl Mapping {
+    // row k of the encoding matrix as a bitmask over the modes
+    pub fn encoding(&self, num_modes: usize) -> Vec<usize> {
+        (0..num_modes)
+            .map(|k| match self {
+                Mapping::JordanWigner => 1 << k,
+                Mapping::Parity => (1 << (k + 1)) - 1,
+                // modes (k & (k + 1))..=k
+                Mapping::BravyiKitaev => (1 << (k + 1)) - (1 << (k & (k + 1))),
+            })
+            .collect()
+    }
+
+    // the qubit basis state encoding the occupation number bitstring
+    pub fn encode(&self, occupations: usize, num_modes: usize) -> usize {
+        self.encoding(num_modes)
+            .iter()
+            .enumerate()
+            .map(|(k, row)| ((row & occupations).count_ones() as usize % 2) << k)
+            .sum()
+    }
+
+    // a+_j = X_F (1 + Z_C) / 2 Z_P where F are the qubits that store n_j, (-1)^n_j is the
+    // parity of the qubits C and (-1)^(n_0 + .. + n_j-1) that of the qubits P
+    fn creation(&self, mode: usize, num_modes: usize) -> SparsePauliOp {
+        let encoding = self.encoding(num_modes);
+        let inverse = inverse(&encoding);
+        let flip = (0..num_modes)
+            .filter(|&k| encoding[k] >> mode & 1 == 1)
+            .map(|k| (k, Pauli::X))
+            .collect::<Vec<_>>();
+        let occupation = (0..num_modes)
+            .filter(|&k| inverse[mode] >> k & 1 == 1)
+            .map(|k| (k, Pauli::Z))
+            .collect::<Vec<_>>();
+        let prefix = inverse[..mode].iter().fold(0, |acc, row| acc ^ row);
+        let parity = (0..num_modes)
+            .filter(|&k| prefix >> k & 1 == 1)
+            .map(|k| (k, Pauli::Z))
+            .collect::<Vec<_>>();
+
+        let string = |paulis: &[(usize, Pauli)]| PauliString::from_sparse(num_modes, paulis);
+        let half = Complex::new(0.5, 0.);
+        let projector = SparsePauliOp::from_terms(
+            num_modes,
+            vec![
+                (half, PauliString::identity(num_modes)),
+                (half, string(&occupation)),
+            ],
+        );
+        SparsePauliOp::from_pauli_string(Complex::new(1., 0.), string(&flip))
+            .compose(&projector)
+            .compose(&SparsePauliOp::from_pauli_string(
+                Complex::new(1., 0.),
+                string(&parity),
+            ))
+    }
+
+    pub fn map_ladder(&self, ladder: &Ladder, num_modes: usize) -> SparsePauliOp {
+        match *ladder {
+            Ladder::Creation(mode) => self.creation(mode, num_modes),
+            Ladder::Annihilation(mode) => self.creation(mode, num_modes).adjoint(),
+        }
+    }
+
+    pub fn map(&self, op: &FermionicOp) -> SparsePauliOp {
+        let num_modes = op.num_modes();
+        let ladders = (0..num_modes)
+            .map(|mode| {
+                (
+                    self.map_ladder(&Ladder::Creation(mode), num_modes),
+                    self.map_ladder(&Ladder::Annihilation(mode), num_modes),
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut result = SparsePauliOp::new(num_modes);
+        for (coefficient, product) in op.terms().iter() {
+            let mut term = &SparsePauliOp::identity(num_modes) * *coefficient;
+            for ladder in product.iter() {
+                let (creation, annihilation) = &ladders[ladder.mode()];
+                term = term.compose(if ladder.is_creation() {
+                    creation
+                } else {
+                    annihilation
+                });
+            }
+            for (c, pauli) in term.terms().iter() {
+                result.add_term(*c, pauli.clone());
+            }
+        }
+        result.simplify()
+    }
+}
+
+// inverse of an invertible binary matrix given by its rows, by gauss-jordan elimination
+fn inverse(rows: &[usize]) -> Vec<usize> {
+    let n = rows.len();
+    let mut a = rows.to_vec();
+    let mut b = (0..n).map(|k| 1 << k).collect::<Vec<usize>>();
+    for column in 0..n {
+        let pivot = (column..n)
+            .find(|&r| a[r] >> column & 1 == 1)
+            .expect("the encoding matrix should be invertible");
+        a.swap(column, pivot);
+        b.swap(column, pivot);
+        for r in 0..n {
+            if r != column && a[r] >> column & 1 == 1 {
+                a[r] ^= a[column];
+                b[r] ^= b[column];
+            }
+        }
+    }
+    b
+}
+
+#[cfg(test)]
+use crate::algebra::linalg::eigh;
+#[cfg(test)]
+use crate::operator::lattice::{Boundary, Lattice};
+#[cfg(test)]
+use crate::operator::model::hubbard;
+
+#[cfg(test)]
+const MAPPINGS: [Mapping; 3] = [
+    Mapping::JordanWigner,
+    Mapping::Parity,
+    Mapping::BravyiKitaev,
+];
+
+#[test]
+fn mapping_anticommutation_test() {
+    let n = 5;
+    for mapping in MAPPINGS {
+        let a = |mode| mapping.map(&FermionicOp::annihilation(mode, n));
+        let c = |mode| mapping.map(&FermionicOp::creation(mode, n));
+        for i in 0..n {
+            for j in 0..n {
+                let anticommutator = (&a(i).compose(&c(j)) + &c(j).compose(&a(i))).simplify();
+                if i == j {
+                    assert_eq!(SparsePauliOp::identity(n), anticommutator);
+                } else {
+                    assert!(anticommutator.is_empty());
+                }
+                let anticommutator = (&a(i).compose(&a(j)) + &a(j).compose(&a(i))).simplify();
+                assert!(anticommutator.is_empty());
+            }
+        }
+    }
+
+    // the jordan-wigner image of a_j is (X_j + i Y_j) / 2 behind a Z string
+    let a2 = Mapping::JordanWigner.map(&FermionicOp::annihilation(2, 3));
+    let expected: SparsePauliOp = "0.5*Z0 Z1 X2 + 0.5i*Z0 Z1 Y2".parse().unwrap();
+    assert!((&a2 - &expected).simplify().is_empty());
+}
+
+#[test]
+fn mapping_number_test() {
+    // the number operators are diagonal and count the encoded occupations
+    let n = 6;
+    for mapping in MAPPINGS {
+        for occupations in [0b101101, 0b010011, 0b111111] {
+            let index = mapping.encode(occupations, n);
+            for mode in 0..n {
+                let number = mapping.map(&FermionicOp::number(mode, n));
+                let value = number.to_matrix()[[index, index]];
+                assert!((value.re - (occupations >> mode & 1) as f64).abs() < 1e-12);
+            }
+        }
+    }
+    assert_eq!(0b000011, Mapping::Parity.encode(0b000101, 6));
+    // qubit 3 of bravyi-kitaev stores n_0 + n_1 + n_2 + n_3 and qubit 2 only n_2
+    assert_eq!(0b0111, Mapping::BravyiKitaev.encode(0b0101, 4));
+}
+
+#[test]
+fn mapping_hubbard_test() {
+    // the hubbard dimer with up spins on modes 0, 1 and down spins on modes 2, 3
+    let (t, u) = (1., 3.);
+    let mut op = FermionicOp::new(4);
+    for spin in [0, 2] {
+        for (i, j) in [(spin, spin + 1), (spin + 1, spin)] {
+            op = &op + &(&(&FermionicOp::creation(i, 4) * &FermionicOp::annihilation(j, 4)) * -t);
+        }
+    }
+    for site in 0..2 {
+        op = &op + &(&(&FermionicOp::number(site, 4) * &FermionicOp::number(site + 2, 4)) * u);
+    }
+    assert!(op.is_hermitian());
+
+    let lattice = Lattice::chain(2, Boundary::Open);
+    let expected = hubbard(&lattice, t, u, 0.);
+    let jordan_wigner = Mapping::JordanWigner.map(&op);
+    assert!((&jordan_wigner - &expected).simplify().is_empty());
+
+    let (spectrum, _) = eigh(&expected.to_matrix());
+    for mapping in [Mapping::Parity, Mapping::BravyiKitaev] {
+        let (values, _) = eigh(&mapping.map(&op).to_matrix());
+        for (a, b) in values.iter().zip(spectrum.iter()) {
+            assert!((a - b).abs() < 1e-10);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod fcidump;
+pub mod fermion;
+pub mod mapping;
@@ -1,21 +1,38 @@
 use crate::algebra::matrix::{indices, masks};
-use crate::circuit;
+use crate::circuit::parameter::ParameterizedCircuit;
+use crate::circuit::qft::QFT;
 use crate::gate::base_gates::{DoubleGate, SingleGate, TripleGate};
 use crate::gate::gate::Gate;
+use crate::operator::pauli::PauliString;
+use crate::qubit::qubit::{qubit_indices, AsQubit, Clbit, Qubit};
+use crate::qubit::register::{ClassicalRegister, QuantumRegister};
 use crate::state::state::State;
 use crate::validate::validate::{
     ctrl_qubit_should_be_different_from_target_qubit, qubit_should_be_less_than_circuit_size,
 };
+use num::complex::Complex;
+#[cfg(test)]
 use num::complex::ComplexFloat;
-use num::{complex::Complex, One, Zero};
+#[cfg(test)]
+use num::{One, Zero};
 use rand;
+use std::collections::HashMap;
+#[cfg(test)]
 use std::f64::consts::PI;
+#[cfg(test)]
 use std::f64::EPSILON;
 use std::fmt;
 
 pub struct QuantumCircuit {
     pub state: State,
     size: usize,
+    quantum_registers: Vec<QuantumRegister>,
+    classical_registers: Vec<ClassicalRegister>,
+    // the classical memory written by measure_into
+    clbits: Vec<usize>,
+    // clean ancillas in |0> waiting for reuse, and the ancillas handed out
+    free_ancillas: Vec<usize>,
+    used_ancillas: Vec<usize>,
 }
 
 pub struct MeasurementResult {
@@ -25,6 +42,7 @@ pub struct MeasurementResult {
     qubit: usize,
 }
 
+#[allow(dead_code)]
 enum Basic {
     Zero,
     One,
@@ -39,6 +57,14 @@ impl MeasurementResult {
             qubit,
         }
     }
+
+    pub fn zeros(&self) -> usize {
+        self.zero
+    }
+
+    pub fn ones(&self) -> usize {
+        self.one
+    }
 }
 
 impl fmt::Display for MeasurementResult {
@@ -56,18 +82,205 @@ impl QuantumCircuit {
         QuantumCircuit {
             state: State::new(size),
             size,
+            quantum_registers: vec![],
+            classical_registers: vec![],
+            clbits: vec![],
+            free_ancillas: vec![],
+            used_ancillas: vec![],
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    // the indices of new qubits in |0> added above the existing ones
+    pub fn add_qubits(&mut self, num_qubits: usize) -> Vec<usize> {
+        self.state.add_qubits(num_qubits);
+        self.size += num_qubits;
+        (self.size - num_qubits..self.size).collect()
+    }
+
+    fn name_should_be_unused(&self, name: &str) {
+        assert!(
+            self.quantum_registers.iter().all(|r| r.name() != name)
+                && self.classical_registers.iter().all(|r| r.name() != name),
+            "the register {} already exists",
+            name
+        );
+    }
+
+    // a register of new qubits in |0>
+    pub fn add_register(&mut self, name: &str, num_qubits: usize) -> QuantumRegister {
+        self.name_should_be_unused(name);
+        let register = QuantumRegister::new(name, &self.add_qubits(num_qubits));
+        self.quantum_registers.push(register.clone());
+        register
+    }
+
+    // a register of new classical bits set to 0
+    pub fn add_classical_register(&mut self, name: &str, num_bits: usize) -> ClassicalRegister {
+        self.name_should_be_unused(name);
+        let start = self.clbits.len();
+        self.clbits.resize(start + num_bits, 0);
+        let register = ClassicalRegister::new(name, &(start..start + num_bits).collect::<Vec<_>>());
+        self.classical_registers.push(register.clone());
+        register
+    }
+
+    pub fn quantum_register(&self, name: &str) -> Option<&QuantumRegister> {
+        self.quantum_registers.iter().find(|r| r.name() == name)
+    }
+
+    // the register of the circuit the qubit was created in
+    pub fn register_of(&self, qubit: Qubit) -> Option<&QuantumRegister> {
+        self.quantum_registers.iter().find(|r| r.owns(qubit))
+    }
+
+    pub fn classical_register(&self, name: &str) -> Option<&ClassicalRegister> {
+        self.classical_registers.iter().find(|r| r.name() == name)
+    }
+
+    // clean ancillas in |0>, reusing released ones before adding qubits
+    pub fn allocate_ancillas(&mut self, num_ancillas: usize) -> QuantumRegister {
+        let reused = num_ancillas.min(self.free_ancillas.len());
+        let mut indices = self
+            .free_ancillas
+            .split_off(self.free_ancillas.len() - reused);
+        indices.extend(self.add_qubits(num_ancillas - reused));
+        self.used_ancillas.extend_from_slice(&indices);
+        QuantumRegister::new("ancilla", &indices)
+    }
+
+    // dirty ancillas in an unknown state, taken from the qubits outside the busy ones,
+    // the ancillas in use and the free ones, which allocate_ancillas may hand out while
+    // they are borrowed. they should be restored before release_borrowed
+    pub fn borrow_ancillas(&mut self, num_ancillas: usize, busy: &[usize]) -> QuantumRegister {
+        let mut indices = (0..self.size)
+            .filter(|q| {
+                !busy.contains(q)
+                    && !self.used_ancillas.contains(q)
+                    && !self.free_ancillas.contains(q)
+            })
+            .take(num_ancillas)
+            .collect::<Vec<_>>();
+        let missing = num_ancillas - indices.len();
+        indices.extend(self.add_qubits(missing));
+        self.used_ancillas.extend_from_slice(&indices);
+        QuantumRegister::new("borrowed", &indices)
+    }
+
+    fn return_ancillas(&mut self, ancillas: &QuantumRegister) {
+        for index in ancillas.indices() {
+            let position = self
+                .used_ancillas
+                .iter()
+                .position(|&q| q == index)
+                .expect("the qubit should be an ancilla in use");
+            self.used_ancillas.swap_remove(position);
+        }
+    }
+
+    // hands clean ancillas back for reuse, checking in debug builds that they are in |0>
+    pub fn release_ancillas(&mut self, ancillas: &QuantumRegister) {
+        for index in ancillas.indices() {
+            debug_assert!(
+                self.probability_of_one(index) < 1e-10,
+                "the ancilla {} should be returned to |0>",
+                index
+            );
         }
+        self.return_ancillas(ancillas);
+        self.free_ancillas.extend(ancillas.indices());
+    }
+
+    pub fn release_borrowed(&mut self, ancillas: &QuantumRegister) {
+        self.return_ancillas(ancillas);
+    }
+
+    fn probability_of_one(&self, qubit: usize) -> f64 {
+        self.state
+            .elements
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i >> qubit & 1 == 1)
+            .map(|(_, x)| x.norm_sqr())
+            .sum()
+    }
+
+    // runs the compute block on clean ancillas, the action and the inverse of the compute
+    // block, which uncomputes the ancillas when the action only reads them. the compute
+    // block is recorded over every qubit of the circuit
+    pub fn compute_uncompute(
+        &mut self,
+        num_ancillas: usize,
+        compute: impl FnOnce(&mut ParameterizedCircuit, &QuantumRegister),
+        action: impl FnOnce(&mut QuantumCircuit, &QuantumRegister),
+    ) {
+        let ancillas = self.allocate_ancillas(num_ancillas);
+        let qubits = (0..self.size).collect::<Vec<_>>();
+        let mut block = ParameterizedCircuit::new(self.size);
+        compute(&mut block, &ancillas);
+        self.append(&block, &qubits);
+        action(self, &ancillas);
+        self.append(&block.inverse(), &qubits);
+        self.release_ancillas(&ancillas);
+    }
+
+    // measures the qubit once and stores the outcome in the classical bit
+    pub fn measure_into(&mut self, qubit: impl AsQubit, clbit: Clbit) -> usize {
+        let outcome = self.measure(qubit, 1).ones();
+        self.clbits[clbit.index] = outcome;
+        outcome
+    }
+
+    // measures qubit i of the register into bit i of the classical one
+    pub fn measure_register(
+        &mut self,
+        qubits: &QuantumRegister,
+        clbits: &ClassicalRegister,
+    ) -> usize {
+        assert_eq!(qubits.len(), clbits.len());
+        for (&qubit, &clbit) in qubits.qubits().iter().zip(clbits.clbits().iter()) {
+            self.measure_into(qubit, clbit);
+        }
+        self.read(clbits)
+    }
+
+    // the value of the classical register, its first bit the least significant
+    pub fn read(&self, clbits: &ClassicalRegister) -> usize {
+        clbits
+            .clbits()
+            .iter()
+            .enumerate()
+            .map(|(bit, clbit)| self.clbits[clbit.index] << bit)
+            .sum()
     }
 
     pub fn apply(&mut self, qubits: &[&usize], gate: &Gate) {
         // assert_eq!(self.size, gate.size());
         for qubit in qubits.iter() {
-            assert_eq!(true, qubit <= &&self.size);
+            assert!(qubit <= &&self.size);
         }
         self.state.apply(qubits, gate);
     }
 
-    pub fn measure(&mut self, qubit: usize, shots: usize) -> MeasurementResult {
+    // runs a circuit without free parameters with its qubit q on qubits[q]
+    pub fn append(&mut self, other: &ParameterizedCircuit, qubits: &[usize]) {
+        assert_eq!(other.size(), qubits.len());
+        let values = HashMap::new();
+        for instruction in other.instructions().iter() {
+            let mapped = instruction
+                .qubits()
+                .iter()
+                .map(|&q| &qubits[q])
+                .collect::<Vec<_>>();
+            self.apply(&mapped, &instruction.gate(&values));
+        }
+    }
+
+    pub fn measure(&mut self, qubit: impl AsQubit, shots: usize) -> MeasurementResult {
+        let qubit = qubit.qubit_index();
         let mut result = MeasurementResult::new(qubit, shots);
         let qubit_ref = qubit.to_owned();
         let (upper_mask, lower_mask) = masks(qubit_ref);
@@ -87,8 +300,8 @@ impl QuantumCircuit {
                 let one_prob = (1. - zero_norm).sqrt();
                 for i in 0..(self.state.elements.len() >> 1) {
                     let (ith_zero, ith_one) = indices(i, &qubit, upper_mask, lower_mask);
-                    self.state.elements[ith_zero] /= one_prob;
-                    self.state.elements[ith_one] = Complex::new(0., 0.);
+                    self.state.elements[ith_zero] = Complex::new(0., 0.);
+                    self.state.elements[ith_one] /= one_prob;
                 }
                 result.one += 1;
             }
@@ -96,65 +309,222 @@ impl QuantumCircuit {
         result
     }
 
-    pub fn H(&mut self, qubit: usize) {
+    pub fn H(&mut self, qubit: impl AsQubit) {
+        let qubit = qubit.qubit_index();
         qubit_should_be_less_than_circuit_size(&qubit, &self.size);
         self.apply(&[&qubit], &SingleGate::H());
     }
 
-    pub fn X(&mut self, qubit: usize) {
+    pub fn X(&mut self, qubit: impl AsQubit) {
+        let qubit = qubit.qubit_index();
         qubit_should_be_less_than_circuit_size(&qubit, &self.size);
         self.apply(&[&qubit], &SingleGate::X());
     }
 
-    pub fn Y(&mut self, qubit: usize) {
+    pub fn Y(&mut self, qubit: impl AsQubit) {
+        let qubit = qubit.qubit_index();
         qubit_should_be_less_than_circuit_size(&qubit, &self.size);
         self.apply(&[&qubit], &SingleGate::Y());
     }
 
-    pub fn Z(&mut self, qubit: usize) {
+    pub fn Z(&mut self, qubit: impl AsQubit) {
+        let qubit = qubit.qubit_index();
         qubit_should_be_less_than_circuit_size(&qubit, &self.size);
         self.apply(&[&qubit], &SingleGate::Z());
     }
 
-    pub fn P(&mut self, qubit: usize, theta: f64) {
+    pub fn P(&mut self, qubit: impl AsQubit, theta: f64) {
+        let qubit = qubit.qubit_index();
         qubit_should_be_less_than_circuit_size(&qubit, &self.size);
         self.apply(&[&qubit], &SingleGate::P(theta));
     }
 
-    pub fn I(&mut self, qubit: usize) {
+    pub fn S(&mut self, qubit: impl AsQubit) {
+        let qubit = qubit.qubit_index();
+        qubit_should_be_less_than_circuit_size(&qubit, &self.size);
+        self.apply(&[&qubit], &SingleGate::S());
+    }
+
+    pub fn Sdg(&mut self, qubit: impl AsQubit) {
+        let qubit = qubit.qubit_index();
+        qubit_should_be_less_than_circuit_size(&qubit, &self.size);
+        self.apply(&[&qubit], &SingleGate::Sdg());
+    }
+
+    pub fn RX(&mut self, qubit: impl AsQubit, theta: f64) {
+        let qubit = qubit.qubit_index();
+        qubit_should_be_less_than_circuit_size(&qubit, &self.size);
+        self.apply(&[&qubit], &SingleGate::RX(theta));
+    }
+
+    pub fn RY(&mut self, qubit: impl AsQubit, theta: f64) {
+        let qubit = qubit.qubit_index();
+        qubit_should_be_less_than_circuit_size(&qubit, &self.size);
+        self.apply(&[&qubit], &SingleGate::RY(theta));
+    }
+
+    pub fn RZ(&mut self, qubit: impl AsQubit, theta: f64) {
+        let qubit = qubit.qubit_index();
+        qubit_should_be_less_than_circuit_size(&qubit, &self.size);
+        self.apply(&[&qubit], &SingleGate::RZ(theta));
+    }
+
+    pub fn I(&mut self, qubit: impl AsQubit) {
+        let qubit = qubit.qubit_index();
         qubit_should_be_less_than_circuit_size(&qubit, &self.size);
         self.apply(&[&qubit], &SingleGate::I());
     }
 
-    pub fn CNOT(&mut self, ctrl_qubit: usize, target_qubit: usize) {
+    pub fn CNOT(&mut self, ctrl_qubit: impl AsQubit, target_qubit: impl AsQubit) {
+        let ctrl_qubit = ctrl_qubit.qubit_index();
+        let target_qubit = target_qubit.qubit_index();
         qubit_should_be_less_than_circuit_size(&ctrl_qubit, &self.size);
         qubit_should_be_less_than_circuit_size(&target_qubit, &self.size);
         ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
         self.apply(&[&ctrl_qubit, &target_qubit], &DoubleGate::CNOT());
     }
 
-    pub fn CZ(&mut self, ctrl_qubit: usize, target_qubit: usize) {
+    pub fn CZ(&mut self, ctrl_qubit: impl AsQubit, target_qubit: impl AsQubit) {
+        let ctrl_qubit = ctrl_qubit.qubit_index();
+        let target_qubit = target_qubit.qubit_index();
         qubit_should_be_less_than_circuit_size(&ctrl_qubit, &self.size);
         qubit_should_be_less_than_circuit_size(&target_qubit, &self.size);
         ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
         self.apply(&[&ctrl_qubit, &target_qubit], &DoubleGate::CZ());
     }
 
-    pub fn SWAP(&mut self, ctrl_qubit: usize, target_qubit: usize) {
+    pub fn SWAP(&mut self, ctrl_qubit: impl AsQubit, target_qubit: impl AsQubit) {
+        let ctrl_qubit = ctrl_qubit.qubit_index();
+        let target_qubit = target_qubit.qubit_index();
         qubit_should_be_less_than_circuit_size(&ctrl_qubit, &self.size);
         qubit_should_be_less_than_circuit_size(&target_qubit, &self.size);
         ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
         self.apply(&[&ctrl_qubit, &target_qubit], &DoubleGate::SWAP());
     }
 
-    pub fn CP(&mut self, ctrl_qubit: usize, target_qubit: usize, theta: f64) {
+    pub fn CP(&mut self, ctrl_qubit: impl AsQubit, target_qubit: impl AsQubit, theta: f64) {
+        let ctrl_qubit = ctrl_qubit.qubit_index();
+        let target_qubit = target_qubit.qubit_index();
+        qubit_should_be_less_than_circuit_size(&ctrl_qubit, &self.size);
+        qubit_should_be_less_than_circuit_size(&target_qubit, &self.size);
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
+        // diagonal, so the phase is applied to the amplitudes directly
+        self.MCP(&[ctrl_qubit], target_qubit, theta);
+    }
+
+    pub fn CRX(&mut self, ctrl_qubit: impl AsQubit, target_qubit: impl AsQubit, theta: f64) {
+        let ctrl_qubit = ctrl_qubit.qubit_index();
+        let target_qubit = target_qubit.qubit_index();
+        qubit_should_be_less_than_circuit_size(&ctrl_qubit, &self.size);
+        qubit_should_be_less_than_circuit_size(&target_qubit, &self.size);
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
+        self.apply(&[&ctrl_qubit, &target_qubit], &DoubleGate::CRX(theta));
+    }
+
+    pub fn CRY(&mut self, ctrl_qubit: impl AsQubit, target_qubit: impl AsQubit, theta: f64) {
+        let ctrl_qubit = ctrl_qubit.qubit_index();
+        let target_qubit = target_qubit.qubit_index();
+        qubit_should_be_less_than_circuit_size(&ctrl_qubit, &self.size);
+        qubit_should_be_less_than_circuit_size(&target_qubit, &self.size);
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
+        self.apply(&[&ctrl_qubit, &target_qubit], &DoubleGate::CRY(theta));
+    }
+
+    pub fn CRZ(&mut self, ctrl_qubit: impl AsQubit, target_qubit: impl AsQubit, theta: f64) {
+        let ctrl_qubit = ctrl_qubit.qubit_index();
+        let target_qubit = target_qubit.qubit_index();
         qubit_should_be_less_than_circuit_size(&ctrl_qubit, &self.size);
         qubit_should_be_less_than_circuit_size(&target_qubit, &self.size);
         ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
-        self.apply(&[&ctrl_qubit, &target_qubit], &DoubleGate::CP(theta));
+        self.apply(&[&ctrl_qubit, &target_qubit], &DoubleGate::CRZ(theta));
     }
 
-    pub fn Toffoli(&mut self, ctrl_qubit1: usize, ctrl_qubit2: usize, target_qubit: usize) {
+    pub fn RZZ(&mut self, qubit1: impl AsQubit, qubit2: impl AsQubit, theta: f64) {
+        let qubit1 = qubit1.qubit_index();
+        let qubit2 = qubit2.qubit_index();
+        qubit_should_be_less_than_circuit_size(&qubit1, &self.size);
+        qubit_should_be_less_than_circuit_size(&qubit2, &self.size);
+        ctrl_qubit_should_be_different_from_target_qubit(&qubit1, &qubit2);
+        self.apply(&[&qubit1, &qubit2], &DoubleGate::RZZ(theta));
+    }
+
+    // exp(-i theta/2 P), decomposed by `ParameterizedCircuit::pauli_rotation`
+    pub fn pauli_rotation(&mut self, pauli: &PauliString, theta: f64) {
+        assert!(pauli.size() <= self.size);
+        let mut rotation = ParameterizedCircuit::new(pauli.size());
+        rotation.pauli_rotation(pauli, theta);
+        self.append(&rotation, &(0..pauli.size()).collect::<Vec<_>>());
+    }
+
+    // multiplies the state by e^{i phi}
+    pub fn global_phase(&mut self, phi: f64) {
+        self.state
+            .elements
+            .mapv_inplace(|x| x * Complex::new(phi.cos(), phi.sin()));
+    }
+
+    // X on the target when every control is 1, acting on the amplitudes directly
+    pub fn MCX(&mut self, ctrl_qubits: &[impl AsQubit], target_qubit: impl AsQubit) {
+        let ctrl_qubits = qubit_indices(ctrl_qubits);
+        let target_qubit = target_qubit.qubit_index();
+        qubit_should_be_less_than_circuit_size(&target_qubit, &self.size);
+        for ctrl_qubit in ctrl_qubits.iter() {
+            qubit_should_be_less_than_circuit_size(ctrl_qubit, &self.size);
+            ctrl_qubit_should_be_different_from_target_qubit(ctrl_qubit, &target_qubit);
+        }
+        let mask = ctrl_qubits.iter().fold(0, |mask, q| mask | 1 << q);
+        let elements = self.state.elements.as_slice_mut().unwrap();
+        for i in 0..elements.len() {
+            if i & mask == mask && i >> target_qubit & 1 == 0 {
+                elements.swap(i, i | 1 << target_qubit);
+            }
+        }
+    }
+
+    // P(theta) on the target when every control is 1, so |1..1> gets e^{-i theta}
+    pub fn MCP(&mut self, ctrl_qubits: &[impl AsQubit], target_qubit: impl AsQubit, theta: f64) {
+        let ctrl_qubits = qubit_indices(ctrl_qubits);
+        let target_qubit = target_qubit.qubit_index();
+        qubit_should_be_less_than_circuit_size(&target_qubit, &self.size);
+        for ctrl_qubit in ctrl_qubits.iter() {
+            qubit_should_be_less_than_circuit_size(ctrl_qubit, &self.size);
+            ctrl_qubit_should_be_different_from_target_qubit(ctrl_qubit, &target_qubit);
+        }
+        let mask = ctrl_qubits
+            .iter()
+            .fold(1 << target_qubit, |mask, q| mask | 1 << q);
+        let phase = Complex::new(theta.cos(), -theta.sin());
+        for (i, element) in self.state.elements.iter_mut().enumerate() {
+            if i & mask == mask {
+                *element *= phase;
+            }
+        }
+    }
+
+    // flips the sign of the basis states in which every qubit is 1
+    pub fn MCZ(&mut self, qubits: &[impl AsQubit]) {
+        let qubits = qubit_indices(qubits);
+        for (k, qubit) in qubits.iter().enumerate() {
+            qubit_should_be_less_than_circuit_size(qubit, &self.size);
+            assert!(!qubits[..k].contains(qubit));
+        }
+        let mask = qubits.iter().fold(0, |mask, q| mask | 1 << q);
+        for i in 0..self.state.elements.len() {
+            if i & mask == mask {
+                self.state.elements[i] = -self.state.elements[i];
+            }
+        }
+    }
+
+    pub fn Toffoli(
+        &mut self,
+        ctrl_qubit1: impl AsQubit,
+        ctrl_qubit2: impl AsQubit,
+        target_qubit: impl AsQubit,
+    ) {
+        let ctrl_qubit1 = ctrl_qubit1.qubit_index();
+        let ctrl_qubit2 = ctrl_qubit2.qubit_index();
+        let target_qubit = target_qubit.qubit_index();
         qubit_should_be_less_than_circuit_size(&ctrl_qubit1, &self.size);
         qubit_should_be_less_than_circuit_size(&ctrl_qubit2, &self.size);
         qubit_should_be_less_than_circuit_size(&target_qubit, &self.size);
@@ -166,7 +536,15 @@ impl QuantumCircuit {
         );
     }
 
-    pub fn CCSWAP(&mut self, ctrl_qubit1: usize, ctrl_qubit2: usize, target_qubit: usize) {
+    pub fn CCSWAP(
+        &mut self,
+        ctrl_qubit1: impl AsQubit,
+        ctrl_qubit2: impl AsQubit,
+        target_qubit: impl AsQubit,
+    ) {
+        let ctrl_qubit1 = ctrl_qubit1.qubit_index();
+        let ctrl_qubit2 = ctrl_qubit2.qubit_index();
+        let target_qubit = target_qubit.qubit_index();
         qubit_should_be_less_than_circuit_size(&ctrl_qubit1, &self.size);
         qubit_should_be_less_than_circuit_size(&ctrl_qubit2, &self.size);
         qubit_should_be_less_than_circuit_size(&target_qubit, &self.size);
@@ -178,24 +556,18 @@ impl QuantumCircuit {
         );
     }
 
+    // fourier transform of the qubits from_qubit..=to_qubit, with from_qubit as the
+    // lowest bit; see QFT for other qubit lists and options
     pub fn QFT(&mut self, from_qubit: usize, to_qubit: usize) {
-        qubit_should_be_less_than_circuit_size(&from_qubit, &self.size);
         qubit_should_be_less_than_circuit_size(&to_qubit, &self.size);
-        let qubit_len = to_qubit - from_qubit + 1;
-        for q in 0..qubit_len {
-            self.apply(&[&q], &SingleGate::H());
-            let mut phase = PI;
-            for qq in 0..qubit_len - q - 1 {
-                phase /= 2.;
-                self.CP(qq + q + 1, q, phase);
-            }
-        }
+        assert!(from_qubit <= to_qubit);
+        QFT::default().apply(self, &(from_qubit..=to_qubit).collect::<Vec<_>>());
+    }
 
-        let mut id = 0;
-        while id < qubit_len - id - 1 {
-            self.SWAP(id, qubit_len - id - 1);
-            id += 1;
-        }
+    pub fn IQFT(&mut self, from_qubit: usize, to_qubit: usize) {
+        qubit_should_be_less_than_circuit_size(&to_qubit, &self.size);
+        assert!(from_qubit <= to_qubit);
+        QFT::inverse().apply(self, &(from_qubit..=to_qubit).collect::<Vec<_>>());
     }
 }
 
@@ -259,6 +631,37 @@ fn P_test() {
     assert_eq!(-1. - qc.state.elements[1].im() < EPSILON, true);
 }
 
+#[test]
+fn RX_test() {
+    // RX(theta) = exp(-i theta X / 2) is unitary
+    let matrix = SingleGate::RX(0.7).matrix().clone();
+    let product = matrix.dot(&matrix.t().mapv(|x| x.conj()));
+    for i in 0..2 {
+        for j in 0..2 {
+            let expected = if i == j {
+                Complex::one()
+            } else {
+                Complex::zero()
+            };
+            assert!((product[[i, j]] - expected).norm() < 1e-12);
+        }
+    }
+
+    // RX(pi)|0> = -i|1>
+    let mut qc = QuantumCircuit::new(1);
+    qc.RX(0, PI);
+    assert!(qc.state.elements[0].norm() < 1e-12);
+    assert!((qc.state.elements[1] - Complex::new(0., -1.)).norm() < 1e-12);
+
+    // RX(pi / 2)|1> = (-i|0> + |1>) / sqrt(2)
+    let mut qc = QuantumCircuit::new(1);
+    qc.X(0);
+    qc.RX(0, PI / 2.);
+    let sqrt2inv = 2.0f64.sqrt().recip();
+    assert!((qc.state.elements[0] - Complex::new(0., -sqrt2inv)).norm() < 1e-12);
+    assert!((qc.state.elements[1] - Complex::new(sqrt2inv, 0.)).norm() < 1e-12);
+}
+
 #[test]
 fn H_test() {
     let mut qc = QuantumCircuit::new(1);
@@ -346,6 +749,78 @@ fn Toffoli_test() {
     assert_eq!(Complex::zero(), qc.state.elements[7]);
 }
 
+#[test]
+fn MCX_test() {
+    // |1011> -> |1111> with controls 0, 1, 3
+    let mut qc = QuantumCircuit::new(4);
+    qc.X(0);
+    qc.X(1);
+    qc.X(3);
+    qc.MCX(&[0, 1, 3], 2);
+    assert_eq!(Complex::one(), qc.state.elements[15]);
+    qc.X(1);
+    qc.MCX(&[0, 1, 3], 2);
+    assert_eq!(Complex::one(), qc.state.elements[13]);
+
+    // without controls it is X
+    let mut qc = QuantumCircuit::new(1);
+    qc.MCX(&[] as &[usize], 0);
+    assert_eq!(Complex::one(), qc.state.elements[1]);
+}
+
+#[test]
+fn MCP_test() {
+    let mut qc = QuantumCircuit::new(3);
+    qc.X(0);
+    qc.X(2);
+    qc.MCP(&[0, 2], 1, PI / 2.);
+    assert_eq!(Complex::one(), qc.state.elements[5]);
+    qc.X(1);
+    qc.MCP(&[0, 2], 1, PI / 2.);
+    assert!((qc.state.elements[7] - Complex::new(0., -1.)).norm() < 1e-12);
+}
+
+#[test]
+fn measure_test() {
+    // a single shot collapses the state onto the outcome
+    let mut qc = QuantumCircuit::new(2);
+    qc.X(0);
+    qc.H(1);
+    assert_eq!(1, qc.measure(0, 1).ones());
+    let result = qc.measure(1, 1);
+    let index = 1 | result.ones() << 1;
+    assert!((qc.state.elements[index] - Complex::one()).norm() < 1e-12);
+}
+
+#[test]
+fn MCZ_test() {
+    let mut qc = QuantumCircuit::new(3);
+    qc.H(0);
+    qc.H(1);
+    qc.H(2);
+    qc.MCZ(&[0, 1, 2]);
+    let amplitude = 8f64.sqrt().recip();
+    for i in 0..8 {
+        let expected = if i == 7 { -amplitude } else { amplitude };
+        assert!((qc.state.elements[i].re() - expected).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn register_controls_test() {
+    // the controls of the multi-controlled gates can be the qubits of a register
+    let mut qc = QuantumCircuit::new(0);
+    let ctrls = qc.add_register("ctrls", 2);
+    let target = qc.add_register("target", 1);
+    qc.X(ctrls[0]);
+    qc.X(ctrls[1]);
+    qc.MCX(ctrls.qubits(), target[0]);
+    assert_eq!(Complex::one(), qc.state.elements[7]);
+    qc.MCP(ctrls.qubits(), target[0], PI / 2.);
+    qc.MCZ(&[ctrls[0], target[0]]);
+    assert!((qc.state.elements[7] - Complex::new(0., 1.)).norm() < 1e-12);
+}
+
 #[test]
 fn CCSWAP_test() {
     let mut qc = QuantumCircuit::new(3);
@@ -363,6 +838,18 @@ fn CCSWAP_test() {
     assert_eq!(Complex::one(), qc.state.elements[7]);
 }
 
+#[test]
+fn pauli_rotation_test() {
+    use crate::operator::pauli::Pauli;
+
+    // Y0 X1 |000> = i|011>, so exp(-i theta/2 Y0 X1)|000> = cos|000> + sin|011>
+    let pauli = PauliString::from_sparse(2, &[(0, Pauli::Y), (1, Pauli::X)]);
+    let mut qc = QuantumCircuit::new(3);
+    qc.pauli_rotation(&pauli, 0.8);
+    assert!((qc.state.elements[0] - Complex::new(0.4f64.cos(), 0.)).norm() < 1e-12);
+    assert!((qc.state.elements[3] - Complex::new(0.4f64.sin(), 0.)).norm() < 1e-12);
+}
+
 #[test]
 fn QFT_test() {
     let circuit_size: usize = 3;
@@ -377,3 +864,122 @@ fn QFT_test() {
         );
     }
 }
+
+#[test]
+fn add_qubits_test() {
+    let mut qc = QuantumCircuit::new(1);
+    qc.H(0);
+    assert_eq!(vec![1, 2], qc.add_qubits(2));
+    assert_eq!(3, qc.size());
+    qc.CNOT(0, 2);
+    let amplitude = Complex::new(1. / 2f64.sqrt(), 0.);
+    assert!((qc.state.elements[0] - amplitude).norm() < 1e-12);
+    assert!((qc.state.elements[5] - amplitude).norm() < 1e-12);
+}
+
+#[test]
+fn register_test() {
+    let mut qc = QuantumCircuit::new(0);
+    let a = qc.add_register("a", 2);
+    let b = qc.add_register("b", 3);
+    let c = qc.add_classical_register("c", 5);
+    assert_eq!(5, qc.size());
+    assert_eq!(vec![2, 3, 4], b.indices());
+    assert_eq!(Some(&b), qc.quantum_register("b"));
+    assert_eq!(None, qc.quantum_register("c"));
+    assert_eq!(Some(&c), qc.classical_register("c"));
+    assert_eq!(Some(&b), qc.register_of(b[1]));
+    assert_eq!(Some(&a), qc.register_of(a.slice(1..2)[0]));
+    assert_eq!(None, qc.register_of(Qubit::new(1)));
+    // a bell pair across the registers and b = 0b101
+    qc.H(a[0]);
+    qc.CNOT(a[0], b[1]);
+    qc.X(b[0]);
+    qc.X(b[2]);
+    let both = a.slice(0..1).concat(&b);
+    let value = qc.measure_register(&both, &c.slice(0..4));
+    assert!(value == 0b1010 || value == 0b1111);
+    assert_eq!(value, qc.read(&c));
+    assert_eq!(0, qc.measure_into(a[1], c[4]));
+}
+
+#[test]
+#[should_panic]
+fn duplicate_register_test() {
+    let mut qc = QuantumCircuit::new(0);
+    qc.add_register("a", 1);
+    qc.add_classical_register("a", 1);
+}
+
+#[test]
+fn ancilla_test() {
+    let mut qc = QuantumCircuit::new(2);
+    let first = qc.allocate_ancillas(2);
+    assert_eq!(vec![2, 3], first.indices());
+    qc.X(first[0]);
+    qc.X(first[0]);
+    qc.release_ancillas(&first);
+    // released ancillas come back before the circuit grows
+    let second = qc.allocate_ancillas(3);
+    assert_eq!(5, qc.size());
+    let mut indices = second.indices();
+    indices.sort();
+    assert_eq!(vec![2, 3, 4], indices);
+    // dirty ancillas come from the idle qubits
+    qc.H(1);
+    let borrowed = qc.borrow_ancillas(2, &[0]);
+    assert_eq!(vec![1, 5], borrowed.indices());
+    qc.release_borrowed(&borrowed);
+    qc.release_ancillas(&second);
+}
+
+#[test]
+fn borrow_then_allocate_test() {
+    // released ancillas are not borrowed, so allocating them later cannot overlap
+    let mut qc = QuantumCircuit::new(1);
+    let clean = qc.allocate_ancillas(2);
+    qc.release_ancillas(&clean);
+    let borrowed = qc.borrow_ancillas(2, &[0]);
+    let allocated = qc.allocate_ancillas(2);
+    assert!(borrowed
+        .indices()
+        .iter()
+        .all(|q| !allocated.indices().contains(q)));
+    qc.release_ancillas(&allocated);
+    qc.release_borrowed(&borrowed);
+}
+
+#[test]
+fn compute_uncompute_test() {
+    // X controlled by four qubits through a chain of toffolis on three ancillas
+    let controls = [0, 1, 2, 3];
+    for value in 0..16 {
+        let mut qc = QuantumCircuit::new(5);
+        for q in controls.iter().filter(|&&q| value >> q & 1 == 1) {
+            qc.X(*q);
+        }
+        qc.compute_uncompute(
+            3,
+            |block, ancillas| {
+                block.Toffoli(controls[0], controls[1], ancillas[0].index);
+                block.Toffoli(controls[2], ancillas[0].index, ancillas[1].index);
+                block.Toffoli(controls[3], ancillas[1].index, ancillas[2].index);
+            },
+            |qc, ancillas| qc.CNOT(ancillas[2], 4),
+        );
+        assert_eq!(8, qc.size());
+        let expected = value | usize::from(value == 15) << 4;
+        assert!((qc.state.elements[expected].norm() - 1.).abs() < 1e-10);
+    }
+}
+
+// the check only exists in debug builds
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic]
+fn dirty_release_test() {
+    let mut qc = QuantumCircuit::new(1);
+    let ancillas = qc.allocate_ancillas(1);
+    qc.H(ancillas[0]);
+    qc.release_ancillas(&ancillas);
+}
@@ -1 +1,14 @@
+#[allow(non_snake_case, clippy::module_inception)]
+#[cfg_attr(
+    test,
+    allow(
+        unused_mut,
+        clippy::bool_assert_comparison,
+        clippy::float_equality_without_abs,
+        clippy::legacy_numeric_constants
+    )
+)]
 pub mod circuit;
+#[allow(non_snake_case)]
+pub mod parameter;
+pub mod qft;
@@ -0,0 +1,791 @@
+use crate::circuit::circuit::QuantumCircuit;
+use crate::gate::base_gates::{DoubleGate, SingleGate, TripleGate};
+use crate::gate::gate::Gate;
+use crate::operator::pauli::{Pauli, PauliString};
+use crate::state::state::State;
+use crate::validate::validate::{
+    ctrl_qubit_should_be_different_from_target_qubit, qubit_should_be_less_than_circuit_size,
+};
+use ndarray::{prelude::Array2, s};
+use num::{complex::Complex, One, Zero};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Parameter {
+    name: String,
+}
+
+impl Parameter {
+    pub fn new(name: &str) -> Parameter {
+        Parameter {
+            name: name.to_string(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+// either a constant angle or `scale * parameter + offset`
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParameterExpression {
+    Constant(f64),
+    Linear {
+        parameter: Parameter,
+        scale: f64,
+        offset: f64,
+    },
+}
+
+impl ParameterExpression {
+    pub fn parameter(&self) -> Option<&Parameter> {
+        match self {
+            ParameterExpression::Constant(_) => None,
+            ParameterExpression::Linear { parameter, .. } => Some(parameter),
+        }
+    }
+
+    pub fn scale(&self) -> f64 {
+        match self {
+            ParameterExpression::Constant(_) => 0.,
+            ParameterExpression::Linear { scale, .. } => *scale,
+        }
+    }
+
+    pub fn is_constant(&self) -> bool {
+        matches!(self, ParameterExpression::Constant(_))
+    }
+
+    pub fn bind(&self, values: &HashMap<String, f64>) -> f64 {
+        match self {
+            ParameterExpression::Constant(value) => *value,
+            ParameterExpression::Linear {
+                parameter,
+                scale,
+                offset,
+            } => match values.get(parameter.name()) {
+                Some(value) => scale * value + offset,
+                None => panic!("parameter '{}' is not bound", parameter.name()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for ParameterExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParameterExpression::Constant(value) => write!(f, "{}", value),
+            ParameterExpression::Linear {
+                parameter,
+                scale,
+                offset,
+            } => write!(f, "{}*{} + {}", scale, parameter, offset),
+        }
+    }
+}
+
+impl From<f64> for ParameterExpression {
+    fn from(value: f64) -> ParameterExpression {
+        ParameterExpression::Constant(value)
+    }
+}
+
+impl From<&Parameter> for ParameterExpression {
+    fn from(parameter: &Parameter) -> ParameterExpression {
+        ParameterExpression::Linear {
+            parameter: parameter.clone(),
+            scale: 1.,
+            offset: 0.,
+        }
+    }
+}
+
+impl From<Parameter> for ParameterExpression {
+    fn from(parameter: Parameter) -> ParameterExpression {
+        ParameterExpression::from(&parameter)
+    }
+}
+
+impl Mul<f64> for ParameterExpression {
+    type Output = ParameterExpression;
+
+    fn mul(self, factor: f64) -> ParameterExpression {
+        match self {
+            ParameterExpression::Constant(value) => ParameterExpression::Constant(value * factor),
+            ParameterExpression::Linear {
+                parameter,
+                scale,
+                offset,
+            } => ParameterExpression::Linear {
+                parameter,
+                scale: scale * factor,
+                offset: offset * factor,
+            },
+        }
+    }
+}
+
+impl Add<f64> for ParameterExpression {
+    type Output = ParameterExpression;
+
+    fn add(self, term: f64) -> ParameterExpression {
+        match self {
+            ParameterExpression::Constant(value) => ParameterExpression::Constant(value + term),
+            ParameterExpression::Linear {
+                parameter,
+                scale,
+                offset,
+            } => ParameterExpression::Linear {
+                parameter,
+                scale,
+                offset: offset + term,
+            },
+        }
+    }
+}
+
+impl Sub<f64> for ParameterExpression {
+    type Output = ParameterExpression;
+
+    fn sub(self, term: f64) -> ParameterExpression {
+        self + (-term)
+    }
+}
+
+impl Neg for ParameterExpression {
+    type Output = ParameterExpression;
+
+    fn neg(self) -> ParameterExpression {
+        self * -1.
+    }
+}
+
+impl Mul<f64> for &Parameter {
+    type Output = ParameterExpression;
+
+    fn mul(self, factor: f64) -> ParameterExpression {
+        ParameterExpression::from(self) * factor
+    }
+}
+
+impl Add<f64> for &Parameter {
+    type Output = ParameterExpression;
+
+    fn add(self, term: f64) -> ParameterExpression {
+        ParameterExpression::from(self) + term
+    }
+}
+
+impl Neg for &Parameter {
+    type Output = ParameterExpression;
+
+    fn neg(self) -> ParameterExpression {
+        -ParameterExpression::from(self)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Rotation {
+    RX,
+    RY,
+    RZ,
+    P,
+    CP,
+    CRX,
+    CRY,
+    CRZ,
+    RZZ,
+}
+
+impl Rotation {
+    pub fn size(&self) -> usize {
+        match self {
+            Rotation::RX | Rotation::RY | Rotation::RZ | Rotation::P => 1,
+            Rotation::CP | Rotation::CRX | Rotation::CRY | Rotation::CRZ | Rotation::RZZ => 2,
+        }
+    }
+
+    pub fn gate(&self, theta: f64) -> Gate {
+        match self {
+            Rotation::RX => SingleGate::RX(theta),
+            Rotation::RY => SingleGate::RY(theta),
+            Rotation::RZ => SingleGate::RZ(theta),
+            Rotation::P => SingleGate::P(theta),
+            Rotation::CP => DoubleGate::CP(theta),
+            Rotation::CRX => DoubleGate::CRX(theta),
+            Rotation::CRY => DoubleGate::CRY(theta),
+            Rotation::CRZ => DoubleGate::CRZ(theta),
+            Rotation::RZZ => DoubleGate::RZZ(theta),
+        }
+    }
+
+    // U(θ) = exp(-iθG)
+    pub fn generator(&self) -> Gate {
+        let half = Complex::new(0.5, 0.);
+        let one = Complex::one();
+        let mut matrix = Array2::<Complex<f64>>::zeros((1 << self.size(), 1 << self.size()));
+        match self {
+            Rotation::RX => matrix.assign(&SingleGate::X().matrix().mapv(|v| v * half)),
+            Rotation::RY => matrix.assign(&SingleGate::Y().matrix().mapv(|v| v * half)),
+            Rotation::RZ => matrix.assign(&SingleGate::Z().matrix().mapv(|v| v * half)),
+            Rotation::P => matrix[[1, 1]] = one,
+            Rotation::CP => matrix[[3, 3]] = one,
+            Rotation::RZZ => {
+                for k in 0..4 {
+                    matrix[[k, k]] = if k == 0 || k == 3 { half } else { -half };
+                }
+            }
+            Rotation::CRX | Rotation::CRY | Rotation::CRZ => {
+                let pauli = match self {
+                    Rotation::CRX => SingleGate::X(),
+                    Rotation::CRY => SingleGate::Y(),
+                    _ => SingleGate::Z(),
+                };
+                matrix
+                    .slice_mut(s![2.., 2..])
+                    .assign(&pauli.matrix().mapv(|v| v * half));
+            }
+        }
+        Gate {
+            size: self.size(),
+            matrix,
+        }
+    }
+
+    // d/dθ f(θ) = sum_k coefficient_k * f(θ + shift_k)
+    // the generators of RX, RY, RZ, RZZ, P and CP have two distinct eigenvalues one apart,
+    // which gives the usual two-term rule; the controlled rotations have the three
+    // eigenvalues {0, ±1/2} and need the four-term rule of Anselmetti et al.
+    pub fn shift_rule(&self) -> Vec<(f64, f64)> {
+        match self {
+            Rotation::RX
+            | Rotation::RY
+            | Rotation::RZ
+            | Rotation::RZZ
+            | Rotation::P
+            | Rotation::CP => {
+                vec![(0.5, PI / 2.), (-0.5, -PI / 2.)]
+            }
+            Rotation::CRX | Rotation::CRY | Rotation::CRZ => {
+                let d1 = (2f64.sqrt() + 1.) / (4. * 2f64.sqrt());
+                let d2 = (2f64.sqrt() - 1.) / (4. * 2f64.sqrt());
+                vec![
+                    (d1, PI / 2.),
+                    (-d1, -PI / 2.),
+                    (-d2, 3. * PI / 2.),
+                    (d2, -3. * PI / 2.),
+                ]
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParameterizedInstruction {
+    Fixed {
+        qubits: Vec<usize>,
+        gate: Gate,
+    },
+    Rotation {
+        rotation: Rotation,
+        qubits: Vec<usize>,
+        angle: ParameterExpression,
+    },
+}
+
+impl ParameterizedInstruction {
+    pub fn qubits(&self) -> &[usize] {
+        match self {
+            ParameterizedInstruction::Fixed { qubits, .. } => qubits,
+            ParameterizedInstruction::Rotation { qubits, .. } => qubits,
+        }
+    }
+
+    pub fn is_parameterized(&self) -> bool {
+        match self {
+            ParameterizedInstruction::Fixed { .. } => false,
+            ParameterizedInstruction::Rotation { angle, .. } => !angle.is_constant(),
+        }
+    }
+
+    pub fn gate(&self, values: &HashMap<String, f64>) -> Gate {
+        match self {
+            ParameterizedInstruction::Fixed { gate, .. } => gate.clone(),
+            ParameterizedInstruction::Rotation {
+                rotation, angle, ..
+            } => rotation.gate(angle.bind(values)),
+        }
+    }
+
+    pub fn apply(&self, state: &mut State, values: &HashMap<String, f64>) {
+        let qubits = self.qubits().iter().collect::<Vec<_>>();
+        match self {
+            ParameterizedInstruction::Fixed { gate, .. } => state.apply(&qubits, gate),
+            _ => state.apply(&qubits, &self.gate(values)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParameterizedCircuit {
+    size: usize,
+    instructions: Vec<ParameterizedInstruction>,
+    parameters: Vec<Parameter>,
+}
+
+impl ParameterizedCircuit {
+    pub fn new(size: usize) -> ParameterizedCircuit {
+        ParameterizedCircuit {
+            size,
+            instructions: vec![],
+            parameters: vec![],
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn instructions(&self) -> &[ParameterizedInstruction] {
+        &self.instructions
+    }
+
+    // parameters in the order they first appear in the circuit
+    pub fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    pub fn num_parameters(&self) -> usize {
+        self.parameters.len()
+    }
+
+    pub fn push(&mut self, instruction: ParameterizedInstruction) {
+        for qubit in instruction.qubits().iter() {
+            qubit_should_be_less_than_circuit_size(qubit, &self.size);
+        }
+        if let ParameterizedInstruction::Rotation { angle, .. } = &instruction {
+            if let Some(parameter) = angle.parameter() {
+                if !self.parameters.contains(parameter) {
+                    self.parameters.push(parameter.clone());
+                }
+            }
+        }
+        self.instructions.push(instruction);
+    }
+
+    pub fn append(&mut self, other: &ParameterizedCircuit) {
+        assert!(other.size <= self.size);
+        for instruction in other.instructions.iter() {
+            self.push(instruction.clone());
+        }
+    }
+
+    // the instructions in reverse order with daggered gates and negated angles
+    pub fn inverse(&self) -> ParameterizedCircuit {
+        let mut inverse = ParameterizedCircuit::new(self.size);
+        for instruction in self.instructions.iter().rev() {
+            inverse.push(match instruction {
+                ParameterizedInstruction::Fixed { qubits, gate } => {
+                    ParameterizedInstruction::Fixed {
+                        qubits: qubits.clone(),
+                        gate: gate.dagger(),
+                    }
+                }
+                ParameterizedInstruction::Rotation {
+                    rotation,
+                    qubits,
+                    angle,
+                } => ParameterizedInstruction::Rotation {
+                    rotation: *rotation,
+                    qubits: qubits.clone(),
+                    angle: -angle.clone(),
+                },
+            });
+        }
+        inverse
+    }
+
+    fn fixed(&mut self, qubits: Vec<usize>, gate: Gate) {
+        self.push(ParameterizedInstruction::Fixed { qubits, gate });
+    }
+
+    fn rotation(&mut self, rotation: Rotation, qubits: Vec<usize>, angle: ParameterExpression) {
+        self.push(ParameterizedInstruction::Rotation {
+            rotation,
+            qubits,
+            angle,
+        });
+    }
+
+    pub fn H(&mut self, qubit: usize) {
+        self.fixed(vec![qubit], SingleGate::H());
+    }
+
+    pub fn X(&mut self, qubit: usize) {
+        self.fixed(vec![qubit], SingleGate::X());
+    }
+
+    pub fn Y(&mut self, qubit: usize) {
+        self.fixed(vec![qubit], SingleGate::Y());
+    }
+
+    pub fn Z(&mut self, qubit: usize) {
+        self.fixed(vec![qubit], SingleGate::Z());
+    }
+
+    pub fn S(&mut self, qubit: usize) {
+        self.fixed(vec![qubit], SingleGate::S());
+    }
+
+    pub fn Sdg(&mut self, qubit: usize) {
+        self.fixed(vec![qubit], SingleGate::Sdg());
+    }
+
+    pub fn P(&mut self, qubit: usize, theta: impl Into<ParameterExpression>) {
+        self.rotation(Rotation::P, vec![qubit], theta.into());
+    }
+
+    pub fn RX(&mut self, qubit: usize, theta: impl Into<ParameterExpression>) {
+        self.rotation(Rotation::RX, vec![qubit], theta.into());
+    }
+
+    pub fn RY(&mut self, qubit: usize, theta: impl Into<ParameterExpression>) {
+        self.rotation(Rotation::RY, vec![qubit], theta.into());
+    }
+
+    pub fn RZ(&mut self, qubit: usize, theta: impl Into<ParameterExpression>) {
+        self.rotation(Rotation::RZ, vec![qubit], theta.into());
+    }
+
+    pub fn CNOT(&mut self, ctrl_qubit: usize, target_qubit: usize) {
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
+        self.fixed(vec![ctrl_qubit, target_qubit], DoubleGate::CNOT());
+    }
+
+    pub fn CZ(&mut self, ctrl_qubit: usize, target_qubit: usize) {
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
+        self.fixed(vec![ctrl_qubit, target_qubit], DoubleGate::CZ());
+    }
+
+    pub fn SWAP(&mut self, ctrl_qubit: usize, target_qubit: usize) {
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
+        self.fixed(vec![ctrl_qubit, target_qubit], DoubleGate::SWAP());
+    }
+
+    pub fn CP(
+        &mut self,
+        ctrl_qubit: usize,
+        target_qubit: usize,
+        theta: impl Into<ParameterExpression>,
+    ) {
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
+        self.rotation(Rotation::CP, vec![ctrl_qubit, target_qubit], theta.into());
+    }
+
+    pub fn CRX(
+        &mut self,
+        ctrl_qubit: usize,
+        target_qubit: usize,
+        theta: impl Into<ParameterExpression>,
+    ) {
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
+        self.rotation(Rotation::CRX, vec![ctrl_qubit, target_qubit], theta.into());
+    }
+
+    pub fn CRY(
+        &mut self,
+        ctrl_qubit: usize,
+        target_qubit: usize,
+        theta: impl Into<ParameterExpression>,
+    ) {
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
+        self.rotation(Rotation::CRY, vec![ctrl_qubit, target_qubit], theta.into());
+    }
+
+    pub fn CRZ(
+        &mut self,
+        ctrl_qubit: usize,
+        target_qubit: usize,
+        theta: impl Into<ParameterExpression>,
+    ) {
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit, &target_qubit);
+        self.rotation(Rotation::CRZ, vec![ctrl_qubit, target_qubit], theta.into());
+    }
+
+    pub fn RZZ(&mut self, qubit1: usize, qubit2: usize, theta: impl Into<ParameterExpression>) {
+        ctrl_qubit_should_be_different_from_target_qubit(&qubit1, &qubit2);
+        self.rotation(Rotation::RZZ, vec![qubit1, qubit2], theta.into());
+    }
+
+    pub fn Toffoli(&mut self, ctrl_qubit1: usize, ctrl_qubit2: usize, target_qubit: usize) {
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit1, &target_qubit);
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit2, &target_qubit);
+        self.fixed(
+            vec![ctrl_qubit1, ctrl_qubit2, target_qubit],
+            TripleGate::Toffoli(),
+        );
+    }
+
+    pub fn CCSWAP(&mut self, ctrl_qubit1: usize, ctrl_qubit2: usize, target_qubit: usize) {
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit1, &target_qubit);
+        ctrl_qubit_should_be_different_from_target_qubit(&ctrl_qubit2, &target_qubit);
+        self.fixed(
+            vec![ctrl_qubit1, ctrl_qubit2, target_qubit],
+            TripleGate::CCSWAP(),
+        );
+    }
+
+    // exp(-i theta/2 P): each factor is rotated onto Z, the parity is collected on the
+    // last qubit of the support with a CNOT ladder and rotated by RZ(theta)
+    pub fn pauli_rotation(&mut self, pauli: &PauliString, theta: impl Into<ParameterExpression>) {
+        assert!(pauli.size() <= self.size);
+        let support = pauli.support();
+        let target = match support.last() {
+            Some(&target) => target,
+            // the identity only contributes a global phase
+            None => return,
+        };
+        for &qubit in support.iter() {
+            match pauli.get(qubit) {
+                Pauli::X => self.H(qubit),
+                Pauli::Y => {
+                    self.Sdg(qubit);
+                    self.H(qubit);
+                }
+                _ => {}
+            }
+        }
+        for pair in support.windows(2) {
+            self.CNOT(pair[0], pair[1]);
+        }
+        self.RZ(target, theta);
+        for pair in support.windows(2).rev() {
+            self.CNOT(pair[0], pair[1]);
+        }
+        for &qubit in support.iter() {
+            match pauli.get(qubit) {
+                Pauli::X => self.H(qubit),
+                Pauli::Y => {
+                    self.H(qubit);
+                    self.S(qubit);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn values_to_map(&self, values: &[f64]) -> HashMap<String, f64> {
+        assert_eq!(self.parameters.len(), values.len());
+        self.parameters
+            .iter()
+            .zip(values.iter())
+            .map(|(p, &v)| (p.name().to_string(), v))
+            .collect()
+    }
+
+    pub fn bind(&self, values: &HashMap<String, f64>) -> QuantumCircuit {
+        let mut qc = QuantumCircuit::new(self.size);
+        for instruction in self.instructions.iter() {
+            let qubits = instruction.qubits().iter().collect::<Vec<_>>();
+            qc.apply(&qubits, &instruction.gate(values));
+        }
+        qc
+    }
+
+    // the unitary of the bound circuit, whose matrix index has qubit q as bit q like
+    // the state; it is applied on the qubits listed from the highest one down
+    pub fn to_gate(&self, values: &HashMap<String, f64>) -> Gate {
+        let dim = 1 << self.size;
+        let mut matrix = Array2::<Complex<f64>>::zeros((dim, dim));
+        for j in 0..dim {
+            let mut state = State::new(self.size);
+            state.elements[0] = Complex::zero();
+            state.elements[j] = Complex::one();
+            for instruction in self.instructions.iter() {
+                instruction.apply(&mut state, values);
+            }
+            matrix.column_mut(j).assign(&state.elements);
+        }
+        Gate {
+            size: self.size,
+            matrix,
+        }
+    }
+
+    // binds the parameters in the order of `parameters()`
+    pub fn bind_values(&self, values: &[f64]) -> QuantumCircuit {
+        self.bind(&self.values_to_map(values))
+    }
+
+    pub fn run(&self, values: &HashMap<String, f64>) -> State {
+        self.run_many(std::slice::from_ref(values)).remove(0)
+    }
+
+    pub fn run_values(&self, values: &[f64]) -> State {
+        self.run(&self.values_to_map(values))
+    }
+
+    // the instructions before the first parameterized one do not depend on the
+    // bindings, so they are simulated only once and the resulting state is reused
+    pub fn run_many(&self, bindings: &[HashMap<String, f64>]) -> Vec<State> {
+        let split = self
+            .instructions
+            .iter()
+            .position(|i| i.is_parameterized())
+            .unwrap_or(self.instructions.len());
+        let empty = HashMap::new();
+        let mut prefix = State::new(self.size);
+        for instruction in self.instructions[..split].iter() {
+            instruction.apply(&mut prefix, &empty);
+        }
+        bindings
+            .iter()
+            .map(|values| {
+                let mut state = prefix.clone();
+                for instruction in self.instructions[split..].iter() {
+                    instruction.apply(&mut state, values);
+                }
+                state
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+use crate::operator::pauli::SparsePauliOp;
+
+#[cfg(test)]
+fn assert_same_state(a: &State, b: &State) {
+    assert!(a
+        .elements
+        .iter()
+        .zip(b.elements.iter())
+        .all(|(x, y)| (x - y).norm() < 1e-12));
+}
+
+#[test]
+fn parameter_expression_test() {
+    let theta = Parameter::new("theta");
+    let values = HashMap::from([("theta".to_string(), 0.5)]);
+    assert_eq!(2.5, (&theta * 3. + 1.).bind(&values));
+    assert_eq!(-1.5, (-(&theta * 2.) - 0.5).bind(&values));
+    assert_eq!(0.5, ParameterExpression::from(&theta).bind(&values));
+    assert_eq!(0.3, ParameterExpression::from(0.3).bind(&values));
+    assert_eq!(2., (&theta * 2.).scale());
+}
+
+#[test]
+#[should_panic]
+fn unbound_parameter_test() {
+    let mut pc = ParameterizedCircuit::new(1);
+    pc.RX(0, Parameter::new("theta"));
+    pc.bind(&HashMap::new());
+}
+
+#[test]
+fn bind_test() {
+    let theta = Parameter::new("theta");
+    let phi = Parameter::new("phi");
+    let mut pc = ParameterizedCircuit::new(3);
+    pc.H(0);
+    pc.RY(1, &theta);
+    pc.CNOT(0, 2);
+    pc.RX(2, &phi * 2. + 0.1);
+    pc.CP(1, 2, &theta * 0.5);
+    pc.RZ(0, 0.7);
+    pc.P(1, -&phi);
+    assert_eq!(vec![theta.clone(), phi.clone()], pc.parameters().to_vec());
+
+    let mut qc = QuantumCircuit::new(3);
+    qc.H(0);
+    qc.RY(1, 0.3);
+    qc.CNOT(0, 2);
+    qc.RX(2, 2. * 1.2 + 0.1);
+    qc.CP(1, 2, 0.5 * 0.3);
+    qc.RZ(0, 0.7);
+    qc.P(1, -1.2);
+
+    let values = HashMap::from([("theta".to_string(), 0.3), ("phi".to_string(), 1.2)]);
+    assert_same_state(&qc.state, &pc.bind(&values).state);
+    assert_same_state(&qc.state, &pc.bind_values(&[0.3, 1.2]).state);
+    assert_same_state(&qc.state, &pc.run(&values));
+}
+
+#[test]
+fn inverse_test() {
+    let theta = Parameter::new("theta");
+    let mut pc = ParameterizedCircuit::new(3);
+    pc.H(0);
+    pc.S(2);
+    pc.CRY(0, 1, &theta * 2.);
+    pc.Toffoli(0, 1, 2);
+    pc.RZZ(1, 2, 0.4);
+    pc.P(2, -&theta);
+    let mut circuit = pc.clone();
+    circuit.append(&pc.inverse());
+    let state = circuit.run_values(&[0.8]);
+    assert_same_state(&State::new(3), &state);
+
+    // the unitaries are each other's adjoints
+    let values = pc.values_to_map(&[0.8]);
+    let product = pc
+        .to_gate(&values)
+        .matrix()
+        .dot(pc.inverse().to_gate(&values).matrix());
+    for ((i, j), value) in product.indexed_iter() {
+        let expected = if i == j { 1. } else { 0. };
+        assert!((value - Complex::new(expected, 0.)).norm() < 1e-12);
+    }
+}
+
+#[test]
+fn run_many_test() {
+    let theta = Parameter::new("theta");
+    let mut pc = ParameterizedCircuit::new(2);
+    pc.H(0);
+    pc.CNOT(0, 1);
+    pc.RZ(1, &theta);
+    pc.H(1);
+    let bindings = (0..5)
+        .map(|i| HashMap::from([("theta".to_string(), 0.4 * i as f64)]))
+        .collect::<Vec<_>>();
+    let states = pc.run_many(&bindings);
+    assert_eq!(5, states.len());
+    for (values, state) in bindings.iter().zip(states.iter()) {
+        assert_same_state(&pc.bind(values).state, state);
+    }
+}
+
+#[test]
+fn pauli_rotation_test() {
+    let theta = Parameter::new("theta");
+    for label in ["X0 Y1 Z3", "Y2", "Z0 X3", "Y0 Y1 Y2 X3"] {
+        let op: SparsePauliOp = label.parse().unwrap();
+        let pauli = op.terms()[0].1.expand(4);
+        let mut pc = ParameterizedCircuit::new(4);
+        pc.H(0);
+        pc.RY(1, 0.4);
+        pc.CNOT(0, 2);
+        pc.RX(3, 1.1);
+        let prepared = pc.run(&HashMap::new());
+        pc.pauli_rotation(&pauli, &theta);
+        let rotated = pc.run_values(&[0.9]);
+
+        // exp(-i theta/2 P) = cos(theta/2) - i sin(theta/2) P
+        let applied = op.expand(4).apply_to(&prepared.elements);
+        let mut expected = prepared.clone();
+        expected.elements = prepared.elements.mapv(|x| x * (0.45f64).cos())
+            - applied.mapv(|x| x * Complex::new(0., (0.45f64).sin()));
+        assert_same_state(&expected, &rotated);
+    }
+}
@@ -0,0 +1,226 @@
+use crate::circuit::circuit::QuantumCircuit;
+#[cfg(test)]
+use crate::synthesis::state_preparation::prepare_state;
+#[cfg(test)]
+use ndarray::prelude::Array2;
+#[cfg(test)]
+use num::complex::Complex;
+use std::f64::consts::PI;
+
+// the quantum fourier transform |j> -> sum_k e^{2 pi i jk / N} |k> / sqrt(N) on a list of
+// qubits, qubits[q] holding bit q of j and k. the approximate transform drops the
+// controlled phases pi / 2^m with m >= n - approximation_degree. without the swaps the
+// output is bit reversed, qubits[n - 1 - q] holding bit q of k, and the inverse expects
+// its input in that order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QFT {
+    pub approximation_degree: usize,
+    pub do_swaps: bool,
+    pub inverse: bool,
+}
+
+impl Default for QFT {
+    fn default() -> Self {
+        QFT {
+            approximation_degree: 0,
+            do_swaps: true,
+            inverse: false,
+        }
+    }
+}
+
+impl QFT {
+    pub fn inverse() -> QFT {
+        QFT {
+            inverse: true,
+            ..QFT::default()
+        }
+    }
+
+    // the controlled phases (control, target, angle) following the hadamard on qubits[i]
+    fn rotations(&self, qubits: &[usize], i: usize) -> Vec<(usize, usize, f64)> {
+        let n = qubits.len();
+        (0..i)
+            .rev()
+            .filter(|&l| i - l + self.approximation_degree < n)
+            .map(|l| (qubits[l], qubits[i], PI / (1 << (i - l)) as f64))
+            .collect()
+    }
+
+    fn swaps(&self, qc: &mut QuantumCircuit, qubits: &[usize]) {
+        let n = qubits.len();
+        for q in 0..n / 2 {
+            qc.SWAP(qubits[q], qubits[n - 1 - q]);
+        }
+    }
+
+    pub fn apply(&self, qc: &mut QuantumCircuit, qubits: &[usize]) {
+        for (k, q) in qubits.iter().enumerate() {
+            assert!(!qubits[..k].contains(q), "the qubits should be distinct");
+        }
+        // CP(theta) multiplies |11> by e^{-i theta}
+        if self.inverse {
+            if self.do_swaps {
+                self.swaps(qc, qubits);
+            }
+            for i in 0..qubits.len() {
+                for (control, target, angle) in self.rotations(qubits, i).into_iter().rev() {
+                    qc.CP(control, target, angle);
+                }
+                qc.H(qubits[i]);
+            }
+        } else {
+            for i in (0..qubits.len()).rev() {
+                qc.H(qubits[i]);
+                for (control, target, angle) in self.rotations(qubits, i) {
+                    qc.CP(control, target, -angle);
+                }
+            }
+            if self.do_swaps {
+                self.swaps(qc, qubits);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn dft(dim: usize, sign: f64) -> Array2<Complex<f64>> {
+    Array2::from_shape_fn((dim, dim), |(k, j)| {
+        Complex::from_polar(
+            1. / (dim as f64).sqrt(),
+            sign * 2. * PI * (j * k) as f64 / dim as f64,
+        )
+    })
+}
+
+// the circuit index in which qubits[q] holds bit q of k
+#[cfg(test)]
+fn embed(k: usize, qubits: &[usize]) -> usize {
+    qubits
+        .iter()
+        .enumerate()
+        .map(|(bit, q)| (k >> bit & 1) << q)
+        .sum()
+}
+
+#[cfg(test)]
+fn reverse_bits(k: usize, n: usize) -> usize {
+    (0..n).map(|bit| (k >> bit & 1) << (n - 1 - bit)).sum()
+}
+
+#[test]
+fn qft_test() {
+    let layouts: [(usize, Vec<usize>); 6] = [
+        (1, vec![0]),
+        (2, vec![0, 1]),
+        (3, vec![0, 1, 2]),
+        (5, vec![2, 3, 4]),
+        (5, vec![4, 1, 3, 0]),
+        (4, vec![1, 2, 3]),
+    ];
+    for (size, qubits) in layouts.iter() {
+        let n = qubits.len();
+        for (qft, sign) in [(QFT::default(), 1.), (QFT::inverse(), -1.)] {
+            let matrix = dft(1 << n, sign);
+            for j in 0..1 << n {
+                let mut qc = QuantumCircuit::new(*size);
+                for (bit, &q) in qubits.iter().enumerate() {
+                    if j >> bit & 1 == 1 {
+                        qc.X(q);
+                    }
+                }
+                qft.apply(&mut qc, qubits);
+                for k in 0..1 << n {
+                    let amplitude = qc.state.elements[embed(k, qubits)];
+                    assert!((amplitude - matrix[[k, j]]).norm() < 1e-10);
+                }
+            }
+        }
+    }
+
+    // the contiguous range starts at from_qubit
+    let mut qc = QuantumCircuit::new(4);
+    qc.X(1);
+    qc.QFT(1, 3);
+    let matrix = dft(8, 1.);
+    for k in 0..8 {
+        assert!((qc.state.elements[k << 1] - matrix[[k, 1]]).norm() < 1e-10);
+    }
+    qc.IQFT(1, 3);
+    assert!((qc.state.elements[2] - Complex::new(1., 0.)).norm() < 1e-10);
+}
+
+#[test]
+fn qft_without_swaps_test() {
+    let qubits = [3, 0, 2, 1];
+    let n = qubits.len();
+    let matrix = dft(1 << n, 1.);
+    let qft = QFT {
+        do_swaps: false,
+        ..QFT::default()
+    };
+    let inverse = QFT {
+        do_swaps: false,
+        ..QFT::inverse()
+    };
+    for j in [0, 5, 11] {
+        let mut qc = QuantumCircuit::new(n);
+        for (bit, &q) in qubits.iter().enumerate() {
+            if j >> bit & 1 == 1 {
+                qc.X(q);
+            }
+        }
+        qft.apply(&mut qc, &qubits);
+        for k in 0..1 << n {
+            let amplitude = qc.state.elements[embed(reverse_bits(k, n), &qubits)];
+            assert!((amplitude - matrix[[k, j]]).norm() < 1e-10);
+        }
+        inverse.apply(&mut qc, &qubits);
+        assert!((qc.state.elements[embed(j, &qubits)] - Complex::new(1., 0.)).norm() < 1e-10);
+    }
+}
+
+#[test]
+fn approximate_qft_test() {
+    let n = 5;
+    let qubits = (0..n).collect::<Vec<_>>();
+    let amplitudes = (0..1 << n)
+        .map(|k| Complex::from_polar(1., 0.7 * k as f64) / (32f64).sqrt())
+        .collect::<Vec<_>>();
+    let transform = |degree| {
+        let mut qc = QuantumCircuit::new(n);
+        prepare_state(&mut qc, &qubits, &amplitudes);
+        QFT {
+            approximation_degree: degree,
+            ..QFT::default()
+        }
+        .apply(&mut qc, &qubits);
+        qc.state.elements
+    };
+    let exact = transform(0);
+    // the fidelity decreases as more of the small rotations are dropped
+    let mut fidelities = vec![];
+    for degree in 1..n {
+        let overlap = transform(degree)
+            .iter()
+            .zip(exact.iter())
+            .map(|(a, b)| a.conj() * b)
+            .sum::<Complex<f64>>();
+        fidelities.push(overlap.norm_sqr());
+    }
+    assert!(fidelities[0] > 0.95 && fidelities[0] < 1. - 1e-6);
+    assert!(fidelities.windows(2).all(|f| f[1] < f[0] + 1e-12));
+
+    // with every rotation dropped only the hadamards remain
+    let mut qc = QuantumCircuit::new(3);
+    qc.X(0);
+    QFT {
+        approximation_degree: 2,
+        ..QFT::default()
+    }
+    .apply(&mut qc, &[0, 1, 2]);
+    for k in 0..8 {
+        let sign = if k & 4 == 0 { 1. } else { -1. };
+        assert!((qc.state.elements[k].re - sign / 8f64.sqrt()).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,276 @@
+use crate::algebra::linalg::expm;
+use crate::circuit::circuit::QuantumCircuit;
+use crate::operator::pauli::{PauliString, SparsePauliOp};
+use crate::state::state::State;
+use num::complex::Complex;
+
+// exp(-iHt) is approximated by `steps` repetitions of a product formula S(t/steps):
+// order 1 is exp(-i H_1 dt)..exp(-i H_m dt), order 2 is the symmetric
+// S_2(dt) = S_1(dt/2) S_1^reversed(dt/2), and the even orders above follow Suzuki's
+// recursion S_2k(dt) = S_2k-2(u dt)^2 S_2k-2((1 - 4u) dt) S_2k-2(u dt)^2
+// with u = 1 / (4 - 4^(1/(2k-1)))
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trotter {
+    pub order: usize,
+    pub steps: usize,
+}
+
+// a single factor exp(-i coefficient * time P)
+pub type Exponential = (f64, PauliString);
+
+impl Trotter {
+    pub fn new(order: usize, steps: usize) -> Trotter {
+        assert!(
+            order == 1 || order.is_multiple_of(2),
+            "order should be 1 or even"
+        );
+        assert!(steps > 0);
+        Trotter { order, steps }
+    }
+
+    // the factors of one step of length dt, in the order they are applied
+    pub fn step_sequence(&self, hamiltonian: &SparsePauliOp, dt: f64) -> Vec<Exponential> {
+        let terms = hermitian_terms(hamiltonian);
+        let mut sequence = vec![];
+        suzuki(&terms, self.order, dt, &mut sequence);
+        merge_neighbours(sequence)
+    }
+
+    pub fn sequence(&self, hamiltonian: &SparsePauliOp, time: f64) -> Vec<Exponential> {
+        let step = self.step_sequence(hamiltonian, time / self.steps as f64);
+        let mut sequence = vec![];
+        for _ in 0..self.steps {
+            sequence.extend(step.iter().cloned());
+        }
+        merge_neighbours(sequence)
+    }
+
+    // appends the product formula for exp(-iHt) to the circuit; identity terms are
+    // applied as a global phase so that the result can be compared with exp(-iHt)
+    pub fn evolve(&self, qc: &mut QuantumCircuit, hamiltonian: &SparsePauliOp, time: f64) {
+        assert!(hamiltonian.size() <= qc.size());
+        for (angle, pauli) in self.sequence(hamiltonian, time) {
+            if pauli.is_identity() {
+                qc.global_phase(-angle);
+            } else {
+                qc.pauli_rotation(&pauli.expand(qc.size()), 2. * angle);
+            }
+        }
+    }
+
+    pub fn circuit(&self, hamiltonian: &SparsePauliOp, time: f64) -> QuantumCircuit {
+        let mut qc = QuantumCircuit::new(hamiltonian.size());
+        self.evolve(&mut qc, hamiltonian, time);
+        qc
+    }
+
+    // upper bound on the operator norm ||exp(-iHt) - S(t/r)^r||. orders 1 and 2 use the
+    // commutator bounds of Childs et al. (2021), with the norm of a pauli sum bounded
+    // by the sum of its absolute coefficients; higher orders use the looser
+    // (2 * 5^(k-1) * lambda * t)^(2k+1) / (3 r^2k) with lambda the same 1-norm of H
+    pub fn error_bound(&self, hamiltonian: &SparsePauliOp, time: f64) -> f64 {
+        let terms = hermitian_terms(hamiltonian)
+            .into_iter()
+            .filter(|(_, p)| !p.is_identity())
+            .map(|(c, p)| SparsePauliOp::from_pauli_string(Complex::new(c, 0.), p))
+            .collect::<Vec<_>>();
+        let size = hamiltonian.size();
+        let r = self.steps as f64;
+        let t = time.abs();
+        // H_>j = sum of the terms after j
+        let tails = (0..terms.len())
+            .map(|j| {
+                terms[j + 1..]
+                    .iter()
+                    .fold(SparsePauliOp::new(size), |acc, term| &acc + term)
+            })
+            .collect::<Vec<_>>();
+        match self.order {
+            1 => {
+                let alpha = terms
+                    .iter()
+                    .zip(tails.iter())
+                    .map(|(h, tail)| one_norm(&tail.commutator(h)))
+                    .sum::<f64>();
+                t * t / (2. * r) * alpha
+            }
+            2 => {
+                let alpha = terms
+                    .iter()
+                    .zip(tails.iter())
+                    .map(|(h, tail)| {
+                        one_norm(&tail.commutator(&tail.commutator(h))) / 12.
+                            + one_norm(&h.commutator(&h.commutator(tail))) / 24.
+                    })
+                    .sum::<f64>();
+                t.powi(3) / (r * r) * alpha
+            }
+            order => {
+                let k = (order / 2) as i32;
+                let lambda = terms.iter().map(one_norm).sum::<f64>();
+                (2. * 5f64.powi(k - 1) * lambda * t).powi(2 * k + 1) / (3. * r.powi(2 * k))
+            }
+        }
+    }
+
+    // the smallest number of steps whose error bound is below `epsilon`
+    pub fn steps_for_error(
+        order: usize,
+        hamiltonian: &SparsePauliOp,
+        time: f64,
+        epsilon: f64,
+    ) -> usize {
+        let bound = |steps: usize| Trotter::new(order, steps).error_bound(hamiltonian, time);
+        let mut high = 1;
+        while bound(high) > epsilon {
+            high *= 2;
+        }
+        let mut low = high / 2;
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            if bound(middle) > epsilon {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        high
+    }
+}
+
+fn hermitian_terms(hamiltonian: &SparsePauliOp) -> Vec<(f64, PauliString)> {
+    hamiltonian
+        .terms()
+        .iter()
+        .map(|(c, p)| {
+            assert!(c.im.abs() < 1e-12, "the hamiltonian should be hermitian");
+            (c.re, p.clone())
+        })
+        .collect()
+}
+
+fn suzuki(terms: &[(f64, PauliString)], order: usize, dt: f64, sequence: &mut Vec<Exponential>) {
+    match order {
+        1 => sequence.extend(terms.iter().map(|(c, p)| (c * dt, p.clone()))),
+        2 => {
+            sequence.extend(terms.iter().map(|(c, p)| (c * dt / 2., p.clone())));
+            sequence.extend(terms.iter().rev().map(|(c, p)| (c * dt / 2., p.clone())));
+        }
+        _ => {
+            let u = 1. / (4. - 4f64.powf(1. / (order - 1) as f64));
+            for fraction in [u, u, 1. - 4. * u, u, u] {
+                suzuki(terms, order - 2, fraction * dt, sequence);
+            }
+        }
+    }
+}
+
+// neighbouring exponentials of the same pauli string commute and are combined
+fn merge_neighbours(sequence: Vec<Exponential>) -> Vec<Exponential> {
+    let mut merged: Vec<Exponential> = vec![];
+    for (angle, pauli) in sequence {
+        match merged.last_mut() {
+            Some((last, p)) if *p == pauli => *last += angle,
+            _ => merged.push((angle, pauli)),
+        }
+    }
+    merged
+}
+
+fn one_norm(op: &SparsePauliOp) -> f64 {
+    op.simplify().terms().iter().map(|(c, _)| c.norm()).sum()
+}
+
+// exp(-iHt)|psi> from the dense matrix of H
+pub fn exact_evolution(hamiltonian: &SparsePauliOp, time: f64, state: &State) -> State {
+    let hamiltonian = hamiltonian.expand(state.size());
+    let unitary = expm(
+        &hamiltonian
+            .to_matrix()
+            .mapv(|x| x * Complex::new(0., -time)),
+    );
+    let mut evolved = state.clone();
+    evolved.elements = unitary.dot(&state.elements);
+    evolved
+}
+
+// ||exp(-iHt)|psi> - S(t/r)^r |psi>|| for the circuit prepared from |psi>
+pub fn evolution_error(
+    trotter: &Trotter,
+    hamiltonian: &SparsePauliOp,
+    time: f64,
+    state: &State,
+) -> f64 {
+    let exact = exact_evolution(hamiltonian, time, state);
+    let mut qc = QuantumCircuit::new(state.size());
+    qc.state = state.clone();
+    trotter.evolve(&mut qc, hamiltonian, time);
+    exact
+        .elements
+        .iter()
+        .zip(qc.state.elements.iter())
+        .map(|(a, b)| (a - b).norm_sqr())
+        .sum::<f64>()
+        .sqrt()
+}
+
+#[cfg(test)]
+fn test_state() -> State {
+    let mut qc = QuantumCircuit::new(3);
+    qc.H(0);
+    qc.RY(1, 0.7);
+    qc.CNOT(0, 2);
+    qc.RX(2, 0.4);
+    qc.state
+}
+
+#[test]
+fn commuting_trotter_test() {
+    // commuting terms are evolved exactly by a single first order step
+    let hamiltonian: SparsePauliOp = "0.3 + Z0 + 0.5*Z0 Z1 - 0.7*Z1 Z2".parse().unwrap();
+    let trotter = Trotter::new(1, 1);
+    assert!(evolution_error(&trotter, &hamiltonian, 1.3, &test_state()) < 1e-12);
+    assert!(trotter.error_bound(&hamiltonian, 1.3) < 1e-12);
+}
+
+#[test]
+fn trotter_order_test() {
+    let hamiltonian: SparsePauliOp = "0.2 + X0 X1 + 0.5*Y1 Y2 + 0.3*Z0 - 0.8*Z2 + 0.4*X2"
+        .parse()
+        .unwrap();
+    let time = 1.;
+    let state = test_state();
+    for order in [1, 2, 4, 6] {
+        let coarse = Trotter::new(order, 4);
+        let fine = Trotter::new(order, 8);
+        let coarse_error = evolution_error(&coarse, &hamiltonian, time, &state);
+        let fine_error = evolution_error(&fine, &hamiltonian, time, &state);
+        assert!(coarse_error <= coarse.error_bound(&hamiltonian, time));
+        assert!(fine_error <= fine.error_bound(&hamiltonian, time));
+        // the error scales as 1 / steps^order
+        let ratio = coarse_error / fine_error;
+        let expected = 2f64.powi(order as i32);
+        assert!(ratio > 0.7 * expected && ratio < 1.5 * expected);
+    }
+
+    // one S_4 step is made of five S_2 steps, neighbouring exponentials being merged
+    let terms = hamiltonian.len();
+    let s2 = Trotter::new(2, 1).step_sequence(&hamiltonian, 0.1).len();
+    let s4 = Trotter::new(4, 1).step_sequence(&hamiltonian, 0.1).len();
+    assert_eq!(2 * terms - 1, s2);
+    assert_eq!(5 * (s2 - 1) + 1, s4);
+}
+
+#[test]
+fn steps_for_error_test() {
+    let hamiltonian: SparsePauliOp = "X0 X1 + 0.5*Z0 + 0.5*Z1".parse().unwrap();
+    for order in [1, 2] {
+        let steps = Trotter::steps_for_error(order, &hamiltonian, 2., 1e-3);
+        let trotter = Trotter::new(order, steps);
+        assert!(trotter.error_bound(&hamiltonian, 2.) <= 1e-3);
+        assert!(Trotter::new(order, steps - 1).error_bound(&hamiltonian, 2.) > 1e-3);
+        let mut qc = QuantumCircuit::new(2);
+        qc.H(0);
+        assert!(evolution_error(&trotter, &hamiltonian, 2., &qc.state) <= 1e-3);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::gate::gate::Gate;
-use ndarray::{array, prelude::Array2};
+use ndarray::array;
 use num::{complex::Complex, One, Zero};
 
 pub struct SingleGate {}
@@ -54,6 +54,26 @@ impl SingleGate {
         }
     }
 
+    pub fn S() -> Gate {
+        let zero = Complex::zero();
+        let one = Complex::one();
+
+        Gate {
+            size: 1,
+            matrix: array![[one, zero], [zero, Complex::new(0., 1.)]],
+        }
+    }
+
+    pub fn Sdg() -> Gate {
+        let zero = Complex::zero();
+        let one = Complex::one();
+
+        Gate {
+            size: 1,
+            matrix: array![[one, zero], [zero, Complex::new(0., -1.)]],
+        }
+    }
+
     pub fn I() -> Gate {
         let zero = Complex::zero();
         let one = Complex::one();
@@ -70,7 +90,7 @@ impl SingleGate {
 
         Gate {
             size: 1,
-            matrix: array![[cos, -isin], [isin, cos]],
+            matrix: array![[cos, -isin], [-isin, cos]],
         }
     }
 
@@ -146,6 +166,75 @@ impl DoubleGate {
         }
     }
 
+    pub fn CRX(theta: f64) -> Gate {
+        let zero = Complex::zero();
+        let one = Complex::one();
+        let cos = Complex::new((theta / 2.).cos(), 0.);
+        let isin = Complex::new(0., (theta / 2.).sin());
+
+        Gate {
+            size: 2,
+            matrix: array![
+                [one, zero, zero, zero],
+                [zero, one, zero, zero],
+                [zero, zero, cos, -isin],
+                [zero, zero, -isin, cos]
+            ],
+        }
+    }
+
+    pub fn CRY(theta: f64) -> Gate {
+        let zero = Complex::zero();
+        let one = Complex::one();
+        let cos = Complex::new((theta / 2.).cos(), 0.);
+        let sin = Complex::new((theta / 2.).sin(), 0.);
+
+        Gate {
+            size: 2,
+            matrix: array![
+                [one, zero, zero, zero],
+                [zero, one, zero, zero],
+                [zero, zero, cos, -sin],
+                [zero, zero, sin, cos]
+            ],
+        }
+    }
+
+    pub fn CRZ(theta: f64) -> Gate {
+        let zero = Complex::zero();
+        let one = Complex::one();
+        let cos = (theta / 2.).cos();
+        let sin = (theta / 2.).sin();
+
+        Gate {
+            size: 2,
+            matrix: array![
+                [one, zero, zero, zero],
+                [zero, one, zero, zero],
+                [zero, zero, Complex::new(cos, -sin), zero],
+                [zero, zero, zero, Complex::new(cos, sin)]
+            ],
+        }
+    }
+
+    pub fn RZZ(theta: f64) -> Gate {
+        let zero = Complex::zero();
+        let cos = (theta / 2.).cos();
+        let sin = (theta / 2.).sin();
+        let even = Complex::new(cos, -sin);
+        let odd = Complex::new(cos, sin);
+
+        Gate {
+            size: 2,
+            matrix: array![
+                [even, zero, zero, zero],
+                [zero, odd, zero, zero],
+                [zero, zero, odd, zero],
+                [zero, zero, zero, even]
+            ],
+        }
+    }
+
     pub fn SWAP() -> Gate {
         let zero = Complex::zero();
         let one = Complex::one();
@@ -1,8 +1,9 @@
 // use crate::algebra::complex::Complex;
-use ndarray::prelude::Array2;
+use ndarray::{prelude::Array2, s};
 use num::complex::Complex;
+use num::One;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Gate {
     pub size: usize,
     pub matrix: Array2<Complex<f64>>,
@@ -23,4 +24,44 @@ impl Gate {
     pub fn matrix(&self) -> &Array2<Complex<f64>> {
         &self.matrix
     }
+
+    pub fn dagger(&self) -> Gate {
+        Gate {
+            size: self.size,
+            matrix: self.matrix.t().mapv(|v| v.conj()),
+        }
+    }
+
+    // blockdiag(I, U), the control being the first of the qubits it is applied to
+    pub fn controlled(&self) -> Gate {
+        let dim = self.matrix.nrows();
+        let mut matrix = Array2::<Complex<f64>>::zeros((2 * dim, 2 * dim));
+        for k in 0..dim {
+            matrix[[k, k]] = Complex::one();
+        }
+        matrix.slice_mut(s![dim.., dim..]).assign(&self.matrix);
+        Gate {
+            size: self.size + 1,
+            matrix,
+        }
+    }
+
+    // U^exponent by repeated squaring
+    pub fn power(&self, exponent: usize) -> Gate {
+        let dim = self.matrix.nrows();
+        let mut result = Array2::<Complex<f64>>::from_diag_elem(dim, Complex::one());
+        let mut square = self.matrix.clone();
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.dot(&square);
+            }
+            square = square.dot(&square);
+            exponent >>= 1;
+        }
+        Gate {
+            size: self.size,
+            matrix: result,
+        }
+    }
 }
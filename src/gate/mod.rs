@@ -1,2 +1,4 @@
+#[allow(non_snake_case)]
 pub mod base_gates;
+#[allow(clippy::module_inception)]
 pub mod gate;
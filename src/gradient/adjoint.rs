@@ -0,0 +1,91 @@
+use crate::circuit::parameter::{ParameterizedCircuit, ParameterizedInstruction};
+use crate::operator::pauli::SparsePauliOp;
+use num::complex::Complex;
+
+// reverse-mode differentiation: one forward pass to |psi> = U_N..U_1|0>, then walking
+// back with |psi> and |lambda> = O|psi>, each parameterized gate contributes
+// 2 Re <lambda| -iG |psi> at its position in the circuit
+pub fn adjoint_gradient(pc: &ParameterizedCircuit, op: &SparsePauliOp, values: &[f64]) -> Vec<f64> {
+    adjoint_value_and_gradient(pc, op, values).1
+}
+
+pub fn adjoint_value_and_gradient(
+    pc: &ParameterizedCircuit,
+    op: &SparsePauliOp,
+    values: &[f64],
+) -> (f64, Vec<f64>) {
+    let map = pc.values_to_map(values);
+    let op = op.expand(pc.size());
+    let mut psi = pc.run(&map);
+    let mut lambda = psi.clone();
+    lambda.elements = op.apply_to(&psi.elements);
+    let value = psi.inner(&lambda).re;
+
+    let minus_i = Complex::new(0., -1.);
+    let mut gradient = vec![0.; pc.num_parameters()];
+    for instruction in pc.instructions().iter().rev() {
+        let qubits = instruction.qubits().iter().collect::<Vec<_>>();
+        let gate = instruction.gate(&map);
+        if let ParameterizedInstruction::Rotation {
+            rotation, angle, ..
+        } = instruction
+        {
+            if let Some(parameter) = angle.parameter() {
+                let mut mu = psi.clone();
+                mu.apply(&qubits, &rotation.generator());
+                let k = pc.parameters().iter().position(|p| p == parameter).unwrap();
+                gradient[k] += 2. * angle.scale() * (minus_i * lambda.inner(&mu)).re;
+            }
+        }
+        let dagger = gate.dagger();
+        psi.apply(&qubits, &dagger);
+        lambda.apply(&qubits, &dagger);
+    }
+    (value, gradient)
+}
+
+#[cfg(test)]
+use crate::circuit::parameter::Parameter;
+#[cfg(test)]
+use crate::gradient::gradient::parameter_shift_gradient;
+
+#[test]
+fn adjoint_gradient_test() {
+    let params = (0..6)
+        .map(|i| Parameter::new(&format!("t{}", i)))
+        .collect::<Vec<_>>();
+    let mut pc = ParameterizedCircuit::new(3);
+    pc.H(0);
+    pc.RX(0, &params[0]);
+    pc.RY(1, &params[1] * 2. - 0.2);
+    pc.CNOT(0, 1);
+    pc.RZ(2, &params[2]);
+    pc.CRX(1, 2, &params[3]);
+    pc.CP(2, 0, &params[4]);
+    pc.P(1, &params[0] * 0.5);
+    pc.CRY(0, 2, -&params[5]);
+    pc.CRZ(2, 1, &params[1]);
+    pc.RZZ(0, 2, &params[3] * 0.5);
+    pc.Toffoli(0, 1, 2);
+    pc.RY(0, 0.3);
+    let op: SparsePauliOp = "0.5 + Z0 Z1 - 0.4*X2 + 0.3*Y0 Y2 + 0.8*X1".parse().unwrap();
+    let values = [0.3, -1.2, 0.8, 2.1, -0.5, 1.4];
+
+    let (value, adjoint) = adjoint_value_and_gradient(&pc, &op, &values);
+    assert!((value - pc.run_values(&values).expectation(&op)).abs() < 1e-10);
+    let shift = parameter_shift_gradient(&pc, &op, &values);
+    for (a, s) in adjoint.iter().zip(shift.iter()) {
+        assert!((a - s).abs() < 1e-10);
+    }
+}
+
+#[test]
+fn adjoint_gradient_smaller_operator_test() {
+    let theta = Parameter::new("theta");
+    let mut pc = ParameterizedCircuit::new(2);
+    pc.RY(0, &theta);
+    pc.CNOT(0, 1);
+    let z: SparsePauliOp = "Z0".parse().unwrap();
+    let gradient = adjoint_gradient(&pc, &z, &[0.6]);
+    assert!((gradient[0] + 0.6f64.sin()).abs() < 1e-10);
+}
@@ -0,0 +1,212 @@
+use crate::circuit::parameter::{ParameterizedCircuit, ParameterizedInstruction};
+use crate::gradient::adjoint::adjoint_gradient;
+use crate::operator::pauli::SparsePauliOp;
+use crate::state::state::State;
+use std::collections::HashMap;
+
+pub const DEFAULT_FINITE_DIFFERENCE_STEP: f64 = 1e-6;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GradientMethod {
+    ParameterShift,
+    Adjoint,
+    FiniteDifference(f64),
+}
+
+pub fn expectation_value(pc: &ParameterizedCircuit, op: &SparsePauliOp, values: &[f64]) -> f64 {
+    pc.run_values(values).expectation(op)
+}
+
+// runs the circuit with the angle of the instruction at `shifted` moved by `shift`
+fn run_shifted(
+    pc: &ParameterizedCircuit,
+    values: &HashMap<String, f64>,
+    shifted: usize,
+    shift: f64,
+) -> State {
+    let mut state = State::new(pc.size());
+    for (index, instruction) in pc.instructions().iter().enumerate() {
+        match instruction {
+            ParameterizedInstruction::Rotation {
+                rotation,
+                qubits,
+                angle,
+            } if index == shifted => {
+                let qubits = qubits.iter().collect::<Vec<_>>();
+                state.apply(&qubits, &rotation.gate(angle.bind(values) + shift));
+            }
+            _ => instruction.apply(&mut state, values),
+        }
+    }
+    state
+}
+
+// a parameter used by several gates, or through `a * theta + b`, is handled by the
+// chain rule: each gate contributes a * d<O>/d(angle)
+pub fn parameter_shift_gradient(
+    pc: &ParameterizedCircuit,
+    op: &SparsePauliOp,
+    values: &[f64],
+) -> Vec<f64> {
+    parameter_shift_gradient_with(pc, values, |state| state.expectation(op))
+}
+
+// shift rule applied to any function of the output state, e.g. a shot-based estimate
+pub fn parameter_shift_gradient_with<F>(
+    pc: &ParameterizedCircuit,
+    values: &[f64],
+    measure: F,
+) -> Vec<f64>
+where
+    F: Fn(&State) -> f64,
+{
+    let map = pc.values_to_map(values);
+    let mut gradient = vec![0.; pc.num_parameters()];
+    for (index, instruction) in pc.instructions().iter().enumerate() {
+        if let ParameterizedInstruction::Rotation {
+            rotation, angle, ..
+        } = instruction
+        {
+            let parameter = match angle.parameter() {
+                Some(parameter) => parameter,
+                None => continue,
+            };
+            let k = pc.parameters().iter().position(|p| p == parameter).unwrap();
+            let derivative = rotation
+                .shift_rule()
+                .iter()
+                .map(|&(coefficient, shift)| {
+                    coefficient * measure(&run_shifted(pc, &map, index, shift))
+                })
+                .sum::<f64>();
+            gradient[k] += angle.scale() * derivative;
+        }
+    }
+    gradient
+}
+
+// central differences for any objective, used when no shift rule applies
+pub fn finite_difference_gradient<F>(objective: F, values: &[f64], step: f64) -> Vec<f64>
+where
+    F: Fn(&[f64]) -> f64,
+{
+    let mut shifted = values.to_vec();
+    (0..values.len())
+        .map(|k| {
+            shifted[k] = values[k] + step;
+            let plus = objective(&shifted);
+            shifted[k] = values[k] - step;
+            let minus = objective(&shifted);
+            shifted[k] = values[k];
+            (plus - minus) / (2. * step)
+        })
+        .collect()
+}
+
+pub fn gradient(
+    pc: &ParameterizedCircuit,
+    op: &SparsePauliOp,
+    values: &[f64],
+    method: GradientMethod,
+) -> Vec<f64> {
+    match method {
+        GradientMethod::ParameterShift => parameter_shift_gradient(pc, op, values),
+        GradientMethod::Adjoint => adjoint_gradient(pc, op, values),
+        GradientMethod::FiniteDifference(step) => {
+            finite_difference_gradient(|v| expectation_value(pc, op, v), values, step)
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::circuit::parameter::Parameter;
+
+#[test]
+fn single_rotation_gradient_test() {
+    let theta = Parameter::new("theta");
+    let z: SparsePauliOp = "Z0".parse().unwrap();
+    let y: SparsePauliOp = "Y0".parse().unwrap();
+    for value in [-2.1, -0.3, 0., 0.7, 1.9] {
+        // <Z> = cos(theta) for RX and RY
+        let mut pc = ParameterizedCircuit::new(1);
+        pc.RY(0, &theta);
+        let grad = parameter_shift_gradient(&pc, &z, &[value]);
+        assert!((grad[0] + value.sin()).abs() < 1e-10);
+
+        // RX(theta)|0> has <Y> = -sin(theta)
+        let mut pc = ParameterizedCircuit::new(1);
+        pc.RX(0, &theta);
+        let grad = parameter_shift_gradient(&pc, &y, &[value]);
+        assert!((grad[0] + value.cos()).abs() < 1e-10);
+
+        // H then RZ(theta) has <Y> = sin(theta)
+        let mut pc = ParameterizedCircuit::new(1);
+        pc.H(0);
+        pc.RZ(0, &theta);
+        let grad = parameter_shift_gradient(&pc, &y, &[value]);
+        assert!((grad[0] - value.cos()).abs() < 1e-10);
+    }
+}
+
+#[test]
+fn controlled_gradient_test() {
+    let theta = Parameter::new("theta");
+    let x1: SparsePauliOp = "X1".parse().unwrap();
+    let z1: SparsePauliOp = "Z1".parse().unwrap();
+    for value in [-1.3, 0.4, 2.2] {
+        // CP on |++> gives <X1> = (1 + cos(theta)) / 2
+        let mut pc = ParameterizedCircuit::new(2);
+        pc.H(0);
+        pc.H(1);
+        pc.CP(0, 1, &theta);
+        let grad = parameter_shift_gradient(&pc, &x1, &[value]);
+        assert!((grad[0] + value.sin() / 2.).abs() < 1e-10);
+
+        // CRX and CRY with a control in |+> give <Z1> = (1 + cos(theta)) / 2
+        for controlled_y in [false, true] {
+            let mut pc = ParameterizedCircuit::new(2);
+            pc.H(0);
+            if controlled_y {
+                pc.CRY(0, 1, &theta);
+            } else {
+                pc.CRX(0, 1, &theta);
+            }
+            let grad = parameter_shift_gradient(&pc, &z1, &[value]);
+            assert!((grad[0] + value.sin() / 2.).abs() < 1e-10);
+        }
+    }
+}
+
+#[test]
+fn chain_rule_gradient_test() {
+    let theta = Parameter::new("theta");
+    let phi = Parameter::new("phi");
+    let mut pc = ParameterizedCircuit::new(3);
+    pc.RY(0, &theta * 2. + 0.3);
+    pc.H(1);
+    pc.CRZ(0, 1, &phi);
+    pc.RX(2, &theta);
+    pc.CNOT(1, 2);
+    pc.CRY(2, 0, -&phi);
+    pc.P(1, &theta * 0.5);
+    pc.RZ(2, 1.1);
+    let op: SparsePauliOp = "Z0 + 0.5*X1 Y2 - 0.7*Z1 Z2 + 0.2*X0 X1".parse().unwrap();
+    let values = [0.4, -0.9];
+    let shift = gradient(&pc, &op, &values, GradientMethod::ParameterShift);
+    let finite = gradient(
+        &pc,
+        &op,
+        &values,
+        GradientMethod::FiniteDifference(DEFAULT_FINITE_DIFFERENCE_STEP),
+    );
+    for (s, f) in shift.iter().zip(finite.iter()) {
+        assert!((s - f).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn finite_difference_gradient_test() {
+    let grad = finite_difference_gradient(|v| v[0] * v[0] + 3. * v[1], &[1.5, 2.], 1e-5);
+    assert!((grad[0] - 3.).abs() < 1e-8);
+    assert!((grad[1] - 3.).abs() < 1e-8);
+}
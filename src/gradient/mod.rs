@@ -0,0 +1,3 @@
+pub mod adjoint;
+#[allow(clippy::module_inception)]
+pub mod gradient;
@@ -0,0 +1,14 @@
+pub mod algebra;
+pub mod algorithm;
+pub mod arithmetic;
+pub mod chemistry;
+pub mod circuit;
+pub mod evolution;
+pub mod gate;
+pub mod gradient;
+pub mod operator;
+pub mod optimizer;
+pub mod qubit;
+pub mod state;
+pub mod synthesis;
+pub mod validate;
@@ -1,11 +1,4 @@
-mod algebra;
-mod circuit;
-mod gate;
-mod state;
-mod validate;
-
-use circuit::circuit::QuantumCircuit;
-use gate::base_gates::{DoubleGate, SingleGate};
+use diraq::circuit::circuit::QuantumCircuit;
 
 fn main() {
     let mut qc = QuantumCircuit::new(3);
@@ -0,0 +1,466 @@
+use crate::circuit::circuit::QuantumCircuit;
+use crate::gate::base_gates::{DoubleGate, SingleGate};
+use crate::gate::gate::Gate;
+use crate::operator::pauli::{Pauli, PauliString, SparsePauliOp};
+use crate::state::expectation::{parity, Estimate};
+use crate::state::state::State;
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GroupingStrategy {
+    QubitWise,
+    General,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CliffordGate {
+    H(usize),
+    S(usize),
+    Sdg(usize),
+    CNOT(usize, usize),
+    CZ(usize, usize),
+}
+
+impl CliffordGate {
+    pub fn qubits(&self) -> Vec<usize> {
+        match *self {
+            CliffordGate::H(q) | CliffordGate::S(q) | CliffordGate::Sdg(q) => vec![q],
+            CliffordGate::CNOT(c, t) | CliffordGate::CZ(c, t) => vec![c, t],
+        }
+    }
+
+    pub fn gate(&self) -> Gate {
+        match self {
+            CliffordGate::H(_) => SingleGate::H(),
+            CliffordGate::S(_) => SingleGate::S(),
+            CliffordGate::Sdg(_) => SingleGate::Sdg(),
+            CliffordGate::CNOT(_, _) => DoubleGate::CNOT(),
+            CliffordGate::CZ(_, _) => DoubleGate::CZ(),
+        }
+    }
+
+    pub fn apply(&self, state: &mut State) {
+        let qubits = self.qubits();
+        state.apply(&qubits.iter().collect::<Vec<_>>(), &self.gate());
+    }
+
+    pub fn apply_to_circuit(&self, qc: &mut QuantumCircuit) {
+        match *self {
+            CliffordGate::H(q) => qc.H(q),
+            CliffordGate::S(q) => qc.S(q),
+            CliffordGate::Sdg(q) => qc.Sdg(q),
+            CliffordGate::CNOT(c, t) => qc.CNOT(c, t),
+            CliffordGate::CZ(c, t) => qc.CZ(c, t),
+        }
+    }
+
+    // U (sign * P) U^dagger, following the tableau update rules of Aaronson and Gottesman
+    pub fn conjugate(&self, sign: f64, pauli: &PauliString) -> (f64, PauliString) {
+        let mut sign = sign;
+        let mut pauli = pauli.clone();
+        let bits = |p: &PauliString, q: usize| (p.get(q).has_x(), p.get(q).has_z());
+        match *self {
+            CliffordGate::H(q) => {
+                let (x, z) = bits(&pauli, q);
+                if x && z {
+                    sign = -sign;
+                }
+                pauli.set(q, Pauli::from_bits(z, x));
+            }
+            CliffordGate::S(q) => {
+                let (x, z) = bits(&pauli, q);
+                if x && z {
+                    sign = -sign;
+                }
+                pauli.set(q, Pauli::from_bits(x, z ^ x));
+            }
+            CliffordGate::Sdg(q) => {
+                for _ in 0..3 {
+                    let (s, p) = CliffordGate::S(q).conjugate(sign, &pauli);
+                    sign = s;
+                    pauli = p;
+                }
+            }
+            CliffordGate::CNOT(c, t) => {
+                let (xc, zc) = bits(&pauli, c);
+                let (xt, zt) = bits(&pauli, t);
+                if xc && zt && !(xt ^ zc) {
+                    sign = -sign;
+                }
+                pauli.set(c, Pauli::from_bits(xc, zc ^ zt));
+                pauli.set(t, Pauli::from_bits(xt ^ xc, zt));
+            }
+            CliffordGate::CZ(c, t) => {
+                for gate in [
+                    CliffordGate::H(t),
+                    CliffordGate::CNOT(c, t),
+                    CliffordGate::H(t),
+                ] {
+                    let (s, p) = gate.conjugate(sign, &pauli);
+                    sign = s;
+                    pauli = p;
+                }
+            }
+        }
+        (sign, pauli)
+    }
+}
+
+// a set of mutually commuting terms measured with a single basis change;
+// after applying `gates`, terms[k] becomes the diagonal operator
+// diagonals[k].0 * Z^(diagonals[k].1)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasurementGroup {
+    pub terms: Vec<usize>,
+    pub gates: Vec<CliffordGate>,
+    pub diagonals: Vec<(f64, usize)>,
+}
+
+impl MeasurementGroup {
+    pub fn apply(&self, state: &mut State) {
+        for gate in self.gates.iter() {
+            gate.apply(state);
+        }
+    }
+
+    pub fn apply_to_circuit(&self, qc: &mut QuantumCircuit) {
+        for gate in self.gates.iter() {
+            gate.apply_to_circuit(qc);
+        }
+    }
+
+    // mean and variance of the per-shot value sum_k c_k <terms[k]>
+    fn statistics(&self, op: &SparsePauliOp, counts: &HashMap<usize, usize>) -> (f64, f64, usize) {
+        let shots = counts.values().sum::<usize>();
+        let shot_value = |index: usize| {
+            self.terms
+                .iter()
+                .zip(self.diagonals.iter())
+                .map(|(&term, &(sign, mask))| op.terms()[term].0.re * sign * parity(index, mask))
+                .sum::<f64>()
+        };
+        let mean = counts
+            .iter()
+            .map(|(&index, &count)| shot_value(index) * count as f64)
+            .sum::<f64>()
+            / shots as f64;
+        let variance = counts
+            .iter()
+            .map(|(&index, &count)| (shot_value(index) - mean).powi(2) * count as f64)
+            .sum::<f64>()
+            / (shots.max(2) - 1) as f64;
+        (mean, variance, shots)
+    }
+}
+
+fn conflict_graph(paulis: &[&PauliString], strategy: GroupingStrategy) -> Vec<Vec<usize>> {
+    let mut graph = vec![vec![]; paulis.len()];
+    for i in 0..paulis.len() {
+        for j in i + 1..paulis.len() {
+            let compatible = match strategy {
+                GroupingStrategy::QubitWise => paulis[i].qubitwise_commutes(paulis[j]),
+                GroupingStrategy::General => paulis[i].commutes(paulis[j]),
+            };
+            if !compatible {
+                graph[i].push(j);
+                graph[j].push(i);
+            }
+        }
+    }
+    graph
+}
+
+// greedy coloring visiting vertices from the largest degree (Welsh-Powell)
+fn color(graph: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut order = (0..graph.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&v| std::cmp::Reverse(graph[v].len()));
+    let mut colors: Vec<Option<usize>> = vec![None; graph.len()];
+    let mut classes: Vec<Vec<usize>> = vec![];
+    for &v in order.iter() {
+        let used = graph[v]
+            .iter()
+            .filter_map(|&u| colors[u])
+            .collect::<Vec<_>>();
+        let c = (0..).find(|c| !used.contains(c)).unwrap();
+        colors[v] = Some(c);
+        if c == classes.len() {
+            classes.push(vec![]);
+        }
+        classes[c].push(v);
+    }
+    for class in classes.iter_mut() {
+        class.sort();
+    }
+    classes
+}
+
+fn qubitwise_basis_change(size: usize, paulis: &[&PauliString]) -> Vec<CliffordGate> {
+    let mut gates = vec![];
+    for q in 0..size {
+        match paulis.iter().map(|p| p.get(q)).find(|&p| p != Pauli::I) {
+            Some(Pauli::X) => gates.push(CliffordGate::H(q)),
+            Some(Pauli::Y) => {
+                gates.push(CliffordGate::Sdg(q));
+                gates.push(CliffordGate::H(q));
+            }
+            _ => {}
+        }
+    }
+    gates
+}
+
+// finds a clifford circuit mapping every (mutually commuting) pauli string to a Z string:
+// each round picks a row with an X part, collects it onto a single qubit with CNOTs,
+// clears its Z part with CZs and turns the remaining X or Y into Z
+fn general_basis_change(paulis: &[&PauliString]) -> Vec<CliffordGate> {
+    let mut rows = paulis.iter().map(|&p| (1., p.clone())).collect::<Vec<_>>();
+    let mut gates = vec![];
+    let mut push = |gate: CliffordGate, rows: &mut Vec<(f64, PauliString)>| {
+        for row in rows.iter_mut() {
+            *row = gate.conjugate(row.0, &row.1);
+        }
+        gates.push(gate);
+    };
+    while let Some(row) = rows.iter().position(|(_, p)| p.x_mask() != 0) {
+        let size = rows[row].1.size();
+        let pivot = rows[row].1.x_mask().trailing_zeros() as usize;
+        for t in pivot + 1..size {
+            if rows[row].1.get(t).has_x() {
+                push(CliffordGate::CNOT(pivot, t), &mut rows);
+            }
+        }
+        for t in 0..size {
+            if t != pivot && rows[row].1.get(t).has_z() {
+                push(CliffordGate::CZ(pivot, t), &mut rows);
+            }
+        }
+        if rows[row].1.get(pivot) == Pauli::Y {
+            push(CliffordGate::Sdg(pivot), &mut rows);
+        }
+        push(CliffordGate::H(pivot), &mut rows);
+    }
+    gates
+}
+
+// the non-identity terms split into groups measured by one circuit each, from a greedy
+// coloring of the conflict graph: few groups, but not necessarily the fewest possible
+pub fn group_commuting(op: &SparsePauliOp, strategy: GroupingStrategy) -> Vec<MeasurementGroup> {
+    let measured = (0..op.len())
+        .filter(|&i| !op.terms()[i].1.is_identity())
+        .collect::<Vec<_>>();
+    let paulis = measured
+        .iter()
+        .map(|&i| &op.terms()[i].1)
+        .collect::<Vec<_>>();
+    let classes = color(&conflict_graph(&paulis, strategy));
+
+    classes
+        .into_iter()
+        .map(|class| {
+            let members = class.iter().map(|&v| paulis[v]).collect::<Vec<_>>();
+            let gates = match strategy {
+                GroupingStrategy::QubitWise => qubitwise_basis_change(op.size(), &members),
+                GroupingStrategy::General => general_basis_change(&members),
+            };
+            let diagonals = members
+                .iter()
+                .map(|&p| {
+                    let (sign, diagonal) = gates
+                        .iter()
+                        .fold((1., p.clone()), |(s, q), gate| gate.conjugate(s, &q));
+                    assert_eq!(0, diagonal.x_mask());
+                    (sign, diagonal.z_mask())
+                })
+                .collect();
+            MeasurementGroup {
+                terms: class.iter().map(|&v| measured[v]).collect(),
+                gates,
+                diagonals,
+            }
+        })
+        .collect()
+}
+
+// reassembles <op> from the counts measured for each group, in the order of `groups`
+pub fn estimate_from_counts(
+    op: &SparsePauliOp,
+    groups: &[MeasurementGroup],
+    counts: &[HashMap<usize, usize>],
+) -> Estimate {
+    assert_eq!(groups.len(), counts.len());
+    let mut mean = op
+        .terms()
+        .iter()
+        .filter(|(_, p)| p.is_identity())
+        .map(|(c, _)| c.re)
+        .sum::<f64>();
+    let mut variance = 0.;
+    for (group, group_counts) in groups.iter().zip(counts.iter()) {
+        let (group_mean, group_variance, shots) = group.statistics(op, group_counts);
+        mean += group_mean;
+        variance += group_variance / shots as f64;
+    }
+    Estimate {
+        mean,
+        standard_error: variance.sqrt(),
+    }
+}
+
+impl State {
+    pub fn estimate_expectation_grouped(
+        &self,
+        op: &SparsePauliOp,
+        strategy: GroupingStrategy,
+        shots: usize,
+    ) -> Estimate {
+        let op = op.expand(self.size());
+        let groups = group_commuting(&op, strategy);
+        let counts = groups
+            .iter()
+            .map(|group| {
+                let mut rotated = self.clone();
+                group.apply(&mut rotated);
+                rotated.sample(shots)
+            })
+            .collect::<Vec<_>>();
+        estimate_from_counts(&op, &groups, &counts)
+    }
+}
+
+#[cfg(test)]
+fn clifford_unitary(
+    size: usize,
+    gates: &[CliffordGate],
+) -> ndarray::prelude::Array2<num::complex::Complex<f64>> {
+    use ndarray::prelude::Array2;
+    use num::complex::Complex;
+
+    let dim = 1 << size;
+    let mut unitary = Array2::<Complex<f64>>::zeros((dim, dim));
+    for j in 0..dim {
+        let mut state = State::new(size);
+        state.elements.fill(Complex::new(0., 0.));
+        state.elements[j] = Complex::new(1., 0.);
+        for gate in gates.iter() {
+            gate.apply(&mut state);
+        }
+        unitary.column_mut(j).assign(&state.elements);
+    }
+    unitary
+}
+
+#[cfg(test)]
+fn assert_conjugates_to(
+    unitary: &ndarray::prelude::Array2<num::complex::Complex<f64>>,
+    pauli: &PauliString,
+    sign: f64,
+    image: &PauliString,
+) {
+    let dagger = unitary.t().mapv(|v| v.conj());
+    let rotated = unitary.dot(&pauli.to_matrix()).dot(&dagger);
+    let expected = image.to_matrix().mapv(|v| v * sign);
+    assert!(rotated
+        .iter()
+        .zip(expected.iter())
+        .all(|(a, b)| (a - b).norm() < 1e-10));
+}
+
+#[cfg(test)]
+fn assert_diagonalizes(op: &SparsePauliOp, group: &MeasurementGroup) {
+    let unitary = clifford_unitary(op.size(), &group.gates);
+    for (&term, &(sign, mask)) in group.terms.iter().zip(group.diagonals.iter()) {
+        let paulis = (0..op.size())
+            .map(|q| {
+                if (mask >> q) & 1 == 1 {
+                    Pauli::Z
+                } else {
+                    Pauli::I
+                }
+            })
+            .collect();
+        assert_conjugates_to(
+            &unitary,
+            &op.terms()[term].1,
+            sign,
+            &PauliString::new(paulis),
+        );
+    }
+}
+
+#[test]
+fn conjugate_test() {
+    let op: SparsePauliOp = "X0 Y1 + Y0 Z1 + Z0 X1 + Y0 Y1 + X0 + Z1".parse().unwrap();
+    let gates = [
+        CliffordGate::H(0),
+        CliffordGate::S(1),
+        CliffordGate::Sdg(0),
+        CliffordGate::CNOT(0, 1),
+        CliffordGate::CNOT(1, 0),
+        CliffordGate::CZ(0, 1),
+    ];
+    for gate in gates.iter() {
+        let unitary = clifford_unitary(2, &[*gate]);
+        for (_, pauli) in op.terms().iter() {
+            let (sign, image) = gate.conjugate(1., pauli);
+            assert_conjugates_to(&unitary, pauli, sign, &image);
+        }
+    }
+}
+
+#[test]
+fn group_commuting_test() {
+    let op: SparsePauliOp = "0.5 + X0 X1 + Y0 Y1 + Z0 Z1".parse().unwrap();
+    let qubitwise = group_commuting(&op, GroupingStrategy::QubitWise);
+    assert_eq!(3, qubitwise.len());
+    let general = group_commuting(&op, GroupingStrategy::General);
+    assert_eq!(1, general.len());
+    assert_eq!(vec![1, 2, 3], general[0].terms);
+    for group in qubitwise.iter().chain(general.iter()) {
+        assert_diagonalizes(&op, group);
+    }
+
+    let op: SparsePauliOp = "X0 Z1 Y2 + Z0 X1 Z2 + Y0 Y1 X2 + X0 X1 X2 + Z0 Z1 Z2 + Y1 Z2"
+        .parse()
+        .unwrap();
+    for strategy in [GroupingStrategy::QubitWise, GroupingStrategy::General] {
+        let groups = group_commuting(&op, strategy);
+        let mut covered = groups
+            .iter()
+            .flat_map(|g| g.terms.clone())
+            .collect::<Vec<_>>();
+        covered.sort();
+        assert_eq!((0..op.len()).collect::<Vec<_>>(), covered);
+        for group in groups.iter() {
+            assert_diagonalizes(&op, group);
+        }
+    }
+}
+
+#[test]
+fn estimate_expectation_grouped_test() {
+    let mut state = State::new(3);
+    state.apply(&[&0], &SingleGate::RY(0.7));
+    state.apply(&[&1], &SingleGate::RX(1.9));
+    state.apply(&[&0, &2], &DoubleGate::CNOT());
+    state.apply(&[&2], &SingleGate::H());
+    let op: SparsePauliOp = "-1.2 + 0.4*X0 X1 + 0.4*Y0 Y1 + 0.7*Z0 Z1 - 0.3*X2 + 0.5*Z0 Y1 X2"
+        .parse()
+        .unwrap();
+    let exact = state.expectation(&op);
+    for strategy in [GroupingStrategy::QubitWise, GroupingStrategy::General] {
+        let estimate = state.estimate_expectation_grouped(&op, strategy, 20000);
+        assert!(estimate.standard_error > 0.);
+        assert!((estimate.mean - exact).abs() < 6. * estimate.standard_error);
+    }
+
+    let mut qc = QuantumCircuit::new(2);
+    qc.H(0);
+    qc.CNOT(0, 1);
+    let bell: SparsePauliOp = "X0 X1 - Y0 Y1 + Z0 Z1".parse().unwrap();
+    let groups = group_commuting(&bell, GroupingStrategy::General);
+    assert_eq!(1, groups.len());
+    groups[0].apply_to_circuit(&mut qc);
+    let counts = vec![qc.state.sample(1000)];
+    let estimate = estimate_from_counts(&bell, &groups, &counts);
+    assert!((estimate.mean - 3.).abs() < 1e-10);
+}
@@ -0,0 +1,175 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Boundary {
+    Open,
+    Periodic,
+}
+
+// sites of a two dimensional bravais lattice with one site per unit cell, numbered
+// x + width * y. nearest and next nearest neighbours are the pairs at the smallest and
+// second smallest distance, measured to the closest periodic image
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lattice {
+    width: usize,
+    height: usize,
+    positions: Vec<(f64, f64)>,
+    bonds: Vec<(usize, usize)>,
+    next_nearest: Vec<(usize, usize)>,
+}
+
+impl Lattice {
+    pub fn chain(length: usize, boundary: Boundary) -> Lattice {
+        Lattice::build(
+            (length, 1),
+            [(1., 0.), (0., 1.)],
+            (boundary == Boundary::Periodic, false),
+        )
+    }
+
+    // two legs of `length` sites joined by rungs, the boundary applies along the legs
+    pub fn ladder(length: usize, boundary: Boundary) -> Lattice {
+        Lattice::build(
+            (length, 2),
+            [(1., 0.), (0., 1.)],
+            (boundary == Boundary::Periodic, false),
+        )
+    }
+
+    pub fn square(width: usize, height: usize, boundary: Boundary) -> Lattice {
+        let periodic = boundary == Boundary::Periodic;
+        Lattice::build((width, height), [(1., 0.), (0., 1.)], (periodic, periodic))
+    }
+
+    pub fn triangular(width: usize, height: usize, boundary: Boundary) -> Lattice {
+        let periodic = boundary == Boundary::Periodic;
+        Lattice::build(
+            (width, height),
+            [(1., 0.), (0.5, 3f64.sqrt() / 2.)],
+            (periodic, periodic),
+        )
+    }
+
+    fn build(
+        (width, height): (usize, usize),
+        [a1, a2]: [(f64, f64); 2],
+        periodic: (bool, bool),
+    ) -> Lattice {
+        assert!(width > 0 && height > 0);
+        let num_sites = width * height;
+        let positions = (0..num_sites)
+            .map(|site| {
+                let (x, y) = ((site % width) as f64, (site / width) as f64);
+                (x * a1.0 + y * a2.0, x * a1.1 + y * a2.1)
+            })
+            .collect::<Vec<_>>();
+
+        let images = |periodic: bool| {
+            if periodic {
+                vec![-1., 0., 1.]
+            } else {
+                vec![0.]
+            }
+        };
+        let mut distances = vec![];
+        for i in 0..num_sites {
+            for j in i + 1..num_sites {
+                let mut distance = f64::INFINITY;
+                for n1 in images(periodic.0) {
+                    for n2 in images(periodic.1) {
+                        let dx = positions[j].0 - positions[i].0
+                            + n1 * width as f64 * a1.0
+                            + n2 * height as f64 * a2.0;
+                        let dy = positions[j].1 - positions[i].1
+                            + n1 * width as f64 * a1.1
+                            + n2 * height as f64 * a2.1;
+                        distance = distance.min((dx * dx + dy * dy).sqrt());
+                    }
+                }
+                distances.push((i, j, distance));
+            }
+        }
+
+        let mut shells = distances.iter().map(|&(_, _, d)| d).collect::<Vec<_>>();
+        shells.sort_by(f64::total_cmp);
+        shells.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+        let shell = |k: usize| {
+            distances
+                .iter()
+                .filter(|&&(_, _, d)| shells.get(k).is_some_and(|s| (d - s).abs() < 1e-9))
+                .map(|&(i, j, _)| (i, j))
+                .collect::<Vec<_>>()
+        };
+        Lattice {
+            width,
+            height,
+            bonds: shell(0),
+            next_nearest: shell(1),
+            positions,
+        }
+    }
+
+    pub fn num_sites(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn position(&self, site: usize) -> (f64, f64) {
+        self.positions[site]
+    }
+
+    // nearest neighbour pairs (i, j) with i < j
+    pub fn bonds(&self) -> &[(usize, usize)] {
+        &self.bonds
+    }
+
+    pub fn next_nearest_bonds(&self) -> &[(usize, usize)] {
+        &self.next_nearest
+    }
+}
+
+#[test]
+fn lattice_test() {
+    let chain = Lattice::chain(5, Boundary::Open);
+    assert_eq!(vec![(0, 1), (1, 2), (2, 3), (3, 4)], chain.bonds());
+    assert_eq!(vec![(0, 2), (1, 3), (2, 4)], chain.next_nearest_bonds());
+    let ring = Lattice::chain(5, Boundary::Periodic);
+    assert_eq!(5, ring.bonds().len());
+    assert!(ring.bonds().contains(&(0, 4)));
+    assert!(ring.next_nearest_bonds().contains(&(0, 3)));
+
+    // legs and rungs, diagonals across the plaquettes
+    let ladder = Lattice::ladder(4, Boundary::Open);
+    assert_eq!(2 * 3 + 4, ladder.bonds().len());
+    assert_eq!(2 * 3, ladder.next_nearest_bonds().len());
+    assert_eq!(
+        2 * 4 + 4,
+        Lattice::ladder(4, Boundary::Periodic).bonds().len()
+    );
+
+    let square = Lattice::square(3, 4, Boundary::Open);
+    assert_eq!(2 * 4 + 3 * 3, square.bonds().len());
+    assert_eq!(2 * 2 * 3, square.next_nearest_bonds().len());
+    let torus = Lattice::square(3, 3, Boundary::Periodic);
+    assert_eq!(2 * 9, torus.bonds().len());
+    assert_eq!(2 * 9, torus.next_nearest_bonds().len());
+
+    // every site of the periodic triangular lattice has six neighbours
+    let triangular = Lattice::triangular(3, 3, Boundary::Open);
+    assert_eq!(6 + 6 + 4, triangular.bonds().len());
+    let triangular = Lattice::triangular(3, 3, Boundary::Periodic);
+    assert_eq!(3 * 9, triangular.bonds().len());
+    for site in 0..9 {
+        let degree = triangular
+            .bonds()
+            .iter()
+            .filter(|&&(i, j)| i == site || j == site)
+            .count();
+        assert_eq!(6, degree);
+    }
+}
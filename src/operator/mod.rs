@@ -0,0 +1,6 @@
+pub mod grouping;
+pub mod lattice;
+pub mod model;
+pub mod pauli;
+pub mod sparse;
+pub mod tapering;
@@ -0,0 +1,188 @@
+use crate::operator::lattice::Lattice;
+use crate::operator::pauli::{Pauli, PauliString, SparsePauliOp};
+use num::complex::Complex;
+
+// spin models act on one qubit per site and are written with pauli matrices rather
+// than spin operators, so S_i . S_j corresponds to (X_i X_j + Y_i Y_j + Z_i Z_j) / 4
+
+fn term(size: usize, paulis: &[(usize, Pauli)]) -> PauliString {
+    PauliString::from_sparse(size, paulis)
+}
+
+fn add(op: &mut SparsePauliOp, coefficient: f64, paulis: &[(usize, Pauli)]) {
+    if coefficient != 0. {
+        op.add_term(Complex::new(coefficient, 0.), term(op.size(), paulis));
+    }
+}
+
+// H = -J sum_<ij> Z_i Z_j - h sum_i X_i - g sum_i Z_i
+pub fn ising(lattice: &Lattice, j: f64, h: f64, g: f64) -> SparsePauliOp {
+    let mut op = SparsePauliOp::new(lattice.num_sites());
+    for &(a, b) in lattice.bonds().iter() {
+        add(&mut op, -j, &[(a, Pauli::Z), (b, Pauli::Z)]);
+    }
+    for site in 0..lattice.num_sites() {
+        add(&mut op, -h, &[(site, Pauli::X)]);
+        add(&mut op, -g, &[(site, Pauli::Z)]);
+    }
+    op
+}
+
+fn exchange(op: &mut SparsePauliOp, bonds: &[(usize, usize)], j: f64, delta: f64) {
+    for &(a, b) in bonds.iter() {
+        add(op, j, &[(a, Pauli::X), (b, Pauli::X)]);
+        add(op, j, &[(a, Pauli::Y), (b, Pauli::Y)]);
+        add(op, j * delta, &[(a, Pauli::Z), (b, Pauli::Z)]);
+    }
+}
+
+// H = J sum_<ij> (X_i X_j + Y_i Y_j + delta Z_i Z_j) + h sum_i Z_i
+pub fn xxz(lattice: &Lattice, j: f64, delta: f64, h: f64) -> SparsePauliOp {
+    let mut op = SparsePauliOp::new(lattice.num_sites());
+    exchange(&mut op, lattice.bonds(), j, delta);
+    for site in 0..lattice.num_sites() {
+        add(&mut op, h, &[(site, Pauli::Z)]);
+    }
+    op
+}
+
+pub fn heisenberg(lattice: &Lattice, j: f64) -> SparsePauliOp {
+    xxz(lattice, j, 1., 0.)
+}
+
+// heisenberg exchange J1 on the nearest and J2 on the next nearest neighbours
+pub fn j1_j2(lattice: &Lattice, j1: f64, j2: f64) -> SparsePauliOp {
+    let mut op = SparsePauliOp::new(lattice.num_sites());
+    exchange(&mut op, lattice.bonds(), j1, 1.);
+    exchange(&mut op, lattice.next_nearest_bonds(), j2, 1.);
+    op
+}
+
+// spinful fermi-hubbard model
+// H = -t sum_<ij>,s (c+_is c_js + h.c.) + U sum_i n_i,up n_i,down - mu sum_i,s n_is
+// on 2 * num_sites qubits, the spin up orbitals first and the spin down orbitals after
+// them, mapped with jordan-wigner: c+_i c_j + h.c. = (X_i Z..Z X_j + Y_i Z..Z Y_j) / 2
+// for i < j and n_i = (1 - Z_i) / 2
+pub fn hubbard(lattice: &Lattice, t: f64, u: f64, mu: f64) -> SparsePauliOp {
+    let sites = lattice.num_sites();
+    let mut op = SparsePauliOp::new(2 * sites);
+    for spin in 0..2 {
+        let offset = spin * sites;
+        for &(a, b) in lattice.bonds().iter() {
+            let (a, b) = (a.min(b) + offset, a.max(b) + offset);
+            for pauli in [Pauli::X, Pauli::Y] {
+                let mut string = vec![(a, pauli), (b, pauli)];
+                string.extend((a + 1..b).map(|k| (k, Pauli::Z)));
+                add(&mut op, -t / 2., &string);
+            }
+        }
+    }
+    // n_up n_down = (1 - Z_up - Z_down + Z_up Z_down) / 4
+    add(&mut op, (u / 4. - mu) * sites as f64, &[]);
+    for site in 0..sites {
+        let (up, down) = (site, site + sites);
+        add(&mut op, u / 4., &[(up, Pauli::Z), (down, Pauli::Z)]);
+        add(&mut op, mu / 2. - u / 4., &[(up, Pauli::Z)]);
+        add(&mut op, mu / 2. - u / 4., &[(down, Pauli::Z)]);
+    }
+    op
+}
+
+// total particle number sum_i n_i on the qubits of a fermionic model
+pub fn number_operator(size: usize) -> SparsePauliOp {
+    let mut op = SparsePauliOp::new(size);
+    add(&mut op, size as f64 / 2., &[]);
+    for k in 0..size {
+        add(&mut op, -0.5, &[(k, Pauli::Z)]);
+    }
+    op
+}
+
+#[cfg(test)]
+use crate::algebra::krylov::Lanczos;
+#[cfg(test)]
+use crate::algebra::linalg::eigh;
+#[cfg(test)]
+use crate::operator::lattice::Boundary;
+#[cfg(test)]
+use std::f64::consts::PI;
+
+#[cfg(test)]
+fn ground_energy(op: &SparsePauliOp) -> f64 {
+    Lanczos::default().ground_state(&op.to_sparse()).0
+}
+
+#[test]
+fn ising_test() {
+    // the critical transverse field chain, solved by free fermions with the
+    // antiperiodic momenta k = (2n + 1) pi / N: E = -sum_k 2 |sin(k / 2)|
+    let n = 8;
+    let hamiltonian = ising(&Lattice::chain(n, Boundary::Periodic), 1., 1., 0.);
+    assert_eq!(2 * n, hamiltonian.len());
+    let exact = -(0..n)
+        .map(|m| 2. * ((2 * m + 1) as f64 * PI / n as f64 / 2.).sin().abs())
+        .sum::<f64>();
+    assert!((ground_energy(&hamiltonian) - exact).abs() < 1e-8);
+
+    // without a transverse field the ground states are the two ferromagnets, split
+    // by the longitudinal field
+    let hamiltonian = ising(&Lattice::square(2, 3, Boundary::Open), 1., 0., 0.1);
+    let bonds = Lattice::square(2, 3, Boundary::Open).bonds().len() as f64;
+    assert!((ground_energy(&hamiltonian) + bonds + 0.6).abs() < 1e-8);
+}
+
+#[test]
+fn heisenberg_test() {
+    // the four site ring has E = -2J in spin operators
+    let hamiltonian = heisenberg(&Lattice::chain(4, Boundary::Periodic), 1.);
+    assert!((ground_energy(&hamiltonian) + 8.).abs() < 1e-8);
+
+    // the ising limit of the xxz model is the neel state
+    let ladder = Lattice::ladder(3, Boundary::Open);
+    let hamiltonian = xxz(&ladder, 1., 1e6, 0.);
+    let neel = -(ladder.bonds().len() as f64) * 1e6;
+    assert!((ground_energy(&hamiltonian) / neel - 1.).abs() < 1e-6);
+}
+
+#[test]
+fn j1_j2_test() {
+    // at the majumdar-ghosh point J2 = J1 / 2 the dimer states have E = -3/8 J1 N in
+    // spin operators
+    let n = 8;
+    let hamiltonian = j1_j2(&Lattice::chain(n, Boundary::Periodic), 1., 0.5);
+    let pairs = Lanczos::default().lowest_eigenpairs(&hamiltonian.to_sparse(), 2);
+    for (energy, _) in pairs.iter() {
+        assert!((energy + 1.5 * n as f64).abs() < 1e-8);
+    }
+
+    // on the triangular lattice the next nearest couplings are absent for J2 = 0
+    let triangular = Lattice::triangular(2, 2, Boundary::Open);
+    assert_eq!(
+        heisenberg(&triangular, 1.).to_matrix(),
+        j1_j2(&triangular, 1., 0.).to_matrix()
+    );
+}
+
+#[test]
+fn hubbard_test() {
+    // dimer at half filling with mu = U / 2: E = -U / 2 - sqrt(U^2 / 4 + 4 t^2)
+    let (t, u) = (1., 4.);
+    let hamiltonian = hubbard(&Lattice::chain(2, Boundary::Open), t, u, u / 2.);
+    assert!(hamiltonian.is_hermitian());
+    let (values, vectors) = eigh(&hamiltonian.to_matrix());
+    let exact = -u / 2. - (u * u / 4. + 4. * t * t).sqrt();
+    assert!((values[0] - exact).abs() < 1e-10);
+    let number = number_operator(4).to_matrix();
+    let ground = vectors.column(0).to_owned();
+    let particles = ground
+        .iter()
+        .zip(number.dot(&ground).iter())
+        .map(|(a, b)| a.conj() * b)
+        .sum::<Complex<f64>>();
+    assert!((particles.re - 2.).abs() < 1e-10);
+
+    // the hopping and the number operator commute
+    let hamiltonian = hubbard(&Lattice::chain(3, Boundary::Periodic), 1., 2., 0.3);
+    let commutator = hamiltonian.commutator(&number_operator(6)).simplify();
+    assert!(commutator.terms().iter().all(|(c, _)| c.norm() < 1e-12));
+}
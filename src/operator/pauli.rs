@@ -0,0 +1,708 @@
+use ndarray::prelude::{Array1, Array2};
+use num::complex::Complex;
+use num::One;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+const SIMPLIFY_TOLERANCE: f64 = 1e-12;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Pauli {
+    I,
+    X,
+    Y,
+    Z,
+}
+
+impl Pauli {
+    pub fn from_char(c: char) -> Option<Pauli> {
+        match c {
+            'I' => Some(Pauli::I),
+            'X' => Some(Pauli::X),
+            'Y' => Some(Pauli::Y),
+            'Z' => Some(Pauli::Z),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Pauli::I => 'I',
+            Pauli::X => 'X',
+            Pauli::Y => 'Y',
+            Pauli::Z => 'Z',
+        }
+    }
+
+    pub fn has_x(&self) -> bool {
+        matches!(self, Pauli::X | Pauli::Y)
+    }
+
+    pub fn has_z(&self) -> bool {
+        matches!(self, Pauli::Z | Pauli::Y)
+    }
+
+    pub fn from_bits(x: bool, z: bool) -> Pauli {
+        match (x, z) {
+            (false, false) => Pauli::I,
+            (true, false) => Pauli::X,
+            (true, true) => Pauli::Y,
+            (false, true) => Pauli::Z,
+        }
+    }
+
+    // self * other = phase * pauli
+    pub fn mul(&self, other: &Pauli) -> (Complex<f64>, Pauli) {
+        let i = Complex::new(0., 1.);
+        match (self, other) {
+            (Pauli::I, p) | (p, Pauli::I) => (Complex::one(), *p),
+            (a, b) if a == b => (Complex::one(), Pauli::I),
+            (Pauli::X, Pauli::Y) => (i, Pauli::Z),
+            (Pauli::Y, Pauli::X) => (-i, Pauli::Z),
+            (Pauli::Y, Pauli::Z) => (i, Pauli::X),
+            (Pauli::Z, Pauli::Y) => (-i, Pauli::X),
+            (Pauli::Z, Pauli::X) => (i, Pauli::Y),
+            (Pauli::X, Pauli::Z) => (-i, Pauli::Y),
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct PauliString {
+    paulis: Vec<Pauli>,
+}
+
+impl PauliString {
+    pub fn new(paulis: Vec<Pauli>) -> PauliString {
+        PauliString { paulis }
+    }
+
+    pub fn identity(size: usize) -> PauliString {
+        PauliString {
+            paulis: vec![Pauli::I; size],
+        }
+    }
+
+    pub fn from_sparse(size: usize, paulis: &[(usize, Pauli)]) -> PauliString {
+        let mut pauli_string = PauliString::identity(size);
+        for &(qubit, pauli) in paulis.iter() {
+            assert!(qubit < size);
+            pauli_string.paulis[qubit] = pauli;
+        }
+        pauli_string
+    }
+
+    pub fn size(&self) -> usize {
+        self.paulis.len()
+    }
+
+    pub fn paulis(&self) -> &[Pauli] {
+        &self.paulis
+    }
+
+    pub fn get(&self, qubit: usize) -> Pauli {
+        self.paulis[qubit]
+    }
+
+    pub fn set(&mut self, qubit: usize, pauli: Pauli) {
+        self.paulis[qubit] = pauli;
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.paulis.iter().all(|&p| p == Pauli::I)
+    }
+
+    pub fn weight(&self) -> usize {
+        self.paulis.iter().filter(|&&p| p != Pauli::I).count()
+    }
+
+    pub fn support(&self) -> Vec<usize> {
+        (0..self.size())
+            .filter(|&q| self.paulis[q] != Pauli::I)
+            .collect()
+    }
+
+    pub fn x_mask(&self) -> usize {
+        (0..self.size())
+            .filter(|&q| self.paulis[q].has_x())
+            .fold(0, |acc, q| acc | (1 << q))
+    }
+
+    pub fn z_mask(&self) -> usize {
+        (0..self.size())
+            .filter(|&q| self.paulis[q].has_z())
+            .fold(0, |acc, q| acc | (1 << q))
+    }
+
+    pub fn expand(&self, size: usize) -> PauliString {
+        assert!(self.size() <= size);
+        let mut paulis = self.paulis.clone();
+        paulis.resize(size, Pauli::I);
+        PauliString { paulis }
+    }
+
+    // self * other = phase * pauli_string
+    pub fn mul(&self, other: &PauliString) -> (Complex<f64>, PauliString) {
+        assert_eq!(self.size(), other.size());
+        let mut phase = Complex::one();
+        let paulis = self
+            .paulis
+            .iter()
+            .zip(other.paulis.iter())
+            .map(|(a, b)| {
+                let (p, pauli) = a.mul(b);
+                phase *= p;
+                pauli
+            })
+            .collect();
+        (phase, PauliString { paulis })
+    }
+
+    pub fn commutes(&self, other: &PauliString) -> bool {
+        assert_eq!(self.size(), other.size());
+        let anticommuting = self
+            .paulis
+            .iter()
+            .zip(other.paulis.iter())
+            .filter(|(&a, &b)| a != Pauli::I && b != Pauli::I && a != b)
+            .count();
+        anticommuting % 2 == 0
+    }
+
+    pub fn qubitwise_commutes(&self, other: &PauliString) -> bool {
+        assert_eq!(self.size(), other.size());
+        self.paulis
+            .iter()
+            .zip(other.paulis.iter())
+            .all(|(&a, &b)| a == Pauli::I || b == Pauli::I || a == b)
+    }
+
+    // self acts on the lower qubits, other on the upper ones
+    pub fn tensor(&self, other: &PauliString) -> PauliString {
+        let mut paulis = self.paulis.clone();
+        paulis.extend(other.paulis.iter());
+        PauliString { paulis }
+    }
+
+    // P|j> = phase(j) |j ^ x_mask>
+    pub fn apply_to_basis(&self, index: usize) -> (Complex<f64>, usize) {
+        let mut phase = Complex::one();
+        for (q, pauli) in self.paulis.iter().enumerate() {
+            let bit = (index >> q) & 1 == 1;
+            match (pauli, bit) {
+                (Pauli::Y, false) => phase *= Complex::new(0., 1.),
+                (Pauli::Y, true) => phase *= Complex::new(0., -1.),
+                (Pauli::Z, true) => phase = -phase,
+                _ => {}
+            }
+        }
+        (phase, index ^ self.x_mask())
+    }
+
+    pub fn to_matrix(&self) -> Array2<Complex<f64>> {
+        let dim = 1 << self.size();
+        let mut matrix = Array2::<Complex<f64>>::zeros((dim, dim));
+        for j in 0..dim {
+            let (phase, i) = self.apply_to_basis(j);
+            matrix[[i, j]] = phase;
+        }
+        matrix
+    }
+}
+
+impl fmt::Display for PauliString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_identity() {
+            return write!(f, "I");
+        }
+        let labels = self
+            .support()
+            .iter()
+            .map(|&q| format!("{}{}", self.paulis[q].to_char(), q))
+            .collect::<Vec<_>>();
+        write!(f, "{}", labels.join(" "))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparsePauliOp {
+    size: usize,
+    terms: Vec<(Complex<f64>, PauliString)>,
+}
+
+impl SparsePauliOp {
+    pub fn new(size: usize) -> SparsePauliOp {
+        SparsePauliOp {
+            size,
+            terms: vec![],
+        }
+    }
+
+    pub fn identity(size: usize) -> SparsePauliOp {
+        SparsePauliOp::from_pauli_string(Complex::one(), PauliString::identity(size))
+    }
+
+    pub fn from_pauli_string(coefficient: Complex<f64>, pauli: PauliString) -> SparsePauliOp {
+        SparsePauliOp {
+            size: pauli.size(),
+            terms: vec![(coefficient, pauli)],
+        }
+    }
+
+    pub fn from_terms(size: usize, terms: Vec<(Complex<f64>, PauliString)>) -> SparsePauliOp {
+        for (_, pauli) in terms.iter() {
+            assert_eq!(size, pauli.size());
+        }
+        SparsePauliOp { size, terms }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn terms(&self) -> &[(Complex<f64>, PauliString)] {
+        &self.terms
+    }
+
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn add_term(&mut self, coefficient: Complex<f64>, pauli: PauliString) {
+        assert_eq!(self.size, pauli.size());
+        self.terms.push((coefficient, pauli));
+    }
+
+    pub fn expand(&self, size: usize) -> SparsePauliOp {
+        SparsePauliOp {
+            size,
+            terms: self
+                .terms
+                .iter()
+                .map(|(c, p)| (*c, p.expand(size)))
+                .collect(),
+        }
+    }
+
+    // merges duplicated pauli strings and drops terms with negligible coefficients,
+    // keeping the order in which each pauli string first appears
+    pub fn simplify(&self) -> SparsePauliOp {
+        let mut terms: Vec<(Complex<f64>, PauliString)> = vec![];
+        let mut positions: HashMap<&PauliString, usize> = HashMap::new();
+        for (coefficient, pauli) in self.terms.iter() {
+            match positions.get(pauli) {
+                Some(&k) => terms[k].0 += coefficient,
+                None => {
+                    positions.insert(pauli, terms.len());
+                    terms.push((*coefficient, pauli.clone()));
+                }
+            }
+        }
+        terms.retain(|(c, _)| c.norm() > SIMPLIFY_TOLERANCE);
+        SparsePauliOp {
+            size: self.size,
+            terms,
+        }
+    }
+
+    pub fn adjoint(&self) -> SparsePauliOp {
+        SparsePauliOp {
+            size: self.size,
+            terms: self
+                .terms
+                .iter()
+                .map(|(c, p)| (c.conj(), p.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn is_hermitian(&self) -> bool {
+        (self - &self.adjoint()).simplify().is_empty()
+    }
+
+    pub fn compose(&self, other: &SparsePauliOp) -> SparsePauliOp {
+        assert_eq!(self.size, other.size);
+        let mut result = SparsePauliOp::new(self.size);
+        for (c1, p1) in self.terms.iter() {
+            for (c2, p2) in other.terms.iter() {
+                let (phase, pauli) = p1.mul(p2);
+                result.add_term(c1 * c2 * phase, pauli);
+            }
+        }
+        result.simplify()
+    }
+
+    pub fn commutator(&self, other: &SparsePauliOp) -> SparsePauliOp {
+        (&self.compose(other) - &other.compose(self)).simplify()
+    }
+
+    pub fn commutes(&self, other: &SparsePauliOp) -> bool {
+        self.commutator(other).is_empty()
+    }
+
+    pub fn tensor(&self, other: &SparsePauliOp) -> SparsePauliOp {
+        let mut result = SparsePauliOp::new(self.size + other.size);
+        for (c1, p1) in self.terms.iter() {
+            for (c2, p2) in other.terms.iter() {
+                result.add_term(c1 * c2, p1.tensor(p2));
+            }
+        }
+        result
+    }
+
+    pub fn apply_to(&self, elements: &Array1<Complex<f64>>) -> Array1<Complex<f64>> {
+        assert_eq!(1 << self.size, elements.len());
+        let mut result = Array1::<Complex<f64>>::zeros(elements.len());
+        for (coefficient, pauli) in self.terms.iter() {
+            for j in 0..elements.len() {
+                let (phase, i) = pauli.apply_to_basis(j);
+                result[i] += coefficient * phase * elements[j];
+            }
+        }
+        result
+    }
+
+    pub fn to_matrix(&self) -> Array2<Complex<f64>> {
+        let dim = 1 << self.size;
+        let mut matrix = Array2::<Complex<f64>>::zeros((dim, dim));
+        for (coefficient, pauli) in self.terms.iter() {
+            for j in 0..dim {
+                let (phase, i) = pauli.apply_to_basis(j);
+                matrix[[i, j]] += coefficient * phase;
+            }
+        }
+        matrix
+    }
+}
+
+impl Add<&SparsePauliOp> for &SparsePauliOp {
+    type Output = SparsePauliOp;
+
+    fn add(self, other: &SparsePauliOp) -> SparsePauliOp {
+        assert_eq!(self.size, other.size);
+        let mut terms = self.terms.clone();
+        terms.extend(other.terms.iter().cloned());
+        SparsePauliOp {
+            size: self.size,
+            terms,
+        }
+    }
+}
+
+impl Sub<&SparsePauliOp> for &SparsePauliOp {
+    type Output = SparsePauliOp;
+
+    fn sub(self, other: &SparsePauliOp) -> SparsePauliOp {
+        self + &(-other)
+    }
+}
+
+impl Neg for &SparsePauliOp {
+    type Output = SparsePauliOp;
+
+    fn neg(self) -> SparsePauliOp {
+        self * Complex::new(-1., 0.)
+    }
+}
+
+impl Mul<&SparsePauliOp> for &SparsePauliOp {
+    type Output = SparsePauliOp;
+
+    fn mul(self, other: &SparsePauliOp) -> SparsePauliOp {
+        self.compose(other)
+    }
+}
+
+impl Mul<Complex<f64>> for &SparsePauliOp {
+    type Output = SparsePauliOp;
+
+    fn mul(self, scalar: Complex<f64>) -> SparsePauliOp {
+        SparsePauliOp {
+            size: self.size,
+            terms: self
+                .terms
+                .iter()
+                .map(|(c, p)| (c * scalar, p.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl Mul<f64> for &SparsePauliOp {
+    type Output = SparsePauliOp;
+
+    fn mul(self, scalar: f64) -> SparsePauliOp {
+        self * Complex::new(scalar, 0.)
+    }
+}
+
+impl fmt::Display for SparsePauliOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+        let terms = self
+            .terms
+            .iter()
+            .map(|(c, p)| format!("({})*{}", c, p))
+            .collect::<Vec<_>>();
+        write!(f, "{}", terms.join(" + "))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Token {
+    Number(Complex<f64>),
+    Pauli(Pauli, Option<usize>),
+    Plus,
+    Minus,
+    Star,
+}
+
+type RawTerm = (Complex<f64>, Vec<(Pauli, usize)>);
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut pos = 0;
+    while pos < chars.len() {
+        let c = chars[pos];
+        if c.is_whitespace() {
+            pos += 1;
+        } else if c == '+' {
+            tokens.push(Token::Plus);
+            pos += 1;
+        } else if c == '-' {
+            tokens.push(Token::Minus);
+            pos += 1;
+        } else if c == '*' {
+            tokens.push(Token::Star);
+            pos += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = pos;
+            while pos < chars.len() && (chars[pos].is_ascii_digit() || chars[pos] == '.') {
+                pos += 1;
+            }
+            if pos < chars.len() && (chars[pos] == 'e' || chars[pos] == 'E') {
+                pos += 1;
+                if pos < chars.len() && (chars[pos] == '+' || chars[pos] == '-') {
+                    pos += 1;
+                }
+                while pos < chars.len() && chars[pos].is_ascii_digit() {
+                    pos += 1;
+                }
+            }
+            let literal = chars[start..pos].iter().collect::<String>();
+            let value = literal
+                .parse::<f64>()
+                .map_err(|_| format!("invalid number '{}'", literal))?;
+            if pos < chars.len() && (chars[pos] == 'i' || chars[pos] == 'j') {
+                tokens.push(Token::Number(Complex::new(0., value)));
+                pos += 1;
+            } else {
+                tokens.push(Token::Number(Complex::new(value, 0.)));
+            }
+        } else if let Some(pauli) = Pauli::from_char(c) {
+            pos += 1;
+            let start = pos;
+            while pos < chars.len() && chars[pos].is_ascii_digit() {
+                pos += 1;
+            }
+            if start == pos {
+                if pauli != Pauli::I {
+                    return Err(format!("missing qubit index after '{}'", c));
+                }
+                tokens.push(Token::Pauli(pauli, None));
+            } else {
+                let qubit = chars[start..pos]
+                    .iter()
+                    .collect::<String>()
+                    .parse::<usize>()
+                    .map_err(|e| e.to_string())?;
+                tokens.push(Token::Pauli(pauli, Some(qubit)));
+            }
+        } else {
+            return Err(format!("unexpected character '{}'", c));
+        }
+    }
+    Ok(tokens)
+}
+
+impl FromStr for SparsePauliOp {
+    type Err = String;
+
+    // parses sums like "0.5*Z0 Z1 - 0.3*X2 + 0.1i*Y0 + 2"; the operator size is the
+    // largest qubit index plus one
+    fn from_str(text: &str) -> Result<SparsePauliOp, String> {
+        let tokens = tokenize(text)?;
+        let mut raw_terms: Vec<RawTerm> = vec![];
+        let mut size = 0;
+        let mut pos = 0;
+        while pos < tokens.len() {
+            let mut coefficient = Complex::one();
+            match tokens[pos] {
+                Token::Plus => pos += 1,
+                Token::Minus => {
+                    coefficient = -coefficient;
+                    pos += 1;
+                }
+                _ if raw_terms.is_empty() => {}
+                _ => return Err("expected '+' or '-' between terms".to_string()),
+            }
+            let mut factors = vec![];
+            let mut expect_factor = true;
+            while pos < tokens.len() {
+                match tokens[pos] {
+                    Token::Number(value) => coefficient *= value,
+                    Token::Pauli(pauli, qubit) => {
+                        if let Some(qubit) = qubit {
+                            size = size.max(qubit + 1);
+                            factors.push((pauli, qubit));
+                        }
+                    }
+                    Token::Star if !expect_factor => {
+                        pos += 1;
+                        expect_factor = true;
+                        continue;
+                    }
+                    _ => break,
+                }
+                expect_factor = false;
+                pos += 1;
+            }
+            if expect_factor {
+                return Err("expected a coefficient or a pauli operator".to_string());
+            }
+            raw_terms.push((coefficient, factors));
+        }
+        if raw_terms.is_empty() {
+            return Err("empty operator".to_string());
+        }
+
+        let mut op = SparsePauliOp::new(size);
+        for (mut coefficient, factors) in raw_terms.into_iter() {
+            let mut pauli_string = PauliString::identity(size);
+            for (pauli, qubit) in factors.into_iter() {
+                let (phase, product) = pauli_string.get(qubit).mul(&pauli);
+                coefficient *= phase;
+                pauli_string.set(qubit, product);
+            }
+            op.add_term(coefficient, pauli_string);
+        }
+        Ok(op)
+    }
+}
+
+#[cfg(test)]
+fn matrix_close(a: &Array2<Complex<f64>>, b: &Array2<Complex<f64>>) -> bool {
+    a.shape() == b.shape() && a.iter().zip(b.iter()).all(|(x, y)| (x - y).norm() < 1e-10)
+}
+
+#[test]
+fn pauli_string_mul_test() {
+    let x = PauliString::from_sparse(2, &[(0, Pauli::X), (1, Pauli::Z)]);
+    let y = PauliString::from_sparse(2, &[(0, Pauli::Y), (1, Pauli::Z)]);
+    let (phase, product) = x.mul(&y);
+    assert_eq!(Complex::new(0., 1.), phase);
+    assert_eq!(PauliString::from_sparse(2, &[(0, Pauli::Z)]), product);
+
+    let lhs = x.to_matrix().dot(&y.to_matrix());
+    let rhs = product.to_matrix().mapv(|v| v * phase);
+    assert!(matrix_close(&lhs, &rhs));
+}
+
+#[test]
+fn pauli_string_commutes_test() {
+    let xx = PauliString::from_sparse(2, &[(0, Pauli::X), (1, Pauli::X)]);
+    let zz = PauliString::from_sparse(2, &[(0, Pauli::Z), (1, Pauli::Z)]);
+    let zi = PauliString::from_sparse(2, &[(0, Pauli::Z)]);
+    assert!(xx.commutes(&zz));
+    assert!(!xx.commutes(&zi));
+    assert!(zz.commutes(&zi));
+}
+
+#[test]
+fn pauli_string_to_matrix_test() {
+    let y = PauliString::from_sparse(1, &[(0, Pauli::Y)]);
+    let matrix = y.to_matrix();
+    assert_eq!(Complex::new(0., -1.), matrix[[0, 1]]);
+    assert_eq!(Complex::new(0., 1.), matrix[[1, 0]]);
+
+    // qubit 0 is the least significant bit of the basis index
+    let x0 = PauliString::from_sparse(2, &[(0, Pauli::X)]);
+    assert_eq!(Complex::one(), x0.to_matrix()[[1, 0]]);
+    assert_eq!(Complex::one(), x0.to_matrix()[[3, 2]]);
+}
+
+#[test]
+fn sparse_pauli_op_parse_test() {
+    let op: SparsePauliOp = "0.5*Z0 Z1 - 0.3*X2".parse().unwrap();
+    assert_eq!(3, op.size());
+    assert_eq!(2, op.len());
+    assert_eq!(Complex::new(0.5, 0.), op.terms()[0].0);
+    assert_eq!(
+        PauliString::from_sparse(3, &[(0, Pauli::Z), (1, Pauli::Z)]),
+        op.terms()[0].1
+    );
+    assert_eq!(Complex::new(-0.3, 0.), op.terms()[1].0);
+    assert_eq!(
+        PauliString::from_sparse(3, &[(2, Pauli::X)]),
+        op.terms()[1].1
+    );
+
+    let op: SparsePauliOp = "X0 * Y0 + 2 - 1e-1j * I".parse().unwrap();
+    assert_eq!(Complex::new(0., 1.), op.terms()[0].0);
+    assert_eq!(
+        PauliString::from_sparse(1, &[(0, Pauli::Z)]),
+        op.terms()[0].1
+    );
+    assert_eq!(Complex::new(2., 0.), op.terms()[1].0);
+    assert_eq!(Complex::new(0., -0.1), op.terms()[2].0);
+
+    assert!("0.5*Q0".parse::<SparsePauliOp>().is_err());
+    assert!("Z0 +".parse::<SparsePauliOp>().is_err());
+    assert!("".parse::<SparsePauliOp>().is_err());
+}
+
+#[test]
+fn sparse_pauli_op_algebra_test() {
+    let a: SparsePauliOp = "X0 + Z1".parse().unwrap();
+    let b: SparsePauliOp = "Z0 Z1".parse().unwrap();
+    let product = &a * &b;
+    let expected = a.to_matrix().dot(&b.to_matrix());
+    assert!(matrix_close(&expected, &product.to_matrix()));
+
+    let square = (&a * &a).simplify();
+    assert_eq!(2, square.len());
+    assert!(square.is_hermitian());
+    assert!(!a.commutes(&b));
+    assert!(b.commutes(&"Z0 + Z1".parse().unwrap()));
+
+    let cancelled = (&a - &a).simplify();
+    assert!(cancelled.is_empty());
+}
+
+#[test]
+fn sparse_pauli_op_tensor_test() {
+    let a: SparsePauliOp = "X0 + 0.5*Z0".parse().unwrap();
+    let b: SparsePauliOp = "Y0".parse().unwrap();
+    let tensor = a.tensor(&b);
+    assert_eq!(2, tensor.size());
+    // the tensor places `a` on the low qubit, so the kron order is b ⊗ a
+    let b_matrix = b.to_matrix();
+    let a_matrix = a.to_matrix();
+    let mut expected = Array2::<Complex<f64>>::zeros((4, 4));
+    for i in 0..4 {
+        for j in 0..4 {
+            expected[[i, j]] = b_matrix[[i >> 1, j >> 1]] * a_matrix[[i & 1, j & 1]];
+        }
+    }
+    assert!(matrix_close(&expected, &tensor.to_matrix()));
+}
@@ -0,0 +1,190 @@
+use crate::algebra::linalg::LinearOperator;
+use crate::operator::pauli::SparsePauliOp;
+use ndarray::prelude::{Array1, Array2};
+use num::complex::Complex;
+use num::Zero;
+
+type PhaseTerm = (Complex<f64>, usize);
+
+// compressed sparse rows: the entries of row i are values[row_offsets[i]..row_offsets[i + 1]]
+// in the columns columns[row_offsets[i]..row_offsets[i + 1]]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix {
+    dim: usize,
+    row_offsets: Vec<usize>,
+    columns: Vec<usize>,
+    values: Vec<Complex<f64>>,
+}
+
+impl SparseMatrix {
+    // duplicated entries are summed and zeros are dropped
+    pub fn from_triplets(dim: usize, triplets: &[(usize, usize, Complex<f64>)]) -> SparseMatrix {
+        let mut rows = vec![vec![]; dim];
+        for &(i, j, value) in triplets.iter() {
+            assert!(i < dim && j < dim);
+            rows[i].push((j, value));
+        }
+        let mut row_offsets = vec![0];
+        let mut columns = vec![];
+        let mut values = vec![];
+        for row in rows.iter_mut() {
+            row.sort_by_key(|&(j, _)| j);
+            let mut merged: Vec<(usize, Complex<f64>)> = vec![];
+            for &(j, value) in row.iter() {
+                match merged.last_mut() {
+                    Some((last, sum)) if *last == j => *sum += value,
+                    _ => merged.push((j, value)),
+                }
+            }
+            for (j, value) in merged {
+                if value.norm() > 1e-14 {
+                    columns.push(j);
+                    values.push(value);
+                }
+            }
+            row_offsets.push(columns.len());
+        }
+        SparseMatrix {
+            dim,
+            row_offsets,
+            columns,
+            values,
+        }
+    }
+
+    // P|j> = i^(number of Y) (-1)^|j & z_mask| |j ^ x_mask>, so the terms sharing an
+    // x mask fill the same entries and are summed before building the rows
+    pub fn from_pauli_op(op: &SparsePauliOp) -> SparseMatrix {
+        let dim = 1 << op.size();
+        // x mask and the terms (coefficient i^(number of Y), z mask) sharing it
+        let mut groups: Vec<(usize, Vec<PhaseTerm>)> = vec![];
+        for (coefficient, pauli) in op.terms().iter() {
+            let x_mask = pauli.x_mask();
+            let num_y = (x_mask & pauli.z_mask()).count_ones();
+            let term = (
+                coefficient * Complex::new(0., 1.).powu(num_y),
+                pauli.z_mask(),
+            );
+            match groups.iter_mut().find(|(mask, _)| *mask == x_mask) {
+                Some((_, terms)) => terms.push(term),
+                None => groups.push((x_mask, vec![term])),
+            }
+        }
+        let mut triplets = vec![];
+        for (x_mask, terms) in groups.iter() {
+            for j in 0..dim {
+                let value = terms
+                    .iter()
+                    .map(|(c, z_mask)| {
+                        if (j & z_mask).count_ones().is_multiple_of(2) {
+                            *c
+                        } else {
+                            -c
+                        }
+                    })
+                    .sum::<Complex<f64>>();
+                triplets.push((j ^ x_mask, j, value));
+            }
+        }
+        SparseMatrix::from_triplets(dim, &triplets)
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> Complex<f64> {
+        let range = self.row_offsets[i]..self.row_offsets[i + 1];
+        match self.columns[range.clone()].binary_search(&j) {
+            Ok(k) => self.values[range.start + k],
+            Err(_) => Complex::zero(),
+        }
+    }
+
+    pub fn diagonal(&self) -> Vec<Complex<f64>> {
+        (0..self.dim).map(|i| self.get(i, i)).collect()
+    }
+
+    pub fn is_hermitian(&self) -> bool {
+        (0..self.dim).all(|i| {
+            (self.row_offsets[i]..self.row_offsets[i + 1])
+                .all(|k| (self.get(self.columns[k], i).conj() - self.values[k]).norm() < 1e-12)
+        })
+    }
+
+    pub fn to_dense(&self) -> Array2<Complex<f64>> {
+        let mut matrix = Array2::<Complex<f64>>::zeros((self.dim, self.dim));
+        for i in 0..self.dim {
+            for k in self.row_offsets[i]..self.row_offsets[i + 1] {
+                matrix[[i, self.columns[k]]] = self.values[k];
+            }
+        }
+        matrix
+    }
+}
+
+impl LinearOperator for SparseMatrix {
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn matvec(&self, vector: &Array1<Complex<f64>>) -> Array1<Complex<f64>> {
+        assert_eq!(self.dim, vector.len());
+        Array1::from_iter((0..self.dim).map(|i| {
+            (self.row_offsets[i]..self.row_offsets[i + 1])
+                .map(|k| self.values[k] * vector[self.columns[k]])
+                .sum::<Complex<f64>>()
+        }))
+    }
+}
+
+impl LinearOperator for SparsePauliOp {
+    fn dim(&self) -> usize {
+        1 << self.size()
+    }
+
+    fn matvec(&self, vector: &Array1<Complex<f64>>) -> Array1<Complex<f64>> {
+        self.apply_to(vector)
+    }
+}
+
+impl SparsePauliOp {
+    pub fn to_sparse(&self) -> SparseMatrix {
+        SparseMatrix::from_pauli_op(self)
+    }
+}
+
+#[test]
+fn sparse_matrix_test() {
+    let op: SparsePauliOp = "0.5 + X0 X1 - 0.3*Y1 Z2 + 0.7*Z0 + 0.2*X0 X1"
+        .parse()
+        .unwrap();
+    let sparse = op.to_sparse();
+    let dense = op.to_matrix();
+    assert_eq!(dense, sparse.to_dense());
+    assert!(sparse.is_hermitian());
+    // the identity and Z0 share the diagonal, the two X0 X1 terms are merged
+    assert_eq!(8 + 8 + 8, sparse.nnz());
+    assert_eq!(Complex::new(1.2, 0.), sparse.get(0, 0));
+
+    let vector = Array1::from_iter((0..8).map(|k| Complex::new(k as f64, 1. - k as f64)));
+    let expected = dense.dot(&vector);
+    let product = sparse.matvec(&vector);
+    assert!(product
+        .iter()
+        .zip(expected.iter())
+        .all(|(a, b)| (a - b).norm() < 1e-12));
+    let product = op.matvec(&vector);
+    assert!(product
+        .iter()
+        .zip(expected.iter())
+        .all(|(a, b)| (a - b).norm() < 1e-12));
+
+    // the three terms flip the same qubits, X0 Y1 and Y0 X1 cancel between |00> and
+    // |11> and leave Y0 Y1 Z2
+    let op: SparsePauliOp = "X0 Y1 - Y0 X1 + 0.5*Y0 Y1 Z2".parse().unwrap();
+    let sparse = op.to_sparse();
+    assert_eq!(op.to_matrix(), sparse.to_dense());
+    assert_eq!(Complex::new(-0.5, 0.), sparse.get(3, 0));
+    assert_eq!(Complex::new(0.5, 0.), sparse.get(7, 4));
+}
@@ -0,0 +1,215 @@
+use crate::operator::pauli::{Pauli, PauliString, SparsePauliOp};
+use num::complex::Complex;
+
+// Z-type Z2 symmetries tau_i of a pauli sum (Bravyi et al. 2017). every generator acts on
+// a qubit q_i that no other generator touches, so the clifford
+// U_i = (X_q_i + tau_i) / sqrt(2) maps tau_i to X_q_i and leaves the other generators
+// unchanged. after the rotation the operator acts on the qubits q_i with I or X only,
+// which are replaced by the eigenvalues +-1 of a symmetry sector and removed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Z2Symmetries {
+    num_qubits: usize,
+    // Z masks of the generators
+    generators: Vec<usize>,
+    qubits: Vec<usize>,
+}
+
+impl Z2Symmetries {
+    // the Z strings commuting with every term are the binary kernel of the X masks of
+    // the terms, whose basis is read off the reduced row echelon form
+    pub fn find(op: &SparsePauliOp) -> Z2Symmetries {
+        let num_qubits = op.size();
+        let mut rows = op
+            .terms()
+            .iter()
+            .map(|(_, p)| p.x_mask())
+            .filter(|&mask| mask != 0)
+            .collect::<Vec<_>>();
+        let mut pivots = vec![];
+        for column in 0..num_qubits {
+            let rank = pivots.len();
+            let Some(pivot) = (rank..rows.len()).find(|&r| rows[r] >> column & 1 == 1) else {
+                continue;
+            };
+            rows.swap(rank, pivot);
+            for r in 0..rows.len() {
+                if r != rank && rows[r] >> column & 1 == 1 {
+                    rows[r] ^= rows[rank];
+                }
+            }
+            pivots.push(column);
+        }
+
+        let mut generators = vec![];
+        let mut qubits = vec![];
+        for free in (0..num_qubits).filter(|q| !pivots.contains(q)) {
+            let mut mask = 1 << free;
+            for (row, &pivot) in pivots.iter().enumerate() {
+                if rows[row] >> free & 1 == 1 {
+                    mask |= 1 << pivot;
+                }
+            }
+            generators.push(mask);
+            qubits.push(free);
+        }
+        Z2Symmetries {
+            num_qubits,
+            generators,
+            qubits,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.generators.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.generators.is_empty()
+    }
+
+    pub fn generators(&self) -> Vec<PauliString> {
+        self.generators
+            .iter()
+            .map(|&mask| z_string(self.num_qubits, mask))
+            .collect()
+    }
+
+    // the qubits removed by the tapering, one per generator
+    pub fn qubits(&self) -> &[usize] {
+        &self.qubits
+    }
+
+    // the eigenvalues of the generators on a computational basis state
+    pub fn sector(&self, basis_state: usize) -> Vec<i32> {
+        self.generators
+            .iter()
+            .map(|mask| {
+                if (mask & basis_state).count_ones().is_multiple_of(2) {
+                    1
+                } else {
+                    -1
+                }
+            })
+            .collect()
+    }
+
+    pub fn sectors(&self) -> Vec<Vec<i32>> {
+        (0..1usize << self.len())
+            .map(|bits| {
+                (0..self.len())
+                    .map(|k| if bits >> k & 1 == 0 { 1 } else { -1 })
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn cliffords(&self) -> Vec<SparsePauliOp> {
+        let amplitude = Complex::new(std::f64::consts::FRAC_1_SQRT_2, 0.);
+        self.generators
+            .iter()
+            .zip(self.qubits.iter())
+            .map(|(&mask, &qubit)| {
+                SparsePauliOp::from_terms(
+                    self.num_qubits,
+                    vec![
+                        (
+                            amplitude,
+                            PauliString::from_sparse(self.num_qubits, &[(qubit, Pauli::X)]),
+                        ),
+                        (amplitude, z_string(self.num_qubits, mask)),
+                    ],
+                )
+            })
+            .collect()
+    }
+
+    // the operator restricted to a symmetry sector, on the remaining qubits in
+    // ascending order; the operator should commute with the generators
+    pub fn taper(&self, op: &SparsePauliOp, sector: &[i32]) -> SparsePauliOp {
+        assert_eq!(self.num_qubits, op.size());
+        assert_eq!(self.len(), sector.len());
+        for generator in self.generators() {
+            assert!(
+                op.terms().iter().all(|(_, p)| p.commutes(&generator)),
+                "the operator does not commute with the symmetries"
+            );
+        }
+        let mut rotated = op.clone();
+        for clifford in self.cliffords() {
+            rotated = clifford.compose(&rotated).compose(&clifford);
+        }
+
+        let kept = (0..self.num_qubits)
+            .filter(|q| !self.qubits.contains(q))
+            .collect::<Vec<_>>();
+        let mut tapered = SparsePauliOp::new(kept.len());
+        for (coefficient, pauli) in rotated.terms().iter() {
+            let mut coefficient = *coefficient;
+            for (&qubit, &eigenvalue) in self.qubits.iter().zip(sector.iter()) {
+                match pauli.get(qubit) {
+                    Pauli::I => {}
+                    Pauli::X => coefficient *= eigenvalue as f64,
+                    _ => unreachable!("the rotated operator should commute with X"),
+                }
+            }
+            let paulis = kept
+                .iter()
+                .enumerate()
+                .map(|(k, &q)| (k, pauli.get(q)))
+                .collect::<Vec<_>>();
+            tapered.add_term(coefficient, PauliString::from_sparse(kept.len(), &paulis));
+        }
+        tapered.simplify()
+    }
+}
+
+fn z_string(size: usize, mask: usize) -> PauliString {
+    let paulis = (0..size)
+        .filter(|q| mask >> q & 1 == 1)
+        .map(|q| (q, Pauli::Z))
+        .collect::<Vec<_>>();
+    PauliString::from_sparse(size, &paulis)
+}
+
+#[cfg(test)]
+use crate::algebra::linalg::eigh;
+#[cfg(test)]
+use crate::operator::lattice::{Boundary, Lattice};
+#[cfg(test)]
+use crate::operator::model::hubbard;
+
+#[test]
+fn tapering_test() {
+    // the hubbard dimer conserves the parities of both spin species
+    let (t, u) = (1., 4.);
+    let hamiltonian = hubbard(&Lattice::chain(2, Boundary::Open), t, u, u / 2.);
+    let symmetries = Z2Symmetries::find(&hamiltonian);
+    assert_eq!(2, symmetries.len());
+    for generator in symmetries.generators() {
+        assert!(hamiltonian
+            .terms()
+            .iter()
+            .all(|(_, p)| p.commutes(&generator)));
+    }
+
+    // the spectra of the sectors make up the full spectrum
+    let (full, _) = eigh(&hamiltonian.to_matrix());
+    let mut spectrum = vec![];
+    for sector in symmetries.sectors() {
+        let tapered = symmetries.taper(&hamiltonian, &sector);
+        assert_eq!(2, tapered.size());
+        assert!(tapered.is_hermitian());
+        spectrum.extend(eigh(&tapered.to_matrix()).0);
+    }
+    spectrum.sort_by(f64::total_cmp);
+    for (a, b) in spectrum.iter().zip(full.iter()) {
+        assert!((a - b).abs() < 1e-10);
+    }
+
+    // one electron of each spin: both generators are odd
+    let sector = symmetries.sector(0b0101);
+    assert_eq!(vec![-1, -1], sector);
+    let (values, _) = eigh(&symmetries.taper(&hamiltonian, &sector).to_matrix());
+    let exact = -u / 2. - (u * u / 4. + 4. * t * t).sqrt();
+    assert!((values[0] - exact).abs() < 1e-10);
+}
@@ -0,0 +1,91 @@
+use crate::optimizer::optimizer::{
+    Callback, ConvergenceCriteria, CountingObjective, Objective, OptimizationResult, Optimizer,
+    Tracker,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Adam {
+    pub learning_rate: f64,
+    pub beta1: f64,
+    pub beta2: f64,
+    pub epsilon: f64,
+    pub criteria: ConvergenceCriteria,
+}
+
+impl Adam {
+    pub fn new(learning_rate: f64) -> Adam {
+        Adam {
+            learning_rate,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+            criteria: ConvergenceCriteria::default(),
+        }
+    }
+}
+
+impl Optimizer for Adam {
+    fn minimize_with_callback(
+        &self,
+        objective: &dyn Objective,
+        initial: &[f64],
+        callback: Callback,
+    ) -> OptimizationResult {
+        let objective = CountingObjective::new(objective);
+        let mut tracker = Tracker::new(self.criteria, callback);
+        let mut parameters = initial.to_vec();
+        let mut first_moment = vec![0.; parameters.len()];
+        let mut second_moment = vec![0.; parameters.len()];
+        let mut value = objective.value(&parameters);
+        let mut t = 0;
+        loop {
+            let gradient = objective.gradient(&parameters);
+            if !tracker.record(value, &parameters, Some(&gradient)) {
+                break;
+            }
+            t += 1;
+            for k in 0..parameters.len() {
+                first_moment[k] = self.beta1 * first_moment[k] + (1. - self.beta1) * gradient[k];
+                second_moment[k] =
+                    self.beta2 * second_moment[k] + (1. - self.beta2) * gradient[k] * gradient[k];
+                let m_hat = first_moment[k] / (1. - self.beta1.powi(t));
+                let v_hat = second_moment[k] / (1. - self.beta2.powi(t));
+                parameters[k] -= self.learning_rate * m_hat / (v_hat.sqrt() + self.epsilon);
+            }
+            value = objective.value(&parameters);
+        }
+        tracker.finish(&objective, parameters, value)
+    }
+}
+
+#[cfg(test)]
+use crate::circuit::parameter::{Parameter, ParameterizedCircuit};
+#[cfg(test)]
+use crate::operator::pauli::SparsePauliOp;
+#[cfg(test)]
+use crate::optimizer::optimizer::ExpectationObjective;
+
+#[test]
+fn adam_circuit_test() {
+    let theta = Parameter::new("theta");
+    let phi = Parameter::new("phi");
+    let mut pc = ParameterizedCircuit::new(2);
+    pc.RY(0, &theta);
+    pc.RY(1, &phi);
+    pc.CNOT(0, 1);
+    // <Z0 Z1 + Z1> = cos(phi) (1 + cos(theta)) has its minimum -2 at theta = 0, phi = pi
+    let op: SparsePauliOp = "Z0 Z1 + Z1".parse().unwrap();
+    let objective = ExpectationObjective::new(&pc, &op);
+
+    let mut values = vec![];
+    let mut callback = |it: &crate::optimizer::optimizer::Iteration| {
+        values.push(it.value);
+        true
+    };
+    let mut optimizer = Adam::new(0.1);
+    optimizer.criteria.max_iterations = 500;
+    let result = optimizer.minimize_with_callback(&objective, &[0.5, 0.3], &mut callback);
+    assert!((result.value + 2.).abs() < 1e-4);
+    assert_eq!(result.history.len(), values.len());
+    assert!(values[values.len() - 1] < values[0]);
+}
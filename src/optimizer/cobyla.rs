@@ -0,0 +1,259 @@
+use crate::optimizer::optimizer::{
+    dot, norm, Callback, ConvergenceCriteria, CountingObjective, Objective, OptimizationResult,
+    Optimizer, Tracker,
+};
+
+// a constraint is satisfied when it evaluates to a non-negative value
+pub type Constraint = Box<dyn Fn(&[f64]) -> f64>;
+
+// derivative-free constrained minimization in the spirit of Powell's COBYLA: the
+// objective and constraints are modelled linearly by interpolation on a simplex of
+// n + 1 points, steps of length rho are taken on the linear models (projected along
+// the constraints that would be violated), and rho shrinks from `rho_begin` to
+// `rho_end` whenever a step fails to decrease the merit f + penalty * violation
+pub struct Cobyla {
+    pub rho_begin: f64,
+    pub rho_end: f64,
+    pub penalty: f64,
+    pub constraints: Vec<Constraint>,
+    pub criteria: ConvergenceCriteria,
+}
+
+impl Cobyla {
+    pub fn new(rho_begin: f64, rho_end: f64) -> Cobyla {
+        Cobyla {
+            rho_begin,
+            rho_end,
+            penalty: 100.,
+            constraints: vec![],
+            criteria: ConvergenceCriteria::default(),
+        }
+    }
+
+    pub fn with_constraint(mut self, constraint: impl Fn(&[f64]) -> f64 + 'static) -> Cobyla {
+        self.constraints.push(Box::new(constraint));
+        self
+    }
+
+    fn vertex(&self, objective: &CountingObjective, x: Vec<f64>) -> Vertex {
+        let f = objective.value(&x);
+        let c = self.constraints.iter().map(|c| c(&x)).collect::<Vec<_>>();
+        let violation = c.iter().map(|ci| (-ci).max(0.)).sum::<f64>();
+        Vertex {
+            merit: f + self.penalty * violation,
+            x,
+            f,
+            c,
+        }
+    }
+
+    fn simplex(&self, objective: &CountingObjective, best: Vertex, rho: f64) -> Vec<Vertex> {
+        let mut simplex = vec![];
+        for k in 0..best.x.len() {
+            let mut x = best.x.clone();
+            x[k] += rho;
+            simplex.push(self.vertex(objective, x));
+        }
+        simplex.insert(0, best);
+        simplex
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Vertex {
+    x: Vec<f64>,
+    f: f64,
+    c: Vec<f64>,
+    merit: f64,
+}
+
+// gaussian elimination with partial pivoting, None when the system is singular
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-14 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let (upper, lower) = a.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for (offset, row) in lower.iter_mut().enumerate() {
+            let factor = row[col] / pivot_row[col];
+            for (r, p) in row[col..].iter_mut().zip(pivot_row[col..].iter()) {
+                *r -= factor * p;
+            }
+            b[col + 1 + offset] -= factor * b[col];
+        }
+    }
+    let mut x = vec![0.; n];
+    for row in (0..n).rev() {
+        let sum = (row + 1..n).map(|k| a[row][k] * x[k]).sum::<f64>();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+// gradient of the linear interpolation of `value` over the simplex
+fn linear_model(simplex: &[Vertex], value: impl Fn(&Vertex) -> f64) -> Option<Vec<f64>> {
+    let a = simplex[1..]
+        .iter()
+        .map(|v| {
+            v.x.iter()
+                .zip(simplex[0].x.iter())
+                .map(|(x, x0)| x - x0)
+                .collect()
+        })
+        .collect();
+    let b = simplex[1..]
+        .iter()
+        .map(|v| value(v) - value(&simplex[0]))
+        .collect();
+    solve(a, b)
+}
+
+impl Optimizer for Cobyla {
+    fn minimize_with_callback(
+        &self,
+        objective: &dyn Objective,
+        initial: &[f64],
+        callback: Callback,
+    ) -> OptimizationResult {
+        let objective = CountingObjective::new(objective);
+        let mut tracker = Tracker::new(self.criteria, callback).ignore_value_change();
+        let mut rho = self.rho_begin;
+        let start = self.vertex(&objective, initial.to_vec());
+        let mut simplex = self.simplex(&objective, start, rho);
+        let mut fresh = true;
+
+        loop {
+            let best = (0..simplex.len())
+                .min_by(|&i, &j| simplex[i].merit.total_cmp(&simplex[j].merit))
+                .unwrap();
+            simplex.swap(0, best);
+            if rho < self.rho_end {
+                tracker.mark_converged();
+            }
+            if !tracker.record(simplex[0].f, &simplex[0].x, None) {
+                break;
+            }
+
+            let models = linear_model(&simplex, |v| v.f).and_then(|gf| {
+                (0..self.constraints.len())
+                    .map(|i| linear_model(&simplex, |v| v.c[i]))
+                    .collect::<Option<Vec<_>>>()
+                    .map(|gc| (gf, gc))
+            });
+            let (gf, gc) = match models {
+                Some(models) => models,
+                None => {
+                    simplex = self.simplex(&objective, simplex[0].clone(), rho);
+                    fresh = true;
+                    continue;
+                }
+            };
+
+            // steepest descent of the linear merit, then projection along the
+            // constraints that the step would violate
+            let mut d = gf.iter().map(|g| -g).collect::<Vec<_>>();
+            for (i, g) in gc.iter().enumerate() {
+                if simplex[0].c[i] < 0. {
+                    for (dk, gk) in d.iter_mut().zip(g.iter()) {
+                        *dk += self.penalty * gk;
+                    }
+                }
+            }
+            for _ in 0..2 {
+                for (i, g) in gc.iter().enumerate() {
+                    let rate = dot(g, &d);
+                    let norm_d = norm(&d);
+                    if simplex[0].c[i] >= 0.
+                        && rate < 0.
+                        && norm_d > 0.
+                        && simplex[0].c[i] + rho * rate / norm_d < 0.
+                    {
+                        let scale = rate / dot(g, g);
+                        for (dk, gk) in d.iter_mut().zip(g.iter()) {
+                            *dk -= scale * gk;
+                        }
+                    }
+                }
+            }
+
+            let length = norm(&d);
+            let mut improved = false;
+            if length > 1e-14 {
+                let x = simplex[0]
+                    .x
+                    .iter()
+                    .zip(d.iter())
+                    .map(|(x, dk)| x + rho * dk / length)
+                    .collect();
+                let candidate = self.vertex(&objective, x);
+                if candidate.merit < simplex[0].merit {
+                    let worst = (1..simplex.len())
+                        .max_by(|&i, &j| simplex[i].merit.total_cmp(&simplex[j].merit))
+                        .unwrap_or(0);
+                    simplex[worst] = candidate;
+                    improved = true;
+                    fresh = false;
+                }
+            }
+            if !improved {
+                if fresh {
+                    rho *= 0.5;
+                }
+                simplex = self.simplex(&objective, simplex[0].clone(), rho);
+                fresh = true;
+            } else {
+                let too_far = simplex.iter().any(|v| {
+                    let distance =
+                        v.x.iter()
+                            .zip(simplex[0].x.iter())
+                            .map(|(a, b)| (a - b).powi(2))
+                            .sum::<f64>()
+                            .sqrt();
+                    distance > 2. * rho
+                });
+                if too_far {
+                    simplex = self.simplex(&objective, simplex[0].clone(), rho);
+                    fresh = true;
+                }
+            }
+        }
+        let best = simplex[0].clone();
+        tracker.finish(&objective, best.x, best.f)
+    }
+}
+
+#[cfg(test)]
+use crate::optimizer::optimizer::{quadratic, FunctionObjective};
+
+#[test]
+fn cobyla_unconstrained_test() {
+    let optimizer = Cobyla::new(0.5, 1e-6);
+    let result = optimizer.minimize(&quadratic(), &[0., 0., 0.]);
+    assert!(result.converged);
+    assert!((result.parameters[0] - 1.).abs() < 1e-4);
+    assert!((result.parameters[1] + 0.5).abs() < 1e-4);
+    assert!((result.parameters[2] - 2.).abs() < 1e-4);
+}
+
+#[test]
+fn cobyla_constrained_test() {
+    // min (x - 2)^2 + (y - 1)^2 subject to x + y <= 2 and x >= 0, at (1.5, 0.5)
+    let objective = FunctionObjective {
+        num_parameters: 2,
+        function: |p: &[f64]| (p[0] - 2.).powi(2) + (p[1] - 1.).powi(2),
+    };
+    let mut optimizer = Cobyla::new(0.5, 1e-6)
+        .with_constraint(|p| 2. - p[0] - p[1])
+        .with_constraint(|p| p[0]);
+    optimizer.criteria.max_iterations = 5000;
+    let result = optimizer.minimize(&objective, &[0., 0.]);
+    assert!(result.converged);
+    assert!((result.parameters[0] - 1.5).abs() < 1e-3);
+    assert!((result.parameters[1] - 0.5).abs() < 1e-3);
+    assert!(2. - result.parameters[0] - result.parameters[1] > -1e-6);
+}
@@ -0,0 +1,59 @@
+use crate::optimizer::optimizer::{
+    Callback, ConvergenceCriteria, CountingObjective, Objective, OptimizationResult, Optimizer,
+    Tracker,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientDescent {
+    pub learning_rate: f64,
+    pub criteria: ConvergenceCriteria,
+}
+
+impl GradientDescent {
+    pub fn new(learning_rate: f64) -> GradientDescent {
+        GradientDescent {
+            learning_rate,
+            criteria: ConvergenceCriteria::default(),
+        }
+    }
+}
+
+impl Optimizer for GradientDescent {
+    fn minimize_with_callback(
+        &self,
+        objective: &dyn Objective,
+        initial: &[f64],
+        callback: Callback,
+    ) -> OptimizationResult {
+        let objective = CountingObjective::new(objective);
+        let mut tracker = Tracker::new(self.criteria, callback);
+        let mut parameters = initial.to_vec();
+        let mut value = objective.value(&parameters);
+        loop {
+            let gradient = objective.gradient(&parameters);
+            if !tracker.record(value, &parameters, Some(&gradient)) {
+                break;
+            }
+            for (p, g) in parameters.iter_mut().zip(gradient.iter()) {
+                *p -= self.learning_rate * g;
+            }
+            value = objective.value(&parameters);
+        }
+        tracker.finish(&objective, parameters, value)
+    }
+}
+
+#[cfg(test)]
+use crate::optimizer::optimizer::quadratic;
+
+#[test]
+fn gradient_descent_test() {
+    let optimizer = GradientDescent::new(0.2);
+    let result = optimizer.minimize(&quadratic(), &[0., 0., 0.]);
+    assert!(result.converged);
+    assert!(result.value < 1e-8);
+    assert!((result.parameters[0] - 1.).abs() < 1e-4);
+    assert!((result.parameters[1] + 0.5).abs() < 1e-4);
+    assert!((result.parameters[2] - 2.).abs() < 1e-4);
+    assert_eq!(result.iterations, result.history.len());
+}
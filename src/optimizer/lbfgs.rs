@@ -0,0 +1,175 @@
+use crate::optimizer::optimizer::{
+    dot, Callback, ConvergenceCriteria, CountingObjective, Objective, OptimizationResult,
+    Optimizer, Tracker,
+};
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LBFGS {
+    pub memory: usize,
+    // sufficient decrease and curvature constants of the (weak Wolfe) line search
+    pub armijo: f64,
+    pub curvature: f64,
+    pub max_line_search_steps: usize,
+    pub criteria: ConvergenceCriteria,
+}
+
+impl LBFGS {
+    pub fn new(memory: usize) -> LBFGS {
+        LBFGS {
+            memory,
+            armijo: 1e-4,
+            curvature: 0.9,
+            max_line_search_steps: 40,
+            criteria: ConvergenceCriteria::default(),
+        }
+    }
+}
+
+// two-loop recursion computing -H g from the stored (s, y) pairs
+fn direction(gradient: &[f64], pairs: &VecDeque<(Vec<f64>, Vec<f64>)>) -> Vec<f64> {
+    let mut q = gradient.to_vec();
+    let mut alphas = vec![];
+    for (s, y) in pairs.iter().rev() {
+        let alpha = dot(s, &q) / dot(y, s);
+        for (qk, yk) in q.iter_mut().zip(y.iter()) {
+            *qk -= alpha * yk;
+        }
+        alphas.push(alpha);
+    }
+    if let Some((s, y)) = pairs.back() {
+        let scale = dot(s, y) / dot(y, y);
+        for qk in q.iter_mut() {
+            *qk *= scale;
+        }
+    }
+    for ((s, y), alpha) in pairs.iter().zip(alphas.iter().rev()) {
+        let beta = dot(y, &q) / dot(y, s);
+        for (qk, sk) in q.iter_mut().zip(s.iter()) {
+            *qk += (alpha - beta) * sk;
+        }
+    }
+    q.iter().map(|x| -x).collect()
+}
+
+impl Optimizer for LBFGS {
+    fn minimize_with_callback(
+        &self,
+        objective: &dyn Objective,
+        initial: &[f64],
+        callback: Callback,
+    ) -> OptimizationResult {
+        let objective = CountingObjective::new(objective);
+        let mut tracker = Tracker::new(self.criteria, callback);
+        let mut pairs: VecDeque<(Vec<f64>, Vec<f64>)> = VecDeque::new();
+        let mut parameters = initial.to_vec();
+        let mut value = objective.value(&parameters);
+        let mut gradient = objective.gradient(&parameters);
+        while tracker.record(value, &parameters, Some(&gradient)) {
+            let mut d = direction(&gradient, &pairs);
+            let mut slope = dot(&gradient, &d);
+            if slope >= 0. {
+                // not a descent direction, restart from steepest descent
+                pairs.clear();
+                d = gradient.iter().map(|g| -g).collect();
+                slope = dot(&gradient, &d);
+            }
+
+            // bisection between a step that is too short (curvature condition fails)
+            // and one that is too long (sufficient decrease fails)
+            let mut step = 1.;
+            let (mut low, mut high) = (0., f64::INFINITY);
+            let mut accepted = None;
+            for _ in 0..self.max_line_search_steps {
+                let candidate = parameters
+                    .iter()
+                    .zip(d.iter())
+                    .map(|(p, dk)| p + step * dk)
+                    .collect::<Vec<_>>();
+                let candidate_value = objective.value(&candidate);
+                if candidate_value > value + self.armijo * step * slope {
+                    high = step;
+                } else {
+                    let candidate_gradient = objective.gradient(&candidate);
+                    if dot(&candidate_gradient, &d) < self.curvature * slope {
+                        low = step;
+                    } else {
+                        accepted = Some((candidate, candidate_value, candidate_gradient));
+                        break;
+                    }
+                }
+                step = if high.is_finite() {
+                    (low + high) / 2.
+                } else {
+                    2. * low
+                };
+            }
+            let (candidate, candidate_value, candidate_gradient) = match accepted {
+                Some(accepted) => accepted,
+                // no acceptable step, which says nothing about being at a minimum
+                None => break,
+            };
+
+            let s = candidate
+                .iter()
+                .zip(parameters.iter())
+                .map(|(a, b)| a - b)
+                .collect::<Vec<_>>();
+            let y = candidate_gradient
+                .iter()
+                .zip(gradient.iter())
+                .map(|(a, b)| a - b)
+                .collect::<Vec<_>>();
+            if dot(&s, &y) > 1e-12 {
+                pairs.push_back((s, y));
+                if pairs.len() > self.memory {
+                    pairs.pop_front();
+                }
+            }
+            parameters = candidate;
+            value = candidate_value;
+            gradient = candidate_gradient;
+        }
+        tracker.finish(&objective, parameters, value)
+    }
+}
+
+#[cfg(test)]
+use crate::optimizer::optimizer::{quadratic, rosenbrock};
+
+#[test]
+fn lbfgs_test() {
+    let optimizer = LBFGS::new(10);
+    let result = optimizer.minimize(&rosenbrock(), &[-1.2, 1.]);
+    assert!(result.converged);
+    assert!((result.parameters[0] - 1.).abs() < 1e-4);
+    assert!((result.parameters[1] - 1.).abs() < 1e-4);
+    assert!(result.iterations < 100);
+
+    let result = optimizer.minimize(&quadratic(), &[0., 0., 0.]);
+    assert!(result.value < 1e-10);
+}
+
+#[test]
+fn line_search_failure_test() {
+    // the gradient points uphill, so every step fails the sufficient decrease
+    struct Uphill;
+    impl Objective for Uphill {
+        fn num_parameters(&self) -> usize {
+            1
+        }
+
+        fn value(&self, parameters: &[f64]) -> f64 {
+            parameters[0] * parameters[0]
+        }
+
+        fn gradient(&self, parameters: &[f64]) -> Vec<f64> {
+            vec![-2. * parameters[0]]
+        }
+    }
+
+    let result = LBFGS::new(5).minimize(&Uphill, &[1.]);
+    assert!(!result.converged);
+    assert_eq!(1, result.iterations);
+    assert_eq!(vec![1.], result.parameters);
+}
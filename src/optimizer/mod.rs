@@ -0,0 +1,8 @@
+pub mod adam;
+pub mod cobyla;
+pub mod gradient_descent;
+pub mod lbfgs;
+pub mod nelder_mead;
+#[allow(clippy::module_inception)]
+pub mod optimizer;
+pub mod spsa;
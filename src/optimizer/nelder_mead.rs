@@ -0,0 +1,125 @@
+use crate::optimizer::optimizer::{
+    Callback, ConvergenceCriteria, CountingObjective, Objective, OptimizationResult, Optimizer,
+    Tracker,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NelderMead {
+    pub initial_step: f64,
+    pub reflection: f64,
+    pub expansion: f64,
+    pub contraction: f64,
+    pub shrink: f64,
+    pub criteria: ConvergenceCriteria,
+}
+
+impl NelderMead {
+    pub fn new(initial_step: f64) -> NelderMead {
+        NelderMead {
+            initial_step,
+            reflection: 1.,
+            expansion: 2.,
+            contraction: 0.5,
+            shrink: 0.5,
+            criteria: ConvergenceCriteria::default(),
+        }
+    }
+}
+
+fn towards(from: &[f64], to: &[f64], t: f64) -> Vec<f64> {
+    from.iter()
+        .zip(to.iter())
+        .map(|(f, t_)| f + t * (t_ - f))
+        .collect()
+}
+
+impl Optimizer for NelderMead {
+    fn minimize_with_callback(
+        &self,
+        objective: &dyn Objective,
+        initial: &[f64],
+        callback: Callback,
+    ) -> OptimizationResult {
+        let objective = CountingObjective::new(objective);
+        let mut tracker = Tracker::new(self.criteria, callback).ignore_value_change();
+        let n = initial.len();
+        let mut simplex = vec![initial.to_vec()];
+        for k in 0..n {
+            let mut vertex = initial.to_vec();
+            vertex[k] += self.initial_step;
+            simplex.push(vertex);
+        }
+        let mut values = simplex
+            .iter()
+            .map(|v| objective.value(v))
+            .collect::<Vec<_>>();
+
+        loop {
+            let mut order = (0..=n).collect::<Vec<_>>();
+            order.sort_by(|&i, &j| values[i].total_cmp(&values[j]));
+            simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+            values = order.iter().map(|&i| values[i]).collect();
+
+            if values[n] - values[0] < self.criteria.tolerance {
+                tracker.mark_converged();
+            }
+            if !tracker.record(values[0], &simplex[0], None) {
+                break;
+            }
+
+            let centroid = (0..n)
+                .map(|k| simplex[..n].iter().map(|v| v[k]).sum::<f64>() / n as f64)
+                .collect::<Vec<_>>();
+            let reflected = towards(&simplex[n], &centroid, 1. + self.reflection);
+            let reflected_value = objective.value(&reflected);
+            if reflected_value < values[0] {
+                let expanded = towards(&simplex[n], &centroid, 1. + self.expansion);
+                let expanded_value = objective.value(&expanded);
+                if expanded_value < reflected_value {
+                    simplex[n] = expanded;
+                    values[n] = expanded_value;
+                } else {
+                    simplex[n] = reflected;
+                    values[n] = reflected_value;
+                }
+            } else if reflected_value < values[n - 1] {
+                simplex[n] = reflected;
+                values[n] = reflected_value;
+            } else {
+                let (base, base_value) = if reflected_value < values[n] {
+                    (reflected, reflected_value)
+                } else {
+                    (simplex[n].clone(), values[n])
+                };
+                let contracted = towards(&centroid, &base, self.contraction);
+                let contracted_value = objective.value(&contracted);
+                if contracted_value < base_value {
+                    simplex[n] = contracted;
+                    values[n] = contracted_value;
+                } else {
+                    for i in 1..=n {
+                        simplex[i] = towards(&simplex[0], &simplex[i], self.shrink);
+                        values[i] = objective.value(&simplex[i]);
+                    }
+                }
+            }
+        }
+        let (parameters, value) = (simplex[0].clone(), values[0]);
+        tracker.finish(&objective, parameters, value)
+    }
+}
+
+#[cfg(test)]
+use crate::optimizer::optimizer::rosenbrock;
+
+#[test]
+fn nelder_mead_test() {
+    let mut optimizer = NelderMead::new(0.5);
+    optimizer.criteria.max_iterations = 5000;
+    optimizer.criteria.tolerance = 1e-14;
+    let result = optimizer.minimize(&rosenbrock(), &[-1.2, 1.]);
+    assert!(result.converged);
+    assert!((result.parameters[0] - 1.).abs() < 1e-3);
+    assert!((result.parameters[1] - 1.).abs() < 1e-3);
+    assert_eq!(0, result.gradient_evaluations);
+}
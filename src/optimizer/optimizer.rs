@@ -0,0 +1,306 @@
+use crate::circuit::parameter::ParameterizedCircuit;
+use crate::gradient::gradient::{
+    expectation_value, finite_difference_gradient, gradient, GradientMethod,
+    DEFAULT_FINITE_DIFFERENCE_STEP,
+};
+use crate::operator::pauli::SparsePauliOp;
+use std::cell::Cell;
+use std::fmt;
+
+pub trait Objective {
+    fn num_parameters(&self) -> usize;
+
+    fn value(&self, parameters: &[f64]) -> f64;
+
+    fn gradient(&self, parameters: &[f64]) -> Vec<f64> {
+        finite_difference_gradient(
+            |p| self.value(p),
+            parameters,
+            DEFAULT_FINITE_DIFFERENCE_STEP,
+        )
+    }
+}
+
+// <O> of a parameterized circuit, with parameters in the order of `circuit.parameters()`
+pub struct ExpectationObjective<'a> {
+    pub circuit: &'a ParameterizedCircuit,
+    pub observable: &'a SparsePauliOp,
+    pub method: GradientMethod,
+}
+
+impl<'a> ExpectationObjective<'a> {
+    pub fn new(
+        circuit: &'a ParameterizedCircuit,
+        observable: &'a SparsePauliOp,
+    ) -> ExpectationObjective<'a> {
+        ExpectationObjective {
+            circuit,
+            observable,
+            method: GradientMethod::Adjoint,
+        }
+    }
+}
+
+impl Objective for ExpectationObjective<'_> {
+    fn num_parameters(&self) -> usize {
+        self.circuit.num_parameters()
+    }
+
+    fn value(&self, parameters: &[f64]) -> f64 {
+        expectation_value(self.circuit, self.observable, parameters)
+    }
+
+    fn gradient(&self, parameters: &[f64]) -> Vec<f64> {
+        gradient(self.circuit, self.observable, parameters, self.method)
+    }
+}
+
+pub struct FunctionObjective<F: Fn(&[f64]) -> f64> {
+    pub num_parameters: usize,
+    pub function: F,
+}
+
+impl<F: Fn(&[f64]) -> f64> Objective for FunctionObjective<F> {
+    fn num_parameters(&self) -> usize {
+        self.num_parameters
+    }
+
+    fn value(&self, parameters: &[f64]) -> f64 {
+        (self.function)(parameters)
+    }
+}
+
+// counts the objective calls made by an optimizer
+pub struct CountingObjective<'a> {
+    objective: &'a dyn Objective,
+    evaluations: Cell<usize>,
+    gradient_evaluations: Cell<usize>,
+}
+
+impl<'a> CountingObjective<'a> {
+    pub fn new(objective: &'a dyn Objective) -> CountingObjective<'a> {
+        CountingObjective {
+            objective,
+            evaluations: Cell::new(0),
+            gradient_evaluations: Cell::new(0),
+        }
+    }
+
+    pub fn evaluations(&self) -> usize {
+        self.evaluations.get()
+    }
+
+    pub fn gradient_evaluations(&self) -> usize {
+        self.gradient_evaluations.get()
+    }
+}
+
+impl Objective for CountingObjective<'_> {
+    fn num_parameters(&self) -> usize {
+        self.objective.num_parameters()
+    }
+
+    fn value(&self, parameters: &[f64]) -> f64 {
+        self.evaluations.set(self.evaluations.get() + 1);
+        self.objective.value(parameters)
+    }
+
+    fn gradient(&self, parameters: &[f64]) -> Vec<f64> {
+        self.gradient_evaluations
+            .set(self.gradient_evaluations.get() + 1);
+        self.objective.gradient(parameters)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConvergenceCriteria {
+    pub max_iterations: usize,
+    // stop when the objective changes less than this between iterations
+    pub tolerance: f64,
+    // stop when the gradient norm drops below this (gradient based optimizers only)
+    pub gradient_tolerance: f64,
+}
+
+impl Default for ConvergenceCriteria {
+    fn default() -> ConvergenceCriteria {
+        ConvergenceCriteria {
+            max_iterations: 1000,
+            tolerance: 1e-10,
+            gradient_tolerance: 1e-8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Iteration {
+    pub iteration: usize,
+    pub value: f64,
+    pub parameters: Vec<f64>,
+    pub gradient_norm: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizationResult {
+    pub parameters: Vec<f64>,
+    pub value: f64,
+    pub iterations: usize,
+    pub evaluations: usize,
+    pub gradient_evaluations: usize,
+    pub converged: bool,
+    pub history: Vec<Iteration>,
+}
+
+impl fmt::Display for OptimizationResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "value: {}, iterations: {}, evaluations: {}, converged: {}, parameters: {:?}",
+            self.value, self.iterations, self.evaluations, self.converged, self.parameters
+        )
+    }
+}
+
+// returning false from the callback stops the optimization
+pub type Callback<'a> = &'a mut dyn FnMut(&Iteration) -> bool;
+
+pub trait Optimizer {
+    fn minimize_with_callback(
+        &self,
+        objective: &dyn Objective,
+        initial: &[f64],
+        callback: Callback,
+    ) -> OptimizationResult;
+
+    fn minimize(&self, objective: &dyn Objective, initial: &[f64]) -> OptimizationResult {
+        self.minimize_with_callback(objective, initial, &mut |_| true)
+    }
+}
+
+// shared bookkeeping of the iteration history, callbacks and stopping rules
+pub struct Tracker<'a> {
+    pub criteria: ConvergenceCriteria,
+    callback: Callback<'a>,
+    history: Vec<Iteration>,
+    stopped: bool,
+    converged: bool,
+    check_value_change: bool,
+}
+
+impl<'a> Tracker<'a> {
+    pub fn new(criteria: ConvergenceCriteria, callback: Callback<'a>) -> Tracker<'a> {
+        Tracker {
+            criteria,
+            callback,
+            history: vec![],
+            stopped: false,
+            converged: false,
+            check_value_change: true,
+        }
+    }
+
+    // simplex methods often keep the best value for several iterations, so they
+    // decide convergence themselves instead of comparing consecutive values
+    pub fn ignore_value_change(mut self) -> Tracker<'a> {
+        self.check_value_change = false;
+        self
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.history.len()
+    }
+
+    // records an iteration and returns whether the optimizer should keep going
+    pub fn record(&mut self, value: f64, parameters: &[f64], gradient: Option<&[f64]>) -> bool {
+        let gradient_norm = gradient.map(norm);
+        let previous = self.history.last().map(|it| it.value);
+        let iteration = Iteration {
+            iteration: self.history.len(),
+            value,
+            parameters: parameters.to_vec(),
+            gradient_norm,
+        };
+        if !(self.callback)(&iteration) {
+            self.stopped = true;
+        }
+        self.history.push(iteration);
+
+        if let Some(norm) = gradient_norm {
+            if norm < self.criteria.gradient_tolerance {
+                self.converged = true;
+            }
+        }
+        if let (Some(previous), true) = (previous, self.check_value_change) {
+            if (previous - value).abs() < self.criteria.tolerance {
+                self.converged = true;
+            }
+        }
+        !(self.stopped || self.converged || self.history.len() >= self.criteria.max_iterations)
+    }
+
+    pub fn mark_converged(&mut self) {
+        self.converged = true;
+    }
+
+    pub fn finish(
+        self,
+        objective: &CountingObjective,
+        parameters: Vec<f64>,
+        value: f64,
+    ) -> OptimizationResult {
+        OptimizationResult {
+            parameters,
+            value,
+            iterations: self.history.len(),
+            evaluations: objective.evaluations(),
+            gradient_evaluations: objective.gradient_evaluations(),
+            converged: self.converged,
+            history: self.history,
+        }
+    }
+}
+
+pub fn norm(vector: &[f64]) -> f64 {
+    vector.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+pub fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+pub fn rosenbrock() -> FunctionObjective<impl Fn(&[f64]) -> f64> {
+    FunctionObjective {
+        num_parameters: 2,
+        function: |p: &[f64]| (1. - p[0]).powi(2) + 100. * (p[1] - p[0] * p[0]).powi(2),
+    }
+}
+
+#[cfg(test)]
+pub fn quadratic() -> FunctionObjective<impl Fn(&[f64]) -> f64> {
+    FunctionObjective {
+        num_parameters: 3,
+        function: |p: &[f64]| {
+            (p[0] - 1.).powi(2) + 2. * (p[1] + 0.5).powi(2) + 0.5 * (p[2] - 2.).powi(2)
+        },
+    }
+}
+
+#[test]
+fn tracker_test() {
+    let mut seen = vec![];
+    let mut callback = |it: &Iteration| {
+        seen.push(it.iteration);
+        it.iteration < 2
+    };
+    let objective = quadratic();
+    let counting = CountingObjective::new(&objective);
+    let mut tracker = Tracker::new(ConvergenceCriteria::default(), &mut callback);
+    assert!(tracker.record(counting.value(&[0., 0., 0.]), &[0., 0., 0.], None));
+    assert!(tracker.record(counting.value(&[1., 0., 0.]), &[1., 0., 0.], None));
+    assert!(!tracker.record(counting.value(&[1., 0., 1.]), &[1., 0., 1.], None));
+    let result = tracker.finish(&counting, vec![1., 0., 1.], 1.);
+    assert_eq!(3, result.iterations);
+    assert_eq!(3, result.evaluations);
+    assert!(!result.converged);
+    assert_eq!(vec![0, 1, 2], seen);
+}
@@ -0,0 +1,90 @@
+use crate::optimizer::optimizer::{
+    Callback, ConvergenceCriteria, CountingObjective, Objective, OptimizationResult, Optimizer,
+    Tracker,
+};
+
+// simultaneous perturbation stochastic approximation (Spall); only two objective
+// evaluations per iteration regardless of the number of parameters, which suits
+// shot-based objectives. the tracked value is their mean, which estimates the value
+// at the current parameters up to O(c_k^2), and the returned value is evaluated once
+// at the end
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SPSA {
+    pub a: f64,
+    pub c: f64,
+    pub alpha: f64,
+    pub gamma: f64,
+    pub stability: f64,
+    pub criteria: ConvergenceCriteria,
+}
+
+impl SPSA {
+    pub fn new(a: f64, c: f64) -> SPSA {
+        SPSA {
+            a,
+            c,
+            alpha: 0.602,
+            gamma: 0.101,
+            stability: 10.,
+            criteria: ConvergenceCriteria::default(),
+        }
+    }
+}
+
+impl Optimizer for SPSA {
+    fn minimize_with_callback(
+        &self,
+        objective: &dyn Objective,
+        initial: &[f64],
+        callback: Callback,
+    ) -> OptimizationResult {
+        let objective = CountingObjective::new(objective);
+        let mut tracker = Tracker::new(self.criteria, callback);
+        let mut parameters = initial.to_vec();
+        let mut k = 0;
+        loop {
+            let a_k = self.a / (k as f64 + 1. + self.stability).powf(self.alpha);
+            let c_k = self.c / (k as f64 + 1.).powf(self.gamma);
+            let delta = (0..parameters.len())
+                .map(|_| if rand::random::<bool>() { 1. } else { -1. })
+                .collect::<Vec<f64>>();
+            let plus = parameters
+                .iter()
+                .zip(delta.iter())
+                .map(|(p, d)| p + c_k * d)
+                .collect::<Vec<_>>();
+            let minus = parameters
+                .iter()
+                .zip(delta.iter())
+                .map(|(p, d)| p - c_k * d)
+                .collect::<Vec<_>>();
+            let (value_plus, value_minus) = (objective.value(&plus), objective.value(&minus));
+            if !tracker.record((value_plus + value_minus) / 2., &parameters, None) {
+                break;
+            }
+            let difference = (value_plus - value_minus) / (2. * c_k);
+            for (p, d) in parameters.iter_mut().zip(delta.iter()) {
+                *p -= a_k * difference * d;
+            }
+            k += 1;
+        }
+        let value = objective.value(&parameters);
+        tracker.finish(&objective, parameters, value)
+    }
+}
+
+#[cfg(test)]
+use crate::optimizer::optimizer::quadratic;
+
+#[test]
+fn spsa_test() {
+    let mut optimizer = SPSA::new(0.2, 0.1);
+    optimizer.criteria.max_iterations = 2000;
+    optimizer.criteria.tolerance = 0.;
+    let result = optimizer.minimize(&quadratic(), &[0., 0., 0.]);
+    assert!(result.value < 1e-3);
+    assert_eq!(2000, result.iterations);
+    // two evaluations per iteration and one for the returned value
+    assert_eq!(2 * 2000 + 1, result.evaluations);
+    assert_eq!(0, result.gradient_evaluations);
+}
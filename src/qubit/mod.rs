@@ -0,0 +1,4 @@
+pub mod qint;
+#[allow(clippy::module_inception)]
+pub mod qubit;
+pub mod register;
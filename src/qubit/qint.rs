@@ -0,0 +1,260 @@
+use crate::arithmetic::{comparator, draper, multiplier};
+use crate::circuit::circuit::QuantumCircuit;
+use crate::qubit::qubit::AsQubit;
+use crate::qubit::register::QuantumRegister;
+use std::cell::RefCell;
+use std::ops::{AddAssign, Mul, SubAssign};
+
+// an unsigned integer on a register of a circuit shared through a RefCell, whose
+// operators emit their gates into that circuit. the arithmetic wraps modulo 2^n for
+// the n qubits of the left operand, the right one being no wider
+pub struct QInt<'a> {
+    circuit: &'a RefCell<QuantumCircuit>,
+    register: QuantumRegister,
+}
+
+impl<'a> QInt<'a> {
+    // an integer on a register of qubits already in the circuit
+    pub fn new(circuit: &'a RefCell<QuantumCircuit>, register: &QuantumRegister) -> QInt<'a> {
+        let size = circuit.borrow().size();
+        assert!(register.indices().iter().all(|&index| index < size));
+        QInt {
+            circuit,
+            register: register.clone(),
+        }
+    }
+
+    // the value written on a register in |0>
+    pub fn with_value(
+        circuit: &'a RefCell<QuantumCircuit>,
+        register: &QuantumRegister,
+        value: usize,
+    ) -> QInt<'a> {
+        assert!(value < 1 << register.len());
+        let qint = QInt::new(circuit, register);
+        let mut qc = circuit.borrow_mut();
+        for (bit, &qubit) in qint.register.qubits().iter().enumerate() {
+            if value >> bit & 1 == 1 {
+                qc.X(qubit);
+            }
+        }
+        drop(qc);
+        qint
+    }
+
+    // a product on new qubits as wide as self, left out of the registers of the
+    // circuit so that repeated products do not clash by name
+    fn product(&self, name: String) -> QInt<'a> {
+        let indices = self.circuit.borrow_mut().add_qubits(self.len());
+        QInt {
+            circuit: self.circuit,
+            register: QuantumRegister::new(&name, &indices),
+        }
+    }
+
+    pub fn register(&self) -> &QuantumRegister {
+        &self.register
+    }
+
+    pub fn len(&self) -> usize {
+        self.register.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.register.is_empty()
+    }
+
+    // collapses the register onto the measured value
+    pub fn measure(&self) -> usize {
+        let mut qc = self.circuit.borrow_mut();
+        self.register
+            .qubits()
+            .iter()
+            .enumerate()
+            .map(|(bit, qubit)| qc.measure(qubit.index, 1).ones() << bit)
+            .sum()
+    }
+
+    // flips the flag when self < other
+    pub fn compare_lt(&self, other: &QInt<'a>, flag: impl AsQubit) {
+        self.check_operand(other);
+        assert_eq!(self.len(), other.len());
+        let mut qc = self.circuit.borrow_mut();
+        comparator::less_than(
+            &mut qc,
+            &self.indices(),
+            &other.indices(),
+            flag.qubit_index(),
+        );
+    }
+
+    // flips the flag when self > other
+    pub fn compare_gt(&self, other: &QInt<'a>, flag: impl AsQubit) {
+        other.compare_lt(self, flag);
+    }
+
+    // flips the flag when self = other
+    pub fn compare_eq(&self, other: &QInt<'a>, flag: impl AsQubit) {
+        self.check_operand(other);
+        assert_eq!(self.len(), other.len());
+        let mut qc = self.circuit.borrow_mut();
+        comparator::equal(
+            &mut qc,
+            &self.indices(),
+            &other.indices(),
+            flag.qubit_index(),
+        );
+    }
+
+    // self += x * constant
+    pub fn add_scaled(&mut self, x: &QInt<'a>, constant: usize) {
+        self.check_operand(x);
+        let mut qc = self.circuit.borrow_mut();
+        multiplier::multiply_constant(&mut qc, &[], &x.indices(), &self.indices(), constant);
+    }
+
+    // self += a * b
+    pub fn add_product(&mut self, a: &QInt<'a>, b: &QInt<'a>) {
+        self.check_operand(a);
+        self.check_operand(b);
+        let mut qc = self.circuit.borrow_mut();
+        multiplier::multiply(&mut qc, &a.indices(), &b.indices(), &self.indices());
+    }
+
+    fn indices(&self) -> Vec<usize> {
+        self.register.indices()
+    }
+
+    fn check_operand(&self, other: &QInt<'a>) {
+        assert!(
+            std::ptr::eq(self.circuit, other.circuit),
+            "the integers should be on the same circuit"
+        );
+        assert!(other.len() <= self.len());
+    }
+}
+
+impl<'a> AddAssign<&QInt<'a>> for QInt<'a> {
+    fn add_assign(&mut self, other: &QInt<'a>) {
+        self.check_operand(other);
+        let mut qc = self.circuit.borrow_mut();
+        draper::add(&mut qc, &[], &other.indices(), &self.indices());
+    }
+}
+
+impl AddAssign<usize> for QInt<'_> {
+    fn add_assign(&mut self, constant: usize) {
+        let mut qc = self.circuit.borrow_mut();
+        draper::add_constant(&mut qc, &[], &self.indices(), constant);
+    }
+}
+
+impl<'a> SubAssign<&QInt<'a>> for QInt<'a> {
+    fn sub_assign(&mut self, other: &QInt<'a>) {
+        self.check_operand(other);
+        let mut qc = self.circuit.borrow_mut();
+        draper::subtract(&mut qc, &[], &other.indices(), &self.indices());
+    }
+}
+
+impl SubAssign<usize> for QInt<'_> {
+    fn sub_assign(&mut self, constant: usize) {
+        let mut qc = self.circuit.borrow_mut();
+        draper::subtract_constant(&mut qc, &[], &self.indices(), constant);
+    }
+}
+
+// the product on new qubits as wide as the left operand
+impl<'a> Mul<usize> for &QInt<'a> {
+    type Output = QInt<'a>;
+
+    fn mul(self, constant: usize) -> QInt<'a> {
+        let mut product = self.product(format!("{}*{}", self.register.name(), constant));
+        product.add_scaled(self, constant);
+        product
+    }
+}
+
+impl<'a> Mul<&QInt<'a>> for &QInt<'a> {
+    type Output = QInt<'a>;
+
+    fn mul(self, other: &QInt<'a>) -> QInt<'a> {
+        self.check_operand(other);
+        let mut product = self.product(format!(
+            "{}*{}",
+            self.register.name(),
+            other.register.name()
+        ));
+        product.add_product(self, other);
+        product
+    }
+}
+
+#[test]
+fn qint_arithmetic_test() {
+    let circuit = RefCell::new(QuantumCircuit::new(0));
+    let a = circuit.borrow_mut().add_register("a", 4);
+    let b = circuit.borrow_mut().add_register("b", 3);
+    let mut a = QInt::with_value(&circuit, &a, 5);
+    let b = QInt::with_value(&circuit, &b, 6);
+    a += &b;
+    a += 9;
+    a -= 3;
+    // a = 17 mod 16
+    let c = &a * 3;
+    let d = &a * &b;
+    a -= &b;
+    assert_eq!("a*3", c.register().name());
+    assert_eq!("a*b", d.register().name());
+    assert_eq!(15, circuit.borrow().size());
+    assert_eq!(11, a.measure());
+    assert_eq!(6, b.measure());
+    assert_eq!(3, c.measure());
+    assert_eq!(6, d.measure());
+}
+
+#[test]
+fn qint_superposition_test() {
+    // b = a + 2 and the flag a < b hold on every branch of a
+    let circuit = RefCell::new(QuantumCircuit::new(0));
+    let flag = circuit.borrow_mut().add_register("flag", 1);
+    let a = circuit.borrow_mut().add_register("a", 3);
+    let b = circuit.borrow_mut().add_register("b", 3);
+    let a = QInt::new(&circuit, &a);
+    for &qubit in a.register().qubits().iter() {
+        circuit.borrow_mut().H(qubit);
+    }
+    let mut b = QInt::new(&circuit, &b);
+    b += &a;
+    b += 2;
+    a.compare_lt(&b, flag[0]);
+    let (x, y) = (a.measure(), b.measure());
+    assert_eq!((x + 2) % 8, y);
+    assert_eq!(
+        usize::from(x < 6),
+        circuit.borrow_mut().measure(flag[0], 1).ones()
+    );
+}
+
+#[test]
+fn qint_comparison_test() {
+    let circuit = RefCell::new(QuantumCircuit::new(0));
+    let flags = circuit.borrow_mut().add_register("flags", 3);
+    let [a, b, c] = ["a", "b", "c"].map(|name| circuit.borrow_mut().add_register(name, 3));
+    let a = QInt::with_value(&circuit, &a, 4);
+    let b = QInt::with_value(&circuit, &b, 6);
+    let c = QInt::with_value(&circuit, &c, 6);
+    a.compare_lt(&b, flags[0]);
+    a.compare_gt(&b, flags[1]);
+    b.compare_eq(&c, flags[2]);
+    let mut qc = circuit.borrow_mut();
+    let bits = qc.add_classical_register("bits", 3);
+    assert_eq!(0b101, qc.measure_register(&flags, &bits));
+}
+
+#[test]
+#[should_panic]
+fn outside_register_test() {
+    let circuit = RefCell::new(QuantumCircuit::new(3));
+    QInt::new(&circuit, &QuantumRegister::new("a", &[2, 3]));
+}
@@ -1,4 +1,65 @@
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+// a qubit of a circuit, remembering the register it was created in if any. two
+// qubits are equal when they are the same qubit of the circuit, whatever the register
+#[derive(Debug, Clone, Copy)]
 pub struct Qubit {
     pub index: usize,
+    register: Option<usize>,
+}
+
+impl Qubit {
+    // a qubit outside of any register
+    pub fn new(index: usize) -> Qubit {
+        Qubit {
+            index,
+            register: None,
+        }
+    }
+
+    pub(crate) fn in_register(index: usize, register: usize) -> Qubit {
+        Qubit {
+            index,
+            register: Some(register),
+        }
+    }
+
+    // the id of the register the qubit was created in
+    pub fn register(&self) -> Option<usize> {
+        self.register
+    }
+}
+
+impl PartialEq for Qubit {
+    fn eq(&self, other: &Qubit) -> bool {
+        self.index == other.index
+    }
+}
+
+impl Eq for Qubit {}
+
+// a bit of the classical memory of a circuit
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Clbit {
+    pub index: usize,
+}
+
+// what the gates of a circuit accept for a qubit, a bare index or a handle
+pub trait AsQubit {
+    fn qubit_index(&self) -> usize;
+}
+
+impl AsQubit for usize {
+    fn qubit_index(&self) -> usize {
+        *self
+    }
+}
+
+impl AsQubit for Qubit {
+    fn qubit_index(&self) -> usize {
+        self.index
+    }
+}
+
+// the circuit indices of a list of qubits
+pub fn qubit_indices(qubits: &[impl AsQubit]) -> Vec<usize> {
+    qubits.iter().map(|qubit| qubit.qubit_index()).collect()
 }
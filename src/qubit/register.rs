@@ -0,0 +1,194 @@
+use crate::qubit::qubit::{Clbit, Qubit};
+use std::ops::{Index, Range};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_REGISTER_ID: AtomicUsize = AtomicUsize::new(0);
+
+// a named list of qubits of a circuit, the first one the least significant bit
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct QuantumRegister {
+    name: String,
+    id: usize,
+    qubits: Vec<Qubit>,
+}
+
+impl QuantumRegister {
+    // a register owning the qubits at the indices
+    pub fn new(name: &str, indices: &[usize]) -> QuantumRegister {
+        let id = NEXT_REGISTER_ID.fetch_add(1, Ordering::Relaxed);
+        let qubits = indices
+            .iter()
+            .map(|&index| Qubit::in_register(index, id))
+            .collect();
+        QuantumRegister::with_qubits(name, id, qubits)
+    }
+
+    // a register over qubits that keep the register they were created in
+    fn view(name: &str, qubits: Vec<Qubit>) -> QuantumRegister {
+        let id = NEXT_REGISTER_ID.fetch_add(1, Ordering::Relaxed);
+        QuantumRegister::with_qubits(name, id, qubits)
+    }
+
+    fn with_qubits(name: &str, id: usize, qubits: Vec<Qubit>) -> QuantumRegister {
+        for (k, qubit) in qubits.iter().enumerate() {
+            assert!(
+                !qubits[..k].contains(qubit),
+                "qubit {} is repeated",
+                qubit.index
+            );
+        }
+        QuantumRegister {
+            name: name.to_string(),
+            id,
+            qubits,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // unique among the registers of the process, recorded by the qubits it creates
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    // whether the qubit was created in this register
+    pub fn owns(&self, qubit: Qubit) -> bool {
+        qubit.register() == Some(self.id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.qubits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.qubits.is_empty()
+    }
+
+    pub fn qubit(&self, i: usize) -> Qubit {
+        self.qubits[i]
+    }
+
+    pub fn qubits(&self) -> &[Qubit] {
+        &self.qubits
+    }
+
+    // the circuit indices of the qubits
+    pub fn indices(&self) -> Vec<usize> {
+        self.qubits.iter().map(|qubit| qubit.index).collect()
+    }
+
+    // the qubits in the range as a register named like name[1..3]
+    pub fn slice(&self, range: Range<usize>) -> QuantumRegister {
+        let name = format!("{}[{}..{}]", self.name, range.start, range.end);
+        QuantumRegister::view(&name, self.qubits[range].to_vec())
+    }
+
+    // the qubits of self followed by those of other, above them
+    pub fn concat(&self, other: &QuantumRegister) -> QuantumRegister {
+        let name = format!("{}+{}", self.name, other.name);
+        QuantumRegister::view(&name, [self.qubits(), other.qubits()].concat())
+    }
+}
+
+impl Index<usize> for QuantumRegister {
+    type Output = Qubit;
+
+    fn index(&self, i: usize) -> &Qubit {
+        &self.qubits[i]
+    }
+}
+
+// a named list of classical bits of a circuit, the first one the least significant
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ClassicalRegister {
+    name: String,
+    clbits: Vec<Clbit>,
+}
+
+impl ClassicalRegister {
+    pub fn new(name: &str, indices: &[usize]) -> ClassicalRegister {
+        for (k, index) in indices.iter().enumerate() {
+            assert!(!indices[..k].contains(index), "bit {} is repeated", index);
+        }
+        ClassicalRegister {
+            name: name.to_string(),
+            clbits: indices.iter().map(|&index| Clbit { index }).collect(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn len(&self) -> usize {
+        self.clbits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clbits.is_empty()
+    }
+
+    pub fn clbits(&self) -> &[Clbit] {
+        &self.clbits
+    }
+
+    pub fn slice(&self, range: Range<usize>) -> ClassicalRegister {
+        let name = format!("{}[{}..{}]", self.name, range.start, range.end);
+        let indices = self.clbits[range]
+            .iter()
+            .map(|clbit| clbit.index)
+            .collect::<Vec<_>>();
+        ClassicalRegister::new(&name, &indices)
+    }
+
+    pub fn concat(&self, other: &ClassicalRegister) -> ClassicalRegister {
+        let name = format!("{}+{}", self.name, other.name);
+        let indices = self
+            .clbits
+            .iter()
+            .chain(other.clbits.iter())
+            .map(|clbit| clbit.index)
+            .collect::<Vec<_>>();
+        ClassicalRegister::new(&name, &indices)
+    }
+}
+
+impl Index<usize> for ClassicalRegister {
+    type Output = Clbit;
+
+    fn index(&self, i: usize) -> &Clbit {
+        &self.clbits[i]
+    }
+}
+
+#[test]
+fn register_slice_test() {
+    let a = QuantumRegister::new("a", &[3, 4, 5, 6]);
+    let b = QuantumRegister::new("b", &[0, 1]);
+    assert_eq!(5, a[2].index);
+    let middle = a.slice(1..3);
+    assert_eq!("a[1..3]", middle.name());
+    assert_eq!(vec![4, 5], middle.indices());
+    let joined = b.concat(&middle);
+    assert_eq!("b+a[1..3]", joined.name());
+    assert_eq!(vec![0, 1, 4, 5], joined.indices());
+    assert_eq!(a[1], joined[2]);
+    // the qubits still belong to the registers they were created in
+    assert!(a.owns(joined[2]) && b.owns(joined[0]));
+    assert!(!middle.owns(joined[2]));
+    assert!(!a.owns(Qubit::new(4)));
+    assert_eq!(Qubit::new(4), a[1]);
+
+    let c = ClassicalRegister::new("c", &[0, 1, 2]);
+    let d = ClassicalRegister::new("d", &[3]);
+    assert_eq!(Clbit { index: 3 }, c.slice(1..3).concat(&d)[2]);
+}
+
+#[test]
+#[should_panic]
+fn overlapping_concat_test() {
+    let a = QuantumRegister::new("a", &[0, 1, 2]);
+    a.concat(&a.slice(0..1));
+}
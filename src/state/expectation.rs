@@ -0,0 +1,172 @@
+use crate::gate::base_gates::SingleGate;
+use crate::gate::gate::Gate;
+use crate::operator::pauli::{Pauli, PauliString, SparsePauliOp};
+use crate::state::state::State;
+use ndarray::prelude::Array2;
+use num::complex::Complex;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub standard_error: f64,
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ± {}", self.mean, self.standard_error)
+    }
+}
+
+// rotates every non-identity factor of `pauli` onto Z, so that the pauli string
+// becomes diagonal in the computational basis
+pub fn rotate_to_z_basis(state: &mut State, pauli: &PauliString) {
+    for (qubit, p) in pauli.paulis().iter().enumerate() {
+        match p {
+            Pauli::X => state.apply(&[&qubit], &SingleGate::H()),
+            Pauli::Y => {
+                state.apply(&[&qubit], &SingleGate::Sdg());
+                state.apply(&[&qubit], &SingleGate::H());
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn parity(index: usize, mask: usize) -> f64 {
+    if (index & mask).count_ones().is_multiple_of(2) {
+        1.
+    } else {
+        -1.
+    }
+}
+
+impl State {
+    pub fn inner(&self, other: &State) -> Complex<f64> {
+        assert_eq!(self.size(), other.size());
+        self.elements
+            .iter()
+            .zip(other.elements.iter())
+            .map(|(a, b)| a.conj() * b)
+            .sum()
+    }
+
+    pub fn expectation_pauli(&self, pauli: &PauliString) -> f64 {
+        assert!(pauli.size() <= self.size());
+        let pauli = pauli.expand(self.size());
+        let value: Complex<f64> = (0..self.elements.len())
+            .map(|j| {
+                let (phase, i) = pauli.apply_to_basis(j);
+                self.elements[i].conj() * phase * self.elements[j]
+            })
+            .sum();
+        value.re
+    }
+
+    // the operator is assumed to be hermitian, so only the real part is returned
+    pub fn expectation(&self, op: &SparsePauliOp) -> f64 {
+        op.terms()
+            .iter()
+            .map(|(c, p)| (c * self.expectation_pauli(p)).re)
+            .sum()
+    }
+
+    // `qubits[0]` corresponds to the most significant bit of the matrix index,
+    // as in `State::apply`
+    pub fn expectation_matrix(&self, qubits: &[usize], matrix: &Array2<Complex<f64>>) -> f64 {
+        assert_eq!(matrix.shape(), &[1 << qubits.len(), 1 << qubits.len()]);
+        let mut applied = self.clone();
+        let gate = Gate {
+            size: qubits.len(),
+            matrix: matrix.clone(),
+        };
+        applied.apply(&qubits.iter().collect::<Vec<_>>(), &gate);
+        self.inner(&applied).re
+    }
+
+    // estimates <O> by rotating each pauli term into the Z basis and sampling it with
+    // `shots` shots; the identity term is added exactly
+    pub fn estimate_expectation(&self, op: &SparsePauliOp, shots: usize) -> Estimate {
+        assert!(shots > 1);
+        let mut mean = 0.;
+        let mut variance = 0.;
+        for (coefficient, pauli) in op.terms().iter() {
+            if pauli.is_identity() {
+                mean += coefficient.re;
+                continue;
+            }
+            let mut rotated = self.clone();
+            rotate_to_z_basis(&mut rotated, pauli);
+            let mask = pauli.z_mask() | pauli.x_mask();
+            let counts = rotated.sample(shots);
+            let term_mean = counts
+                .iter()
+                .map(|(&index, &count)| parity(index, mask) * count as f64)
+                .sum::<f64>()
+                / shots as f64;
+            let term_variance = (1. - term_mean * term_mean) * shots as f64 / (shots - 1) as f64;
+            mean += coefficient.re * term_mean;
+            variance += coefficient.re * coefficient.re * term_variance / shots as f64;
+        }
+        Estimate {
+            mean,
+            standard_error: variance.sqrt(),
+        }
+    }
+}
+
+#[test]
+fn expectation_pauli_test() {
+    let mut state = State::new(2);
+    state.apply(&[&0], &SingleGate::H());
+    let op: SparsePauliOp = "X0 + 0.5*Z0 - 2*Z1 + 0.25*X0 Z1".parse().unwrap();
+    assert!((state.expectation(&op) - (1. - 2. + 0.25)).abs() < 1e-10);
+
+    let mut state = State::new(1);
+    state.apply(&[&0], &SingleGate::H());
+    state.apply(&[&0], &SingleGate::S());
+    let y: SparsePauliOp = "Y0".parse().unwrap();
+    assert!((state.expectation(&y) - 1.).abs() < 1e-10);
+}
+
+#[test]
+fn expectation_matrix_test() {
+    let mut state = State::new(3);
+    state.apply(&[&0], &SingleGate::RY(0.3));
+    state.apply(&[&2], &SingleGate::RX(1.1));
+    let op: SparsePauliOp = "0.7*Z0 X2 + 0.2*Y2 - Z0".parse().unwrap();
+    let matrix = op.to_matrix();
+    let dense: Complex<f64> = {
+        let psi = &state.elements;
+        let o_psi = matrix.dot(psi);
+        psi.iter()
+            .zip(o_psi.iter())
+            .map(|(a, b)| a.conj() * b)
+            .sum()
+    };
+    assert!((state.expectation(&op) - dense.re).abs() < 1e-10);
+
+    // the dense matrix of `op` acts on qubits (2, 1, 0) in State::apply order
+    let reduced: SparsePauliOp = "0.7*Z0 X1 + 0.2*Y1 - Z0".parse().unwrap();
+    let value = state.expectation_matrix(&[2, 0], &reduced.to_matrix());
+    assert!((value - dense.re).abs() < 1e-10);
+}
+
+#[test]
+fn estimate_expectation_test() {
+    let mut state = State::new(2);
+    state.apply(&[&0], &SingleGate::RY(0.9));
+    state.apply(&[&1], &SingleGate::H());
+    state.apply(&[&1], &SingleGate::S());
+    let op: SparsePauliOp = "1.5 + Z0 + 0.5*X0 - 0.8*Y1 + 0.3*X0 Y1".parse().unwrap();
+    let exact = state.expectation(&op);
+    let estimate = state.estimate_expectation(&op, 20000);
+    assert!(estimate.standard_error > 0.);
+    assert!((estimate.mean - exact).abs() < 6. * estimate.standard_error);
+
+    // eigenstates give exact estimates
+    let z: SparsePauliOp = "Z0 Z1".parse().unwrap();
+    let estimate = State::new(2).estimate_expectation(&z, 100);
+    assert_eq!(1., estimate.mean);
+    assert_eq!(0., estimate.standard_error);
+}
@@ -1 +1,3 @@
+pub mod expectation;
+#[allow(clippy::module_inception)]
 pub mod state;
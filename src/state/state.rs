@@ -1,11 +1,12 @@
 use crate::gate::gate::Gate;
 // use crate::algebra::complex::Complex;
-use crate::algebra::matrix::{index_vec, mask_vec};
-use ndarray::{array, prelude::Array1};
+use crate::algebra::matrix::{base_index, index_vec, mask_vec};
+use ndarray::prelude::Array1;
 use num::complex::Complex;
+use std::collections::HashMap;
 use std::fmt;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct State {
     size: usize,
     pub elements: Array1<Complex<f64>>,
@@ -21,19 +22,94 @@ impl State {
         }
     }
 
+    // amplitude k belongs to the basis state whose bit q is the value of qubit q
+    pub fn from_amplitudes(amplitudes: &[Complex<f64>]) -> State {
+        assert!(
+            amplitudes.len().is_power_of_two(),
+            "the number of amplitudes should be a power of two"
+        );
+        let norm = amplitudes.iter().map(|a| a.norm_sqr()).sum::<f64>();
+        assert!(
+            (norm - 1.).abs() < 1e-10,
+            "the amplitudes should be normalized"
+        );
+        State {
+            size: amplitudes.len().trailing_zeros() as usize,
+            elements: Array1::from_vec(amplitudes.to_vec()),
+        }
+    }
+
+    // the basis state written with the highest qubit first, "011" sets qubits 0 and 1
+    pub fn from_bitstring(bits: &str) -> State {
+        let size = bits.len();
+        let index = bits.chars().fold(0, |acc, c| match c {
+            '0' => acc << 1,
+            '1' => acc << 1 | 1,
+            _ => panic!("invalid bit: {}", c),
+        });
+        let mut state = State::new(size);
+        state.elements[0] = Complex::new(0., 0.);
+        state.elements[index] = Complex::new(1., 0.);
+        state
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    // appends qubits in |0> above the existing ones, which keeps every amplitude index
+    pub fn add_qubits(&mut self, num_qubits: usize) {
+        let mut elements = self.elements.to_vec();
+        elements.resize(1 << (self.size + num_qubits), Complex::new(0., 0.));
+        self.elements = Array1::from_vec(elements);
+        self.size += num_qubits;
+    }
+
+    pub fn probabilities(&self) -> Vec<f64> {
+        self.elements.iter().map(|e| e.norm_sqr()).collect()
+    }
+
+    // samples basis states without collapsing the state, keyed by basis index
+    pub fn sample(&self, shots: usize) -> HashMap<usize, usize> {
+        let mut cumulative = self.probabilities();
+        for i in 1..cumulative.len() {
+            cumulative[i] += cumulative[i - 1];
+        }
+        let total = cumulative[cumulative.len() - 1];
+        let mut counts = HashMap::new();
+        for _ in 0..shots {
+            let r = rand::random::<f64>() * total;
+            let index = cumulative
+                .partition_point(|&c| c <= r)
+                .min(cumulative.len() - 1);
+            *counts.entry(index).or_insert(0) += 1;
+        }
+        counts
+    }
+
     pub fn apply(&mut self, qubits: &[&usize], gate: &Gate) {
         let dim = qubits.len();
 
         let masks = mask_vec(qubits);
-        for i in 0..(self.elements.len() >> dim) {
-            let indices = index_vec(i, qubits, &masks, dim);
-            let old_values = indices
-                .iter()
-                .map(|&i| self.elements[i])
-                .collect::<Vec<_>>();
-            let new_values = gate.matrix().dot(&Array1::from_vec(old_values));
-            for (&i, nv) in indices.iter().zip(new_values.to_vec()) {
-                self.elements[i] = nv;
+        // the offsets of the amplitudes a gate mixes from the state with the qubits at 0
+        let offsets = index_vec(0, qubits, &masks, dim);
+        let matrix = gate.matrix().iter().copied().collect::<Vec<_>>();
+        let elements = self
+            .elements
+            .as_slice_mut()
+            .expect("the amplitudes should be contiguous");
+        let mut old_values = vec![Complex::new(0., 0.); offsets.len()];
+        for i in 0..(elements.len() >> dim) {
+            let base = base_index(i, &masks, dim);
+            for k in 0..offsets.len() {
+                old_values[k] = elements[base | offsets[k]];
+            }
+            for (row, offset) in offsets.iter().enumerate() {
+                let mut value = Complex::new(0., 0.);
+                for column in 0..offsets.len() {
+                    value += matrix[row * offsets.len() + column] * old_values[column];
+                }
+                elements[base | offset] = value;
             }
         }
     }
@@ -60,3 +136,64 @@ impl fmt::Display for State {
 
 //     assert_eq!(correct_elements, state.elements);
 // }
+
+#[test]
+fn apply_on_part_of_state_test() {
+    use crate::gate::base_gates::{DoubleGate, SingleGate};
+
+    // |q2 q1 q0> = |100> stays untouched by CNOT(0, 1)
+    let mut state = State::new(3);
+    state.apply(&[&2], &SingleGate::X());
+    state.apply(&[&0, &1], &DoubleGate::CNOT());
+    assert_eq!(Complex::new(1., 0.), state.elements[4]);
+
+    // |101> -> |111>
+    state.apply(&[&0], &SingleGate::X());
+    state.apply(&[&0, &1], &DoubleGate::CNOT());
+    assert_eq!(Complex::new(1., 0.), state.elements[7]);
+}
+
+#[test]
+fn apply_matches_full_matrix_test() {
+    use crate::gate::base_gates::DoubleGate;
+
+    // a gate on qubits 2 and 0 acts as G[(i2 i0), (j2 j0)] when i1 = j1
+    let gate = DoubleGate::CRY(0.9);
+    let mut state = State::new(3);
+    for k in 0..8 {
+        state.elements[k] = Complex::new(k as f64 + 1., 0.5 - k as f64);
+    }
+    let old = state.elements.clone();
+    state.apply(&[&2, &0], &gate);
+    let local = |i: usize| (i >> 2 & 1) << 1 | (i & 1);
+    for i in 0..8 {
+        let expected = (0..8)
+            .filter(|j| (i ^ j) & 0b010 == 0)
+            .map(|j| gate.matrix()[[local(i), local(j)]] * old[j])
+            .sum::<Complex<f64>>();
+        assert!((state.elements[i] - expected).norm() < 1e-12);
+    }
+}
+
+#[test]
+fn state_constructors_test() {
+    let state = State::from_bitstring("0110");
+    assert_eq!(4, state.size());
+    assert_eq!(Complex::new(1., 0.), state.elements[6]);
+    assert_eq!(1., state.probabilities().iter().sum::<f64>());
+    // the first character is the highest qubit
+    let state = State::from_bitstring("001");
+    assert_eq!(Complex::new(1., 0.), state.elements[1]);
+
+    let half = Complex::new(0.5, 0.);
+    let amplitudes = [half, Complex::new(0., 0.5), -half, half];
+    let state = State::from_amplitudes(&amplitudes);
+    assert_eq!(2, state.size());
+    assert_eq!(amplitudes.to_vec(), state.elements.to_vec());
+}
+
+#[test]
+#[should_panic]
+fn unnormalized_amplitudes_test() {
+    State::from_amplitudes(&[Complex::new(1., 0.), Complex::new(1., 0.)]);
+}
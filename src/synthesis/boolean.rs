@@ -0,0 +1,210 @@
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Constant(bool),
+    Variable(usize),
+    Not(Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Xor(Box<Node>, Box<Node>),
+}
+
+impl Node {
+    fn evaluate(&self, input: usize) -> bool {
+        match self {
+            Node::Constant(value) => *value,
+            Node::Variable(i) => input >> i & 1 == 1,
+            Node::Not(node) => !node.evaluate(input),
+            Node::And(lhs, rhs) => lhs.evaluate(input) && rhs.evaluate(input),
+            Node::Or(lhs, rhs) => lhs.evaluate(input) || rhs.evaluate(input),
+            Node::Xor(lhs, rhs) => lhs.evaluate(input) ^ rhs.evaluate(input),
+        }
+    }
+}
+
+// a boolean function of named variables, variable i being bit i of the input in the
+// order the variables first appear
+#[derive(Debug, Clone, PartialEq)]
+pub struct BooleanExpression {
+    variables: Vec<String>,
+    root: Node,
+}
+
+impl BooleanExpression {
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    pub fn num_variables(&self) -> usize {
+        self.variables.len()
+    }
+
+    pub fn evaluate(&self, input: usize) -> bool {
+        self.root.evaluate(input)
+    }
+
+    // the values on the inputs 0..2^n
+    pub fn truth_table(&self) -> Vec<bool> {
+        (0..1 << self.num_variables())
+            .map(|input| self.evaluate(input))
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Identifier(String),
+    Constant(bool),
+    Not,
+    And,
+    Or,
+    Xor,
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut pos = 0;
+    while pos < chars.len() {
+        let c = chars[pos];
+        pos += 1;
+        match c {
+            _ if c.is_whitespace() => {}
+            '!' | '~' => tokens.push(Token::Not),
+            '&' => tokens.push(Token::And),
+            '|' => tokens.push(Token::Or),
+            '^' => tokens.push(Token::Xor),
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '0' => tokens.push(Token::Constant(false)),
+            '1' => tokens.push(Token::Constant(true)),
+            _ if c.is_ascii_alphabetic() || c == '_' => {
+                let start = pos - 1;
+                while pos < chars.len() && (chars[pos].is_ascii_alphanumeric() || chars[pos] == '_')
+                {
+                    pos += 1;
+                }
+                tokens.push(Token::Identifier(chars[start..pos].iter().collect()));
+            }
+            _ => return Err(format!("unexpected character '{}'", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+// recursive descent with ! binding tightest, then &, ^ and |
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    variables: Vec<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn binary(
+        &mut self,
+        operator: Token,
+        operand: fn(&mut Parser) -> Result<Node, String>,
+        combine: fn(Box<Node>, Box<Node>) -> Node,
+    ) -> Result<Node, String> {
+        let mut node = operand(self)?;
+        while self.peek() == Some(&operator) {
+            self.pos += 1;
+            node = combine(Box::new(node), Box::new(operand(self)?));
+        }
+        Ok(node)
+    }
+
+    fn or(&mut self) -> Result<Node, String> {
+        self.binary(Token::Or, Parser::xor, Node::Or)
+    }
+
+    fn xor(&mut self) -> Result<Node, String> {
+        self.binary(Token::Xor, Parser::and, Node::Xor)
+    }
+
+    fn and(&mut self) -> Result<Node, String> {
+        self.binary(Token::And, Parser::unary, Node::And)
+    }
+
+    fn unary(&mut self) -> Result<Node, String> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| "unexpected end of expression".to_string())?;
+        self.pos += 1;
+        match token {
+            Token::Not => Ok(Node::Not(Box::new(self.unary()?))),
+            Token::Constant(value) => Ok(Node::Constant(value)),
+            Token::Identifier(name) => {
+                let index = match self.variables.iter().position(|v| *v == name) {
+                    Some(index) => index,
+                    None => {
+                        self.variables.push(name);
+                        self.variables.len() - 1
+                    }
+                };
+                Ok(Node::Variable(index))
+            }
+            Token::Open => {
+                let node = self.or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err("expected ')'".to_string());
+                }
+                self.pos += 1;
+                Ok(node)
+            }
+            _ => Err(format!("unexpected {:?}", token)),
+        }
+    }
+}
+
+impl FromStr for BooleanExpression {
+    type Err = String;
+
+    // parses expressions like "(a & b) ^ !c | 1" with ! or ~ for the negation
+    fn from_str(text: &str) -> Result<BooleanExpression, String> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            pos: 0,
+            variables: vec![],
+        };
+        let root = parser.or()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("unexpected {:?}", token));
+        }
+        Ok(BooleanExpression {
+            variables: parser.variables,
+            root,
+        })
+    }
+}
+
+#[test]
+fn boolean_expression_test() {
+    let expression = "(a & b) ^ !c".parse::<BooleanExpression>().unwrap();
+    assert_eq!(vec!["a", "b", "c"], expression.variables());
+    for input in 0..8 {
+        let (a, b, c) = (input & 1 == 1, input >> 1 & 1 == 1, input >> 2 & 1 == 1);
+        assert_eq!((a && b) ^ !c, expression.evaluate(input));
+    }
+    // ! before &, & before ^ and ^ before |
+    let expression = "x | ~y & z ^ x_1 & 1".parse::<BooleanExpression>().unwrap();
+    assert_eq!(vec!["x", "y", "z", "x_1"], expression.variables());
+    let table = expression.truth_table();
+    for (input, &value) in table.iter().enumerate() {
+        let bit = |i: usize| input >> i & 1 == 1;
+        assert_eq!(bit(0) || ((!bit(1) && bit(2)) ^ bit(3)), value);
+    }
+
+    assert!("a & ".parse::<BooleanExpression>().is_err());
+    assert!("(a | b".parse::<BooleanExpression>().is_err());
+    assert!("a b".parse::<BooleanExpression>().is_err());
+    assert!("a + b".parse::<BooleanExpression>().is_err());
+}
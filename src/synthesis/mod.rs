@@ -0,0 +1,3 @@
+pub mod boolean;
+pub mod reversible;
+pub mod state_preparation;
@@ -0,0 +1,510 @@
+use crate::algorithm::grover::Oracle;
+#[cfg(test)]
+use crate::algorithm::grover::PhaseOracle;
+use crate::circuit::circuit::QuantumCircuit;
+use crate::synthesis::boolean::BooleanExpression;
+use std::f64::consts::PI;
+
+// a product of literals: the variables in ones should be 1, those in zeros 0 and the
+// others are free
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cube {
+    pub ones: usize,
+    pub zeros: usize,
+}
+
+impl Cube {
+    pub fn evaluate(&self, input: usize) -> bool {
+        input & self.ones == self.ones && input & self.zeros == 0
+    }
+
+    pub fn variables(&self) -> usize {
+        self.ones | self.zeros
+    }
+
+    pub fn num_literals(&self) -> usize {
+        self.variables().count_ones() as usize
+    }
+
+    // the single cube equal to the exclusive or of two cubes that differ in one
+    // variable: of the states 1, 0 and free of that variable, it takes the one neither
+    // has. None when they differ elsewhere
+    fn link(&self, other: &Cube) -> Option<Cube> {
+        let difference = (self.ones ^ other.ones) | (self.zeros ^ other.zeros);
+        if difference.count_ones() != 1 {
+            return None;
+        }
+        let common = Cube {
+            ones: self.ones & !difference,
+            zeros: self.zeros & !difference,
+        };
+        let ones = (self.ones | other.ones) & difference != 0;
+        let zeros = (self.zeros | other.zeros) & difference != 0;
+        Some(match (ones, zeros) {
+            (true, true) => common,
+            (true, false) => Cube {
+                zeros: common.zeros | difference,
+                ..common
+            },
+            _ => Cube {
+                ones: common.ones | difference,
+                ..common
+            },
+        })
+    }
+}
+
+// an exclusive sum of products
+#[derive(Debug, Clone, PartialEq)]
+pub struct Esop {
+    num_variables: usize,
+    cubes: Vec<Cube>,
+}
+
+impl Esop {
+    pub fn new(num_variables: usize, cubes: &[Cube]) -> Esop {
+        assert!(cubes
+            .iter()
+            .all(|cube| cube.variables() < 1 << num_variables && cube.ones & cube.zeros == 0));
+        Esop {
+            num_variables,
+            cubes: cubes.to_vec(),
+        }
+    }
+
+    // the cheapest of the optimized positive polarity reed-muller form, from the
+    // mobius transform of the table, and the optimized sum of minterms, for the function
+    // and for 1 ^ its complement
+    pub fn from_truth_table(table: &[bool]) -> Esop {
+        assert!(table.len().is_power_of_two());
+        let complement = table.iter().map(|value| !value).collect::<Vec<_>>();
+        let one = Cube { ones: 0, zeros: 0 };
+        let mut candidates = vec![];
+        for (table, constant) in [(table, vec![]), (&complement[..], vec![one])] {
+            for mut cubes in [reed_muller(table), minterms(table)] {
+                cubes.extend_from_slice(&constant);
+                let n = table.len().trailing_zeros() as usize;
+                candidates.push(Esop::new(n, &cubes).optimize());
+            }
+        }
+        candidates
+            .into_iter()
+            .min_by_key(|esop| esop.cost())
+            .unwrap()
+    }
+
+    pub fn from_expression(expression: &BooleanExpression) -> Esop {
+        Esop::from_truth_table(&expression.truth_table())
+    }
+
+    pub fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    pub fn cubes(&self) -> &[Cube] {
+        &self.cubes
+    }
+
+    pub fn evaluate(&self, input: usize) -> bool {
+        self.cubes
+            .iter()
+            .fold(false, |value, cube| value ^ cube.evaluate(input))
+    }
+
+    pub fn truth_table(&self) -> Vec<bool> {
+        (0..1 << self.num_variables)
+            .map(|input| self.evaluate(input))
+            .collect()
+    }
+
+    // the number of cubes, then of literals, which are the gates and their controls
+    pub fn cost(&self) -> (usize, usize) {
+        let literals = self.cubes.iter().map(|cube| cube.num_literals()).sum();
+        (self.cubes.len(), literals)
+    }
+
+    // cancels equal cubes and links cubes that differ in one variable until no pair is
+    // left, each step removing a cube
+    pub fn optimize(&self) -> Esop {
+        let mut cubes = self.cubes.clone();
+        'search: loop {
+            for i in 0..cubes.len() {
+                for j in i + 1..cubes.len() {
+                    if cubes[i] == cubes[j] {
+                        cubes.remove(j);
+                        cubes.remove(i);
+                        continue 'search;
+                    }
+                    if let Some(linked) = cubes[i].link(&cubes[j]) {
+                        cubes.remove(j);
+                        cubes[i] = linked;
+                        continue 'search;
+                    }
+                }
+            }
+            break;
+        }
+        cubes.sort_by_key(|cube| (cube.zeros, cube.ones));
+        Esop {
+            num_variables: self.num_variables,
+            cubes,
+        }
+    }
+
+    // target ^= f(x) on lines 0..n for x and line n for the target
+    pub fn to_circuit(&self) -> ReversibleCircuit {
+        let mut circuit = ReversibleCircuit::new(self.num_variables + 1);
+        circuit.add_esop(self, self.num_variables);
+        circuit.optimize();
+        circuit
+    }
+
+    // |x> -> (-1)^f(x)|x> with qubits[i] holding variable i: one multi-controlled Z
+    // per cube, with the negative literals flipped as in the reversible circuit
+    pub fn apply_phase(&self, qc: &mut QuantumCircuit, qubits: &[usize]) {
+        assert_eq!(self.num_variables, qubits.len());
+        let mut flipped = 0;
+        for cube in self.cubes.iter() {
+            flipped = flip_to(qc, qubits, flipped, cube);
+            let controls = bits(cube.variables())
+                .map(|v| qubits[v])
+                .collect::<Vec<_>>();
+            if controls.is_empty() {
+                qc.global_phase(PI);
+            } else {
+                qc.MCZ(&controls);
+            }
+        }
+        flip_to(qc, qubits, flipped, &Cube { ones: 0, zeros: 0 });
+    }
+}
+
+// the phase oracle of the function
+impl Oracle for Esop {
+    fn num_qubits(&self) -> usize {
+        self.num_variables
+    }
+
+    fn is_good(&self, item: usize) -> bool {
+        self.evaluate(item)
+    }
+
+    fn apply(&self, qc: &mut QuantumCircuit, qubits: &[usize]) {
+        self.apply_phase(qc, qubits);
+    }
+}
+
+// the cubes of the monomials with coefficient 1 in the algebraic normal form
+fn reed_muller(table: &[bool]) -> Vec<Cube> {
+    let mut coefficients = table.to_vec();
+    for i in 0..table.len().trailing_zeros() {
+        for x in 0..table.len() {
+            if x >> i & 1 == 1 {
+                coefficients[x] ^= coefficients[x ^ 1 << i];
+            }
+        }
+    }
+    (0..table.len())
+        .filter(|&x| coefficients[x])
+        .map(|x| Cube { ones: x, zeros: 0 })
+        .collect()
+}
+
+fn minterms(table: &[bool]) -> Vec<Cube> {
+    (0..table.len())
+        .filter(|&x| table[x])
+        .map(|x| Cube {
+            ones: x,
+            zeros: !x & (table.len() - 1),
+        })
+        .collect()
+}
+
+fn bits(mask: usize) -> impl Iterator<Item = usize> {
+    (0..usize::BITS as usize).filter(move |i| mask >> i & 1 == 1)
+}
+
+// the variables to flip so that exactly the negative literals of the cube are flipped
+// among its variables, the others staying as they are. the empty cube restores all
+fn toggles(flipped: usize, cube: &Cube) -> usize {
+    if cube.variables() == 0 {
+        flipped
+    } else {
+        (flipped ^ cube.zeros) & cube.variables()
+    }
+}
+
+fn flip_to(qc: &mut QuantumCircuit, qubits: &[usize], flipped: usize, cube: &Cube) -> usize {
+    let toggled = toggles(flipped, cube);
+    for v in bits(toggled) {
+        qc.X(qubits[v]);
+    }
+    flipped ^ toggled
+}
+
+// a gate on the lines of a reversible circuit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReversibleGate {
+    X(usize),
+    // X on the target when every control is 1
+    MCX(Vec<usize>, usize),
+}
+
+impl ReversibleGate {
+    fn lines(&self) -> Vec<usize> {
+        match self {
+            ReversibleGate::X(target) => vec![*target],
+            ReversibleGate::MCX(controls, target) => {
+                let mut lines = controls.clone();
+                lines.push(*target);
+                lines
+            }
+        }
+    }
+
+    // whether the gates commute for certain: disjoint lines, or the same target and
+    // neither controlled by the other's target
+    fn commutes(&self, other: &ReversibleGate) -> bool {
+        let target = |gate: &ReversibleGate| match gate {
+            ReversibleGate::X(target) | ReversibleGate::MCX(_, target) => *target,
+        };
+        let (lines, other_lines) = (self.lines(), other.lines());
+        lines.iter().all(|line| !other_lines.contains(line))
+            || (target(self) == target(other)
+                && !lines[..lines.len() - 1].contains(&target(other))
+                && !other_lines[..other_lines.len() - 1].contains(&target(self)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReversibleCircuit {
+    num_lines: usize,
+    gates: Vec<ReversibleGate>,
+}
+
+impl ReversibleCircuit {
+    pub fn new(num_lines: usize) -> ReversibleCircuit {
+        ReversibleCircuit {
+            num_lines,
+            gates: vec![],
+        }
+    }
+
+    // y ^= f(x) for every output bit of the table, x on lines 0..n and output bit j on
+    // line n + j
+    pub fn from_truth_table(table: &[usize], num_outputs: usize) -> ReversibleCircuit {
+        assert!(table.len().is_power_of_two());
+        let n = table.len().trailing_zeros() as usize;
+        let mut circuit = ReversibleCircuit::new(n + num_outputs);
+        for j in 0..num_outputs {
+            let bit = table.iter().map(|y| y >> j & 1 == 1).collect::<Vec<_>>();
+            circuit.add_esop(&Esop::from_truth_table(&bit), n + j);
+        }
+        circuit.optimize();
+        circuit
+    }
+
+    pub fn from_expression(expression: &BooleanExpression) -> ReversibleCircuit {
+        Esop::from_expression(expression).to_circuit()
+    }
+
+    pub fn num_lines(&self) -> usize {
+        self.num_lines
+    }
+
+    pub fn gates(&self) -> &[ReversibleGate] {
+        &self.gates
+    }
+
+    pub fn push(&mut self, gate: ReversibleGate) {
+        assert!(gate.lines().iter().all(|&line| line < self.num_lines));
+        self.gates.push(gate);
+    }
+
+    // the cubes on the variable lines 0..n onto the target, flipping the negative
+    // literals only when the previous cube left them otherwise
+    fn add_esop(&mut self, esop: &Esop, target: usize) {
+        let mut flipped = 0;
+        let mut flip_to = |circuit: &mut ReversibleCircuit, cube: &Cube| {
+            let toggled = toggles(flipped, cube);
+            for v in bits(toggled) {
+                circuit.push(ReversibleGate::X(v));
+            }
+            flipped ^= toggled;
+        };
+        for cube in esop.cubes().iter() {
+            flip_to(self, cube);
+            let controls = bits(cube.variables()).collect::<Vec<_>>();
+            if controls.is_empty() {
+                self.push(ReversibleGate::X(target));
+            } else {
+                self.push(ReversibleGate::MCX(controls, target));
+            }
+        }
+        flip_to(self, &Cube { ones: 0, zeros: 0 });
+    }
+
+    // removes pairs of equal gates that meet after moving one back past the gates it
+    // commutes with
+    pub fn optimize(&mut self) {
+        let mut gates: Vec<ReversibleGate> = vec![];
+        for gate in self.gates.drain(..) {
+            let partner = gates
+                .iter()
+                .rposition(|previous| *previous == gate || !previous.commutes(&gate));
+            match partner {
+                Some(k) if gates[k] == gate => {
+                    gates.remove(k);
+                }
+                _ => gates.push(gate),
+            }
+        }
+        self.gates = gates;
+    }
+
+    // the number of X and multi-controlled X gates
+    pub fn num_gates(&self) -> usize {
+        self.gates.len()
+    }
+
+    // the output on the classical input, line i being bit i
+    pub fn simulate(&self, input: usize) -> usize {
+        self.gates.iter().fold(input, |bits, gate| match gate {
+            ReversibleGate::X(target) => bits ^ 1 << target,
+            ReversibleGate::MCX(controls, target) => {
+                let mask = controls.iter().fold(0, |mask, c| mask | 1 << c);
+                if bits & mask == mask {
+                    bits ^ 1 << target
+                } else {
+                    bits
+                }
+            }
+        })
+    }
+
+    // runs the circuit with line i on qubits[i]
+    pub fn apply(&self, qc: &mut QuantumCircuit, qubits: &[usize]) {
+        assert_eq!(self.num_lines, qubits.len());
+        for gate in self.gates.iter() {
+            match gate {
+                ReversibleGate::X(target) => qc.X(qubits[*target]),
+                ReversibleGate::MCX(controls, target) => {
+                    let controls = controls.iter().map(|&c| qubits[c]).collect::<Vec<_>>();
+                    qc.MCX(&controls, qubits[*target]);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn esop_test() {
+    // every function of three variables
+    for f in 0..256usize {
+        let table = (0..8).map(|x| f >> x & 1 == 1).collect::<Vec<_>>();
+        let esop = Esop::from_truth_table(&table);
+        assert_eq!(table, esop.truth_table());
+        let circuit = esop.to_circuit();
+        for (x, &value) in table.iter().enumerate() {
+            for y in 0..2 {
+                let input = x | y << 3;
+                assert_eq!(input ^ usize::from(value) << 3, circuit.simulate(input));
+            }
+        }
+    }
+    // a | b | c = 1 ^ !a !b !c
+    let esop = Esop::from_truth_table(&[false, true, true, true, true, true, true, true]);
+    assert_eq!((2, 3), esop.cost());
+    // the links of a cube with a positive, a negative and a free literal
+    let (a, b) = (Cube { ones: 1, zeros: 0 }, Cube { ones: 0, zeros: 0 });
+    assert_eq!(Some(Cube { ones: 0, zeros: 1 }), a.link(&b));
+    assert_eq!(Some(b), a.link(&Cube { ones: 0, zeros: 1 }));
+    assert_eq!(None, a.link(&Cube { ones: 2, zeros: 1 }));
+}
+
+#[test]
+fn truth_table_synthesis_test() {
+    // y ^= 3x + 1 mod 4 for a 4-bit x
+    let table = (0..16).map(|x: usize| (x * 3 + 1) % 4).collect::<Vec<_>>();
+    let circuit = ReversibleCircuit::from_truth_table(&table, 2);
+    assert_eq!(6, circuit.num_lines());
+    for (x, &value) in table.iter().enumerate() {
+        for y in 0..4 {
+            let input = x | y << 4;
+            assert_eq!(x | (y ^ value) << 4, circuit.simulate(input));
+        }
+    }
+    // the same on the state vector, on scattered qubits
+    let qubits = [5, 0, 3, 1, 4, 2];
+    for x in [0, 7, 10, 15] {
+        let mut qc = QuantumCircuit::new(6);
+        for (line, &q) in qubits.iter().enumerate().take(4) {
+            if x >> line & 1 == 1 {
+                qc.X(q);
+            }
+        }
+        circuit.apply(&mut qc, &qubits);
+        let output = circuit.simulate(x);
+        let index = (0..6)
+            .filter(|&line| output >> line & 1 == 1)
+            .fold(0, |index, line| index | 1 << qubits[line]);
+        assert!((qc.state.elements[index].norm() - 1.).abs() < 1e-10);
+    }
+}
+
+#[test]
+fn optimize_test() {
+    let mut circuit = ReversibleCircuit::new(3);
+    circuit.push(ReversibleGate::X(0));
+    circuit.push(ReversibleGate::MCX(vec![1], 2));
+    circuit.push(ReversibleGate::X(0));
+    circuit.push(ReversibleGate::MCX(vec![0], 2));
+    circuit.push(ReversibleGate::MCX(vec![1], 2));
+    circuit.push(ReversibleGate::X(1));
+    circuit.push(ReversibleGate::MCX(vec![1], 2));
+    let before = (0..8).map(|x| circuit.simulate(x)).collect::<Vec<_>>();
+    circuit.optimize();
+    // the X on 0 pair and the CNOT from 1 pair cancel, the last one does not
+    assert_eq!(
+        vec![
+            ReversibleGate::MCX(vec![0], 2),
+            ReversibleGate::X(1),
+            ReversibleGate::MCX(vec![1], 2)
+        ],
+        circuit.gates()
+    );
+    assert_eq!(
+        before,
+        (0..8).map(|x| circuit.simulate(x)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn expression_synthesis_test() {
+    let expression = "(a & b) ^ !c".parse::<BooleanExpression>().unwrap();
+    let circuit = ReversibleCircuit::from_expression(&expression);
+    assert_eq!(4, circuit.num_lines());
+    for x in 0..8 {
+        assert_eq!(
+            x | usize::from(expression.evaluate(x)) << 3,
+            circuit.simulate(x)
+        );
+    }
+    // !c ^ ab as two cubes, a toffoli and a negated cnot
+    assert_eq!((2, 3), Esop::from_expression(&expression).cost());
+
+    // the phase oracle flips the same items as one built from the predicate
+    let esop = Esop::from_expression(&"a & !b | c & d".parse().unwrap());
+    let reference = PhaseOracle::from_predicate(4, |x| esop.evaluate(x));
+    let mut qc = QuantumCircuit::new(4);
+    let mut expected = QuantumCircuit::new(4);
+    for q in 0..4 {
+        qc.H(q);
+        expected.H(q);
+    }
+    Oracle::apply(&esop, &mut qc, &[0, 1, 2, 3]);
+    reference.apply(&mut expected, &[0, 1, 2, 3]);
+    for (a, b) in qc.state.elements.iter().zip(expected.state.elements.iter()) {
+        assert!((a - b).norm() < 1e-10);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::circuit::circuit::QuantumCircuit;
+#[cfg(test)]
+use crate::state::state::State;
+use num::complex::Complex;
+
+// a uniformly controlled rotation applies R(angles[j]) to the target when the controls
+// hold j, with controls[i] as bit i of j. it is decomposed into 2^m rotations and 2^m
+// CNOTs (Mottonen et al. 2004): before rotation i the CNOTs have flipped the target for
+// the controls selected by the gray code g_i, and X R(theta) X = R(-theta) for RY and RZ,
+// so angles[j] = sum_i (-1)^|j & g_i| theta_i which is inverted by the transpose
+fn uniformly_controlled_rotation(
+    qc: &mut QuantumCircuit,
+    rotation: fn(&mut QuantumCircuit, usize, f64),
+    controls: &[usize],
+    target: usize,
+    angles: &[f64],
+) {
+    let m = controls.len();
+    assert_eq!(1 << m, angles.len());
+    if angles.iter().all(|angle| angle.abs() < 1e-14) {
+        return;
+    }
+    if m == 0 {
+        rotation(qc, target, angles[0]);
+        return;
+    }
+    let scale = (1 << m) as f64;
+    for i in 0..1usize << m {
+        let gray = i ^ (i >> 1);
+        let theta = angles
+            .iter()
+            .enumerate()
+            .map(|(j, angle)| {
+                if (j & gray).count_ones().is_multiple_of(2) {
+                    *angle
+                } else {
+                    -angle
+                }
+            })
+            .sum::<f64>()
+            / scale;
+        rotation(qc, target, theta);
+        // the bit flipped between g_i and g_i+1, the last CNOT returns to g_0 = 0
+        let control = ((i + 1).trailing_zeros() as usize).min(m - 1);
+        qc.CNOT(controls[control], target);
+    }
+}
+
+pub fn uniformly_controlled_ry(
+    qc: &mut QuantumCircuit,
+    controls: &[usize],
+    target: usize,
+    angles: &[f64],
+) {
+    uniformly_controlled_rotation(qc, QuantumCircuit::RY, controls, target, angles);
+}
+
+pub fn uniformly_controlled_rz(
+    qc: &mut QuantumCircuit,
+    controls: &[usize],
+    target: usize,
+    angles: &[f64],
+) {
+    uniformly_controlled_rotation(qc, QuantumCircuit::RZ, controls, target, angles);
+}
+
+// maps |0..0> on the qubits to sum_k amplitudes[k] |k>, with qubits[q] as bit q of k.
+// the magnitudes are set from the highest qubit down, each qubit rotated by RY
+// conditioned on the qubits above it. the phases form a diagonal which splits into
+// RZ(phi_2j+1 - phi_2j) on the lowest qubit conditioned on the others and a diagonal
+// of the mean phases on the remaining qubits, down to a global phase
+pub fn prepare_state(qc: &mut QuantumCircuit, qubits: &[usize], amplitudes: &[Complex<f64>]) {
+    let n = qubits.len();
+    assert_eq!(1 << n, amplitudes.len());
+    let norm = amplitudes.iter().map(|a| a.norm_sqr()).sum::<f64>();
+    assert!(
+        (norm - 1.).abs() < 1e-10,
+        "the amplitudes should be normalized"
+    );
+
+    // weights[k] are the probabilities of the values of the qubits k..n
+    let mut weights = vec![amplitudes.iter().map(|a| a.norm_sqr()).collect::<Vec<_>>()];
+    for k in 0..n {
+        let lower = &weights[k];
+        let upper = (0..lower.len() / 2)
+            .map(|j| lower[2 * j] + lower[2 * j + 1])
+            .collect();
+        weights.push(upper);
+    }
+    for k in (0..n).rev() {
+        let angles = weights[k]
+            .chunks(2)
+            .map(|pair| 2. * pair[1].sqrt().atan2(pair[0].sqrt()))
+            .collect::<Vec<_>>();
+        uniformly_controlled_ry(qc, &qubits[k + 1..], qubits[k], &angles);
+    }
+
+    let mut phases = amplitudes.iter().map(|a| a.arg()).collect::<Vec<_>>();
+    for k in 0..n {
+        let angles = phases
+            .chunks(2)
+            .map(|pair| pair[1] - pair[0])
+            .collect::<Vec<_>>();
+        uniformly_controlled_rz(qc, &qubits[k + 1..], qubits[k], &angles);
+        phases = phases
+            .chunks(2)
+            .map(|pair| (pair[0] + pair[1]) / 2.)
+            .collect();
+    }
+    if phases[0].abs() > 1e-14 {
+        qc.global_phase(phases[0]);
+    }
+}
+
+// a circuit on log2(len) qubits whose state is the normalized amplitude vector
+pub fn state_preparation(amplitudes: &[Complex<f64>]) -> QuantumCircuit {
+    assert!(
+        amplitudes.len().is_power_of_two(),
+        "the number of amplitudes should be a power of two"
+    );
+    let n = amplitudes.len().trailing_zeros() as usize;
+    let mut qc = QuantumCircuit::new(n);
+    prepare_state(&mut qc, &(0..n).collect::<Vec<_>>(), amplitudes);
+    qc
+}
+
+#[cfg(test)]
+fn random_amplitudes(dim: usize) -> Vec<Complex<f64>> {
+    let amplitudes = (0..dim)
+        .map(|_| Complex::new(rand::random::<f64>() - 0.5, rand::random::<f64>() - 0.5))
+        .collect::<Vec<_>>();
+    let norm = amplitudes.iter().map(|a| a.norm_sqr()).sum::<f64>().sqrt();
+    amplitudes.iter().map(|a| a / norm).collect()
+}
+
+#[test]
+fn state_preparation_test() {
+    for n in 1..=5 {
+        let amplitudes = random_amplitudes(1 << n);
+        let qc = state_preparation(&amplitudes);
+        assert!(qc
+            .state
+            .elements
+            .iter()
+            .zip(amplitudes.iter())
+            .all(|(a, b)| (a - b).norm() < 1e-10));
+    }
+
+    // sparse amplitudes with zero weight branches: (|000> - i|111>) / sqrt(2)
+    let mut ghz = vec![Complex::new(0., 0.); 8];
+    ghz[0] = Complex::new(std::f64::consts::FRAC_1_SQRT_2, 0.);
+    ghz[7] = Complex::new(0., -std::f64::consts::FRAC_1_SQRT_2);
+    let qc = state_preparation(&ghz);
+    assert!(qc
+        .state
+        .elements
+        .iter()
+        .zip(ghz.iter())
+        .all(|(a, b)| (a - b).norm() < 1e-10));
+
+    let basis = State::from_bitstring("001");
+    let qc = state_preparation(&basis.elements.to_vec());
+    assert!((qc.state.elements[1] - Complex::new(1., 0.)).norm() < 1e-10);
+}
+
+#[test]
+fn prepare_state_on_qubits_test() {
+    // qubits 3 and 1 of a circuit whose qubit 0 is already set
+    let amplitudes = random_amplitudes(4);
+    let mut qc = QuantumCircuit::new(4);
+    qc.X(0);
+    prepare_state(&mut qc, &[3, 1], &amplitudes);
+    for (k, amplitude) in amplitudes.iter().enumerate() {
+        let index = 1 | (k & 1) << 3 | (k >> 1) << 1;
+        assert!((qc.state.elements[index] - amplitude).norm() < 1e-10);
+    }
+}
+
+#[test]
+fn uniformly_controlled_rotation_test() {
+    // every control value rotates the target by its own angle
+    let angles = [0.3, -1.2, 2.5, 0.7];
+    for j in 0..4 {
+        let mut qc = QuantumCircuit::new(3);
+        for bit in 0..2 {
+            if j >> bit & 1 == 1 {
+                qc.X(bit);
+            }
+        }
+        uniformly_controlled_ry(&mut qc, &[0, 1], 2, &angles);
+        let (cos, sin) = ((angles[j] / 2.).cos(), (angles[j] / 2.).sin());
+        assert!((qc.state.elements[j].re - cos).abs() < 1e-12);
+        assert!((qc.state.elements[j | 4].re - sin).abs() < 1e-12);
+    }
+}
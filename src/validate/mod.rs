@@ -1 +1,2 @@
+#[allow(clippy::module_inception, clippy::bool_assert_comparison)]
 pub mod validate;